@@ -0,0 +1,148 @@
+# Placeholders posted in the question channels and in #getting-started
+
+questions-welcome-title = **Willkommen in der Gitpod-Community!**
+questions-welcome-community = Die Community ist das Herz von Gitpod, wir helfen dir gerne weiter 🧡
+questions-welcome-docs = Bevor du **eine Frage stellst**, schau bitte in unsere Dokumentation oder unsere Screencasts.
+questions-welcome-status = Falls Gitpod nicht funktioniert, wirf bitte einen Blick auf unsere Statusseite. Danke!
+questions-ask-button = Frage stellen
+questions-ask-private-button = Privat fragen
+questions-docs-button = Doku
+questions-youtube-button = YouTube
+questions-status-button = Status
+questions-use-ask-button = ☝️ Bitte klicke auf **`💡 Frage stellen`**, um deine Frage abzuschließen
+
+getting-started-placeholder = **Drück den Button unten** 👇, um Zugang zum Server zu bekommen
+getting-started-button = Los geht's
+
+# Question form and the thread created from it
+
+question-form-title = Vorlage
+question-form-title-label = Titel
+question-form-description-label = Beschreibung
+question-form-workspace-label = Betroffener Workspace
+question-form-config-yaml-label = Inhalt deiner config.yaml
+question-form-example-repo-label = Beispiel-Repository
+question-form-kubectl-label = Ergebnis von `kubectl get pods -n <namespace>`
+
+question-description-heading = Beschreibung
+question-workspace-field = Betroffener Workspace
+question-example-repo-field = Beispiel-Repository
+question-config-yaml-heading = Inhalt der config.yaml
+question-kubectl-heading = Ergebnis von kubectl
+question-thread-welcome = Hey { $user }! Danke für deine Frage — bitte hab etwas Geduld, jemand aus der Community hilft dir bestimmt weiter. Du kannst in diesem Thread gerne weitere Informationen ergänzen!
+question-close-button = Schließen
+question-relevant-links = { $user } Ich habe auch ein paar Links gefunden, die deine Frage beantworten könnten, schau sie dir bitte an 🙏:
+question-open-link = Link öffnen
+private-ticket-welcome = Hey { $user }! Diesen Thread sehen nur du und { $role }, hier kannst du also auch interne Details teilen. Gleich kümmert sich jemand um dich!
+private-ticket-opened = Deine private Frage ist in { $thread } geöffnet.
+private-ticket-unavailable = Private Fragen sind hier noch nicht eingerichtet, bitte frag stattdessen im Kanal.
+private-ticket-failed = Der private Thread konnte nicht geöffnet werden, bitte versuch es später noch einmal.
+
+# Closing threads, thread_update relies on these to tell a closed thread apart
+
+question-closed = Diese Frage wurde geschlossen
+thread-closed = Dieser Thread wurde geschlossen
+question-closed-by = Diese Frage wurde von { $user } geschlossen
+thread-closed-by = Dieser Thread wurde von { $user } geschlossen
+
+# Onboarding
+
+onboarding-step-channels = Zu welchen weiteren Kanälen möchtest du Zugang haben?
+onboarding-channels-placeholder = Kanäle auswählen (optional)
+onboarding-channels-skip = [Überspringen] Ich brauche keine!
+onboarding-channels-skip-description = Nein danke, mehr brauche ich nicht.
+onboarding-step-subscribe = Möchtest du über Ankündigungen und Community-Events benachrichtigt werden?
+onboarding-subscribe-yes = Ja!
+onboarding-subscribe-no = Nein, danke!
+onboarding-step-reason = Warum bist du unserer Community beigetreten?
+onboarding-step-reason-hint = Damit kann ich dich zu den passenden Kanälen führen.
+onboarding-reason-hangout = Um mit anderen abzuhängen
+onboarding-reason-gitpodio-help = Um Hilfe mit Gitpod.io zu bekommen
+onboarding-reason-selfhosted-help = Um Hilfe mit meiner selbst gehosteten Installation zu bekommen
+onboarding-step-found = Wie hast du Gitpod gefunden?
+onboarding-found-placeholder = [Umfrage]: Quellen auswählen (optional)
+onboarding-found-skip = [Überspringen] Möchte ich nicht sagen
+onboarding-open-form = Formular ausfüllen
+onboarding-form-title = Erzähl uns von dir
+onboarding-intro-unlock = Danke { $user }! Um den Server freizuschalten, schau in { $channel } vorbei :wave:
+onboarding-intro-ask = Wir würden dich gerne besser kennenlernen und hören:
+onboarding-intro-working-on = 🔧 woran du gerade arbeitest!
+onboarding-intro-blockers = 🛑 was dich in deinem Entwickleralltag am meisten ausbremst
+onboarding-intro-feature = 🌈 deine liebste Gitpod-Funktion
+onboarding-intro-emoji = ✨ dein liebstes Emoji
+onboarding-profile-updated = Super, dein Serverprofil wird jetzt aktualisiert!
+intro-reminder = Hey { $user }! Du bist fast in **{ $guild }**, stell dich in { $channel } vor, um den Rest des Servers freizuschalten 👋
+onboarding-paused = Wir lassen gerade keine neuen Mitglieder herein, bitte versuch es später noch einmal 🙏
+mod-notice-warn = Du hast in **{ $guild }** eine Verwarnung erhalten: { $reason }
+mod-notice-timeout = Du wurdest in **{ $guild }** für { $duration } stummgeschaltet: { $reason }
+mod-notice-kick = Du wurdest aus **{ $guild }** geworfen: { $reason }
+mod-notice-ban = Du wurdest aus **{ $guild }** gebannt: { $reason }
+mod-notice-ban-temporary = Du wurdest aus **{ $guild }** für { $duration } gebannt: { $reason }
+mod-notice-ban-failed = Bitte ignoriere die vorherige Nachricht, der Bann aus **{ $guild }** ist nicht durchgegangen.
+screen-removed = Du wurdest aus **{ $guild }** entfernt: { $reason }
+report-form-title = Nachricht melden
+report-form-reason-label = Was stimmt mit dieser Nachricht nicht?
+report-own-message = Du kannst deine eigene Nachricht nicht melden.
+report-already = Du hast diese Nachricht bereits gemeldet, die Moderatoren kümmern sich darum.
+report-unavailable = Meldungen sind hier noch nicht eingerichtet, bitte wende dich direkt an einen Moderator.
+report-message-gone = Diese Nachricht gibt es nicht mehr.
+report-sent = Danke für die Meldung! Die Moderatoren sehen sie sich an und du bekommst eine DM, sobald sie es getan haben.
+report-resolved = Die Moderatoren von **{ $guild }** haben wegen der von dir gemeldeten Nachricht etwas unternommen. Danke, dass du aufpasst!
+report-dismissed = Die Moderatoren von **{ $guild }** haben sich die von dir gemeldete Nachricht angesehen und entschieden, dass nichts zu tun ist. Danke, dass du aufpasst!
+modmail-opened = Deine Nachricht wurde an das Team von **{ $guild }** weitergeleitet. Die Antworten kommen hier an, und alles, was du hier noch schreibst, geht auch an das Team.
+modmail-blocked = Du kannst das Team von **{ $guild }** nicht über den Bot kontaktieren.
+modmail-closed = Das Team von **{ $guild }** hat diese Unterhaltung geschlossen. Schreib einfach wieder, wenn du noch etwas brauchst.
+modmail-reply-named = **{ $name }** von **{ $guild }**:
+modmail-reply-anonymous = **Team von { $guild }**:
+onboarding-busy = Einen Moment, deine vorherige Antwort wird noch gespeichert.
+onboarding-expired = Diese Onboarding-Sitzung ist abgelaufen, drück in { $channel } auf **Los geht's**, um neu zu beginnen.
+
+onboarding-role-jetbrains = JetBrains (BETA)
+onboarding-role-jetbrains-description = Diskutiere über JetBrains-IDEs für Gitpod!
+onboarding-role-devx = Developer Experience
+onboarding-role-devx-description = Alles rund um DevX
+onboarding-role-selfhosted = Self-Hosted Gitpod
+onboarding-role-selfhosted-description = Du hostest Gitpod selbst? Dann brauchst du das!
+onboarding-role-mobile = Mobilgeräte und Tablets
+onboarding-role-mobile-description = Sprich über die Nutzung von Gitpod auf Mobilgeräten
+onboarding-role-language-description = Diskussionen
+
+onboarding-found-friend = Freund oder Kollege
+onboarding-found-friend-description = Ein Freund oder Kollege hat mir Gitpod gezeigt
+onboarding-found-google = Google
+onboarding-found-google-description = Ich habe Gitpod über eine Google-Suche gefunden
+onboarding-found-youtube = YouTube
+onboarding-found-youtube-description = Ich habe Gitpod in einem YouTube-Video gesehen
+onboarding-found-twitter = Twitter
+onboarding-found-twitter-description = Ich habe in einem Tweet von Gitpod gelesen
+onboarding-found-gitrepo = Git-Repository
+onboarding-found-gitrepo-description = Ich habe Gitpod in einem Git-Repository gefunden
+
+# Role menu
+
+roles-menu-heading = **Wähle die Rollen, die du möchtest** 👇 eine Rolle, die du schon hast, wird entfernt
+roles-added = Hinzugefügt: { $roles }
+roles-removed = Entfernt: { $roles }
+roles-current = Deine Rollen: { $roles }
+
+# Welcome thread on introductions
+
+intro-thread-name = Willkommen { $name }!
+intro-welcome = Willkommen in der Gitpod-Community { $user } 🙌
+intro-gitpodio-help = **Du hast erwähnt**, dass du Hilfe mit Gitpod.io brauchst, bitte frag in { $channel }
+intro-selfhosted-help = **Du hast erwähnt**, dass du Hilfe mit Self-Hosted brauchst, bitte frag in { $channel }
+intro-channels-heading = Hier sind ein paar Kanäle, die du dir ansehen solltest:
+intro-channel-general = • { $channel } - für Technik, Programmierung und alles, was dazugehört 🖥
+intro-channel-offtopic = • { $channel } - für alles Mögliche ☕️
+intro-channel-questions = • { $channel } - eine Frage zu Gitpod? Hier bist du richtig! ❓
+intro-explore = …und es gibt noch mehr! Nimm dir Zeit, dich umzusehen :)
+intro-learn-more = Auf diesen Seiten erfährst du mehr über Gitpod:
+
+# Announcements, the defaults of `/welcome`
+
+announce-join = > :arrow_forward: **{ $tag }** ist da (Kontoalter: **{ $account_age }**, Mitglied #{ $member_count }, Einladung: { $invite })
+announce-leave = > :arrow_forward: **{ $tag }** (**{ $id }**) ist nicht mehr da <a:duckdance:835457840365568012>, so ist das Leben...
+
+# Misc
+
+message-posted = Nachricht in diesem Kanal gepostet
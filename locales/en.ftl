@@ -0,0 +1,148 @@
+# Placeholders posted in the question channels and in #getting-started
+
+questions-welcome-title = **Welcome to the Gitpod community!**
+questions-welcome-community = Community is at the heart of Gitpod, we’re happy to help you out 🧡
+questions-welcome-docs = Before **asking a question**, remember to check out our documentation or watch our screencasts.
+questions-welcome-status = If you think Gitpod is not working, please check our status page. Thank you!
+questions-ask-button = Ask a question
+questions-ask-private-button = Ask privately
+questions-docs-button = Docs
+questions-youtube-button = YouTube
+questions-status-button = Status
+questions-use-ask-button = ☝️ Please click on **`💡 Ask a Question`** button to complete your question
+
+getting-started-placeholder = **Press the button below** 👇 to gain access to the server
+getting-started-button = Let's go
+
+# Question form and the thread created from it
+
+question-form-title = Template
+question-form-title-label = Title
+question-form-description-label = Description
+question-form-workspace-label = Workspace affected
+question-form-config-yaml-label = Your config.yaml contents
+question-form-example-repo-label = Example repo
+question-form-kubectl-label = Result of `kubectl get pods -n <namespace>`
+
+question-description-heading = Description
+question-workspace-field = Workspace affected
+question-example-repo-field = Example Repository
+question-config-yaml-heading = config.yaml contents
+question-kubectl-heading = Result of kubectl
+question-thread-welcome = Hey { $user }! Thank you for raising this — please hang tight as someone from our community may help you out. Meanwhile, feel free to add anymore information in this thread!
+question-close-button = Close
+question-relevant-links = { $user } I also found some relevant links which might answer your question, please do check them out below 🙏:
+question-open-link = Open link
+private-ticket-welcome = Hey { $user }! This thread is only visible to you and { $role }, so it's safe to share internal details here. Someone will be with you shortly!
+private-ticket-opened = Your private question is open in { $thread }.
+private-ticket-unavailable = Private questions aren't set up here yet, please ask in the channel instead.
+private-ticket-failed = Couldn't open a private thread, please try again later.
+
+# Closing threads, thread_update relies on these to tell a closed thread apart
+
+question-closed = This question was closed
+thread-closed = This thread was closed
+question-closed-by = This question was closed by { $user }
+thread-closed-by = This thread was closed by { $user }
+
+# Onboarding
+
+onboarding-step-channels = Which additional channels would you like to have access to?
+onboarding-channels-placeholder = Select channels (Optional)
+onboarding-channels-skip = [Skip] I don't want any!
+onboarding-channels-skip-description = Nopes, I ain't need more.
+onboarding-step-subscribe = Would you like to get notified for announcements and community events?
+onboarding-subscribe-yes = Yes!
+onboarding-subscribe-no = No, thank you!
+onboarding-step-reason = Why did you join our community?
+onboarding-step-reason-hint = I will point you to the correct channels with this info.
+onboarding-reason-hangout = To hangout with others
+onboarding-reason-gitpodio-help = To get help with Gitpod.io
+onboarding-reason-selfhosted-help = To get help with my selfhosted installation
+onboarding-step-found = How did you find Gitpod?
+onboarding-found-placeholder = [Poll]: Select sources (Optional)
+onboarding-found-skip = [Skip] Prefer not to share
+onboarding-open-form = Fill in the form
+onboarding-form-title = Tell us about yourself
+onboarding-intro-unlock = Thank you { $user }! To unlock the server, drop by { $channel } :wave:
+onboarding-intro-ask = We’d love to get to know you better and hear about:
+onboarding-intro-working-on = 🔧 what you’re working on!
+onboarding-intro-blockers = 🛑 what blocks you most in your daily dev workflow
+onboarding-intro-feature = 🌈 your favourite Gitpod feature
+onboarding-intro-emoji = ✨ your favourite emoji
+onboarding-profile-updated = Awesome, your server profile will be updated now!
+intro-reminder = Hey { $user }! You're almost in **{ $guild }**, introduce yourself in { $channel } to unlock the rest of the server 👋
+onboarding-paused = We're not letting new members in right now, please try again later 🙏
+mod-notice-warn = You received a warning in **{ $guild }**: { $reason }
+mod-notice-timeout = You were timed out in **{ $guild }** for { $duration }: { $reason }
+mod-notice-kick = You were kicked from **{ $guild }**: { $reason }
+mod-notice-ban = You were banned from **{ $guild }**: { $reason }
+mod-notice-ban-temporary = You were banned from **{ $guild }** for { $duration }: { $reason }
+mod-notice-ban-failed = Please disregard the previous message, the ban from **{ $guild }** didn't go through.
+screen-removed = You were removed from **{ $guild }**: { $reason }
+report-form-title = Report message
+report-form-reason-label = What's wrong with this message?
+report-own-message = You can't report your own message.
+report-already = You reported this message already, the moderators are on it.
+report-unavailable = Reports aren't set up here yet, please reach out to a moderator directly.
+report-message-gone = That message is gone already.
+report-sent = Thanks for the report! The moderators will look into it and you'll get a DM when they do.
+report-resolved = The moderators of **{ $guild }** took action on the message you reported. Thanks for looking out!
+report-dismissed = The moderators of **{ $guild }** reviewed the message you reported and decided no action was needed. Thanks for looking out!
+modmail-opened = Your message was passed on to the staff of **{ $guild }**. Their answers will arrive here, and anything else you send here goes to them too.
+modmail-blocked = You can't contact the staff of **{ $guild }** through the bot.
+modmail-closed = The staff of **{ $guild }** closed this conversation. Send another message if you need anything else.
+modmail-reply-named = **{ $name }** from **{ $guild }**:
+modmail-reply-anonymous = **Staff of { $guild }**:
+onboarding-busy = Hold on, your previous answer is still being saved.
+onboarding-expired = This onboarding session has expired, press **Let's go** in { $channel } to start again.
+
+onboarding-role-jetbrains = JetBrains (BETA)
+onboarding-role-jetbrains-description = Discuss about Jetbrains IDEs for Gitpod!
+onboarding-role-devx = Developer Experience
+onboarding-role-devx-description = All things about DevX
+onboarding-role-selfhosted = Self Hosted Gitpod
+onboarding-role-selfhosted-description = Do you selfhost Gitpod? Then you need this!
+onboarding-role-mobile = Mobile and tablets
+onboarding-role-mobile-description = Talk about using Gitpod on mobile devices
+onboarding-role-language-description = Discussions
+
+onboarding-found-friend = Friend or colleague
+onboarding-found-friend-description = A friend or colleague of mine introduced Gitpod to me
+onboarding-found-google = Google
+onboarding-found-google-description = I found Gitpod from a Google search
+onboarding-found-youtube = YouTube
+onboarding-found-youtube-description = Saw Gitpod on a Youtube Video
+onboarding-found-twitter = Twitter
+onboarding-found-twitter-description = Saw people talking about Gitpod on a Tweet
+onboarding-found-gitrepo = Git Repository
+onboarding-found-gitrepo-description = Found Gitpod on a Git repository
+
+# Role menu
+
+roles-menu-heading = **Pick the roles you want** 👇 picking a role you already have removes it
+roles-added = Added: { $roles }
+roles-removed = Removed: { $roles }
+roles-current = Your roles: { $roles }
+
+# Welcome thread on introductions
+
+intro-thread-name = Welcome { $name }!
+intro-welcome = Welcome to the Gitpod community { $user } 🙌
+intro-gitpodio-help = **You mentioned that** you need help with Gitpod.io, please ask in { $channel }
+intro-selfhosted-help = **You mentioned that** you need help with selfhosted, please ask in { $channel }
+intro-channels-heading = Here are some channels that you should check out:
+intro-channel-general = • { $channel } - for tech, programming and anything related 🖥
+intro-channel-offtopic = • { $channel } - for any random discussions ☕️
+intro-channel-questions = • { $channel } - have a question about Gitpod? this is the place to ask! ❓
+intro-explore = …And there’s more! Take your time to explore :)
+intro-learn-more = Feel free to check out the following pages to learn more about Gitpod:
+
+# Announcements, the defaults of `/welcome`
+
+announce-join = > :arrow_forward: **{ $tag }** came in (account age: **{ $account_age }**, member #{ $member_count }, invite: { $invite })
+announce-leave = > :arrow_forward: **{ $tag }** (**{ $id }**) is no more <a:duckdance:835457840365568012>, sed lyf...
+
+# Misc
+
+message-posted = Posted message on this channel
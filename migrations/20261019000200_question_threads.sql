@@ -0,0 +1,10 @@
+CREATE TABLE IF NOT EXISTS question_threads (
+	thread_id INTEGER PRIMARY KEY,
+	guild_id INTEGER NOT NULL,
+	channel_id INTEGER NOT NULL,
+	user_id INTEGER NOT NULL,
+	created_at INTEGER NOT NULL,
+	first_response_at INTEGER,
+	first_staff_response_at INTEGER,
+	closed_at INTEGER
+);
@@ -0,0 +1,21 @@
+CREATE TABLE IF NOT EXISTS oncall_rotation (
+	guild_id INTEGER NOT NULL,
+	position INTEGER NOT NULL,
+	user_id INTEGER NOT NULL,
+	PRIMARY KEY (guild_id, position)
+);
+
+CREATE TABLE IF NOT EXISTS oncall_overrides (
+	guild_id INTEGER NOT NULL,
+	week_start INTEGER NOT NULL,
+	user_id INTEGER NOT NULL,
+	PRIMARY KEY (guild_id, week_start)
+);
+
+CREATE TABLE IF NOT EXISTS escalations (
+	thread_id INTEGER PRIMARY KEY,
+	guild_id INTEGER NOT NULL,
+	channel_id INTEGER NOT NULL,
+	escalated_at INTEGER NOT NULL,
+	oncall_user_id INTEGER
+);
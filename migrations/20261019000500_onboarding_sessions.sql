@@ -0,0 +1,8 @@
+CREATE TABLE IF NOT EXISTS onboarding_sessions (
+	guild_id INTEGER NOT NULL,
+	user_id INTEGER NOT NULL,
+	answers TEXT NOT NULL,
+	awaiting_intro BOOLEAN NOT NULL DEFAULT 0,
+	updated_at INTEGER NOT NULL,
+	PRIMARY KEY (guild_id, user_id)
+);
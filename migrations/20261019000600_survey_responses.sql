@@ -0,0 +1,9 @@
+CREATE TABLE IF NOT EXISTS survey_responses (
+	guild_id INTEGER NOT NULL,
+	user_id INTEGER NOT NULL,
+	question TEXT NOT NULL,
+	answer TEXT NOT NULL,
+	answered_at INTEGER NOT NULL,
+	PRIMARY KEY (guild_id, user_id, question, answer)
+);
+CREATE INDEX IF NOT EXISTS survey_responses_answered_at ON survey_responses (guild_id, answered_at);
@@ -0,0 +1,6 @@
+CREATE TABLE IF NOT EXISTS onboarding_completions (
+	guild_id INTEGER NOT NULL,
+	user_id INTEGER NOT NULL,
+	completed_at INTEGER NOT NULL,
+	PRIMARY KEY (guild_id, user_id)
+);
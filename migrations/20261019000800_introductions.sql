@@ -0,0 +1,9 @@
+ALTER TABLE onboarding_sessions ADD COLUMN reminded_at INTEGER;
+
+CREATE TABLE IF NOT EXISTS introductions (
+	guild_id INTEGER NOT NULL,
+	user_id INTEGER NOT NULL,
+	message_id INTEGER NOT NULL,
+	introduced_at INTEGER NOT NULL,
+	PRIMARY KEY (guild_id, user_id)
+);
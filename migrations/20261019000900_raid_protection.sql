@@ -0,0 +1,15 @@
+CREATE TABLE IF NOT EXISTS raid_lockdowns (
+	guild_id INTEGER PRIMARY KEY,
+	started_at INTEGER NOT NULL,
+	verification_level INTEGER,
+	slowmodes TEXT NOT NULL,
+	alert_channel_id INTEGER,
+	alert_message_id INTEGER
+);
+
+CREATE TABLE IF NOT EXISTS raid_flagged (
+	guild_id INTEGER NOT NULL,
+	user_id INTEGER NOT NULL,
+	joined_at INTEGER NOT NULL,
+	PRIMARY KEY (guild_id, user_id)
+);
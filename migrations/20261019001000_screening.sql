@@ -0,0 +1,24 @@
+CREATE TABLE IF NOT EXISTS screening_rules (
+	id INTEGER PRIMARY KEY AUTOINCREMENT,
+	guild_id INTEGER NOT NULL,
+	kind TEXT NOT NULL,
+	pattern TEXT NOT NULL,
+	action TEXT NOT NULL,
+	reason TEXT NOT NULL,
+	created_by INTEGER NOT NULL,
+	created_at INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS screening_hits (
+	id INTEGER PRIMARY KEY AUTOINCREMENT,
+	guild_id INTEGER NOT NULL,
+	rule_id INTEGER NOT NULL,
+	user_id INTEGER NOT NULL,
+	name TEXT NOT NULL,
+	pattern TEXT NOT NULL,
+	action TEXT NOT NULL,
+	trigger TEXT NOT NULL,
+	hit_at INTEGER NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS screening_hits_user ON screening_hits (guild_id, user_id);
@@ -0,0 +1,12 @@
+CREATE TABLE IF NOT EXISTS name_history (
+	id INTEGER PRIMARY KEY AUTOINCREMENT,
+	user_id INTEGER NOT NULL,
+	-- Unset for names that aren't per guild
+	guild_id INTEGER,
+	kind TEXT NOT NULL,
+	-- Unset when a nickname was removed
+	name TEXT,
+	recorded_at INTEGER NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS name_history_user ON name_history (user_id, kind);
@@ -0,0 +1,23 @@
+CREATE TABLE IF NOT EXISTS mod_cases (
+	id INTEGER PRIMARY KEY AUTOINCREMENT,
+	guild_id INTEGER NOT NULL,
+	-- Numbered per guild, starting at 1
+	case_number INTEGER NOT NULL,
+	user_id INTEGER NOT NULL,
+	moderator_id INTEGER NOT NULL,
+	action TEXT NOT NULL,
+	reason TEXT NOT NULL,
+	duration_secs INTEGER,
+	expires_at INTEGER,
+	-- Set once a temporary action ended or was lifted
+	expired_at INTEGER,
+	-- Message links, one per line
+	evidence TEXT NOT NULL,
+	created_at INTEGER NOT NULL,
+	log_channel_id INTEGER,
+	log_message_id INTEGER,
+	UNIQUE (guild_id, case_number)
+);
+
+CREATE INDEX IF NOT EXISTS mod_cases_user ON mod_cases (guild_id, user_id);
+CREATE INDEX IF NOT EXISTS mod_cases_expires_at ON mod_cases (expires_at);
@@ -0,0 +1,5 @@
+CREATE TABLE IF NOT EXISTS scam_domains (
+	domain TEXT PRIMARY KEY NOT NULL,
+	added_by INTEGER NOT NULL,
+	added_at INTEGER NOT NULL
+);
@@ -0,0 +1,5 @@
+CREATE TABLE IF NOT EXISTS automod_rules (
+	guild_id INTEGER PRIMARY KEY,
+	definition TEXT NOT NULL,
+	updated_at INTEGER NOT NULL
+);
@@ -0,0 +1,21 @@
+CREATE TABLE IF NOT EXISTS message_reports (
+	id INTEGER PRIMARY KEY AUTOINCREMENT,
+	guild_id INTEGER NOT NULL,
+	reporter_id INTEGER NOT NULL,
+	reporter_locale TEXT NOT NULL,
+	author_id INTEGER NOT NULL,
+	channel_id INTEGER NOT NULL,
+	message_id INTEGER NOT NULL,
+	content TEXT NOT NULL,
+	attachments TEXT NOT NULL,
+	reason TEXT NOT NULL,
+	status TEXT NOT NULL DEFAULT 'open',
+	claimed_by INTEGER,
+	handled_by INTEGER,
+	handled_at INTEGER,
+	case_number INTEGER,
+	queue_channel_id INTEGER,
+	queue_message_id INTEGER,
+	created_at INTEGER NOT NULL
+);
+CREATE INDEX IF NOT EXISTS message_reports_message ON message_reports (guild_id, message_id);
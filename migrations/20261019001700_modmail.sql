@@ -0,0 +1,41 @@
+CREATE TABLE IF NOT EXISTS modmail_threads (
+	id INTEGER PRIMARY KEY AUTOINCREMENT,
+	guild_id INTEGER NOT NULL,
+	user_id INTEGER NOT NULL,
+	thread_id INTEGER NOT NULL,
+	opened_at INTEGER NOT NULL,
+	closed_at INTEGER,
+	closed_by INTEGER,
+	transcript TEXT
+);
+CREATE INDEX IF NOT EXISTS modmail_threads_user ON modmail_threads (user_id, closed_at);
+CREATE INDEX IF NOT EXISTS modmail_threads_thread ON modmail_threads (thread_id);
+
+CREATE TABLE IF NOT EXISTS modmail_messages (
+	id INTEGER PRIMARY KEY AUTOINCREMENT,
+	modmail_id INTEGER NOT NULL REFERENCES modmail_threads (id),
+	author_id INTEGER NOT NULL,
+	author_name TEXT NOT NULL,
+	from_staff BOOLEAN NOT NULL,
+	anonymous BOOLEAN NOT NULL,
+	content TEXT NOT NULL,
+	attachments TEXT NOT NULL,
+	sent_at INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS modmail_blocks (
+	guild_id INTEGER NOT NULL,
+	user_id INTEGER NOT NULL,
+	blocked_by INTEGER NOT NULL,
+	reason TEXT NOT NULL,
+	blocked_at INTEGER NOT NULL,
+	PRIMARY KEY (guild_id, user_id)
+);
+
+CREATE TABLE IF NOT EXISTS modmail_snippets (
+	guild_id INTEGER NOT NULL,
+	name TEXT NOT NULL,
+	content TEXT NOT NULL,
+	created_by INTEGER NOT NULL,
+	PRIMARY KEY (guild_id, name)
+);
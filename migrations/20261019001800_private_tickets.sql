@@ -0,0 +1,9 @@
+CREATE TABLE IF NOT EXISTS private_tickets (
+	thread_id INTEGER PRIMARY KEY,
+	guild_id INTEGER NOT NULL,
+	channel_id INTEGER NOT NULL,
+	user_id INTEGER NOT NULL,
+	created_at INTEGER NOT NULL,
+	closed_at INTEGER
+);
+CREATE INDEX IF NOT EXISTS private_tickets_user ON private_tickets (guild_id, user_id);
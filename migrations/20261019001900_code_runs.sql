@@ -0,0 +1,6 @@
+CREATE TABLE IF NOT EXISTS code_runs (
+	message_id INTEGER PRIMARY KEY,
+	channel_id INTEGER NOT NULL,
+	result_message_id INTEGER NOT NULL,
+	created_at INTEGER NOT NULL
+);
@@ -2,7 +2,14 @@ use super::*;
 
 #[command]
 pub async fn av(_ctx: &Context, _msg: &Message, mut _args: Args) -> CommandResult {
-    let user = Parse::user(_ctx, _msg, &_args).await;
+    let user = match Parse::user(_ctx, _msg, &_args).await {
+        Some(user_id) => user_id.0,
+        None => {
+            _msg.reply(&_ctx.http, format!("No user found for `{}`", _args.rest()))
+                .await?;
+            return Ok(());
+        }
+    };
     let guild_id = &_msg.guild_id.unwrap();
     let user_data = &_ctx
         .http
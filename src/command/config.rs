@@ -1,5 +1,7 @@
 use crate::event::questions_thread;
-use serenity::model::id::ChannelId;
+use crate::i18n;
+use serenity::model::id::{ChannelId, GuildId, RoleId};
+use std::str::FromStr;
 
 use super::*;
 
@@ -34,6 +36,67 @@ impl Db {
 
         Ok(q)
     }
+
+    pub async fn set_guild_config(&self, guild_id: GuildId, key: &str, value: &str) -> Result<()> {
+        let guild_id = guild_id.0 as i64;
+        sqlx::query!(
+            "insert into guild_config (guild_id, key, value) values (?1, ?2, ?3) on conflict(guild_id, key) do update set value=?3",
+            guild_id,
+            key,
+            value
+        )
+        .execute(&self.sqlitedb)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn get_guild_config(&self, guild_id: GuildId, key: &str) -> Result<Option<String>> {
+        let guild_id = guild_id.0 as i64;
+        let value = sqlx::query!(
+            "select value from guild_config where guild_id=? and key=?",
+            guild_id,
+            key
+        )
+        .fetch_optional(&self.sqlitedb)
+        .await?
+        .map(|x| x.value);
+        Ok(value)
+    }
+
+    pub async fn remove_guild_config(&self, guild_id: GuildId, key: &str) -> Result<()> {
+        let guild_id = guild_id.0 as i64;
+        sqlx::query!(
+            "delete from guild_config where guild_id=? and key=?",
+            guild_id,
+            key
+        )
+        .execute(&self.sqlitedb)
+        .await?;
+        Ok(())
+    }
+
+    /// All guilds that have `key` set, along with its value.
+    pub async fn get_guild_configs(&self, key: &str) -> Result<Vec<(GuildId, String)>> {
+        let q = sqlx::query!("select guild_id, value from guild_config where key=?", key)
+            .fetch_all(&self.sqlitedb)
+            .await?
+            .into_iter()
+            .map(|x| (GuildId(x.guild_id as u64), x.value))
+            .collect();
+        Ok(q)
+    }
+
+    /// Same as `get_guild_config`, but parsed into `T`. Unparsable values are treated as unset.
+    pub async fn get_guild_config_as<T: FromStr>(
+        &self,
+        guild_id: GuildId,
+        key: &str,
+    ) -> Result<Option<T>> {
+        Ok(self
+            .get_guild_config(guild_id, key)
+            .await?
+            .and_then(|x| x.parse::<T>().ok()))
+    }
 }
 
 // A command can have sub-commands, just like in command lines tools.
@@ -46,6 +109,7 @@ impl Db {
 //     getting_started,
 //     default_role
 // )]
+#[only_in(guilds)]
 #[required_permissions(ADMINISTRATOR)]
 async fn config(ctx: &Context, msg: &Message, _args: Args) -> CommandResult {
     if _args.is_empty() {
@@ -66,6 +130,116 @@ async fn config(ctx: &Context, msg: &Message, _args: Args) -> CommandResult {
                 db.set_watch_channels(config.as_str(), value.parse::<ChannelId>().unwrap(), ctx)
                     .await?
             }
+            "staff_role" | "helper_role" | "quarantine_role" | "support_role" => {
+                let role_id = value.parse::<RoleId>()?;
+                db.set_guild_config(msg.guild_id.unwrap(), config.as_str(), &role_id.to_string())
+                    .await?;
+                msg.react(&ctx.http, '✅').await?;
+            }
+            "log_channel" | "mod_log_channel" | "report_channel" | "modmail_channel" => {
+                let channel_id = value.parse::<ChannelId>()?;
+                db.set_guild_config(
+                    msg.guild_id.unwrap(),
+                    config.as_str(),
+                    &channel_id.to_string(),
+                )
+                .await?;
+                msg.react(&ctx.http, '✅').await?;
+            }
+            // Comma separated role ids, or `none`
+            "restore_excluded_roles" => {
+                let roles = if value == "none" {
+                    vec![]
+                } else {
+                    value
+                        .split(',')
+                        .map(|x| x.trim().parse::<RoleId>())
+                        .collect::<Result<Vec<_>, _>>()?
+                };
+                db.set_guild_config(
+                    msg.guild_id.unwrap(),
+                    config.as_str(),
+                    &roles
+                        .iter()
+                        .map(|x| x.to_string())
+                        .collect::<Vec<_>>()
+                        .join(","),
+                )
+                .await?;
+                msg.react(&ctx.http, '✅').await?;
+            }
+            // Days, 0 disables them
+            "intro_reminder_days" | "intro_timeout_days" => {
+                let days = value.parse::<u32>()?;
+                db.set_guild_config(msg.guild_id.unwrap(), config.as_str(), &days.to_string())
+                    .await?;
+                msg.react(&ctx.http, '✅').await?;
+            }
+            "intro_timeout_action" => match value.as_str() {
+                "remove_temp" | "kick" => {
+                    db.set_guild_config(msg.guild_id.unwrap(), config.as_str(), &value)
+                        .await?;
+                    msg.react(&ctx.http, '✅').await?;
+                }
+                "none" => {
+                    db.remove_guild_config(msg.guild_id.unwrap(), config.as_str())
+                        .await?;
+                    msg.react(&ctx.http, '✅').await?;
+                }
+                _ => {
+                    msg.reply(
+                        &ctx.http,
+                        "Invalid action, use one of: remove_temp, kick, none",
+                    )
+                    .await?;
+                }
+            },
+            "escalation_minutes" => {
+                let minutes = value.parse::<u32>()?;
+                db.set_guild_config(msg.guild_id.unwrap(), config.as_str(), &minutes.to_string())
+                    .await?;
+                msg.react(&ctx.http, '✅').await?;
+            }
+            "raid_join_threshold"
+            | "raid_window_secs"
+            | "raid_new_account_days"
+            | "raid_new_account_ratio"
+            | "raid_slowmode_secs"
+            | "spam_channels"
+            | "spam_window_secs" => {
+                let number = value.parse::<u32>()?;
+                db.set_guild_config(msg.guild_id.unwrap(), config.as_str(), &number.to_string())
+                    .await?;
+                msg.react(&ctx.http, '✅').await?;
+            }
+            "escalation_dm"
+            | "raid_protection"
+            | "raid_raise_verification"
+            | "raid_pause_onboarding"
+            | "spam_protection" => {
+                let enabled = value.parse::<bool>()?;
+                db.set_guild_config(msg.guild_id.unwrap(), config.as_str(), &enabled.to_string())
+                    .await?;
+                msg.react(&ctx.http, '✅').await?;
+            }
+            "locale" => match i18n::resolve(&value) {
+                Some(locale) => {
+                    db.set_guild_config(msg.guild_id.unwrap(), config.as_str(), locale)
+                        .await?;
+                    msg.react(&ctx.http, '✅').await?;
+                }
+                None => {
+                    msg.reply(
+                        &ctx.http,
+                        format!(
+                            "Unsupported locale: {}, available: {}",
+                            value,
+                            i18n::available_locales().join(", ")
+                        ),
+                    )
+                    .await?;
+                }
+            },
             _ => {
                 msg.reply(
                     &ctx.http,
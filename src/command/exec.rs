@@ -1,61 +1,109 @@
 use super::*;
-use regex::Regex;
+use crate::utils::piston;
 use serenity::utils::MessageBuilder;
 
+// Attachments bigger than this aren't source files
+const MAX_ATTACHMENT_BYTES: u64 = 1024 * 1024;
+
 #[command]
 #[only_in(guilds)]
 #[aliases("sh")]
 async fn exec(ctx: &Context, _msg: &Message, mut _args: Args) -> CommandResult {
     let typing = _msg.channel_id.start_typing(&ctx.http)?;
     let args = &_args.rest();
-    if let Some(input) = Regex::new(r#"```(?P<lang>[a-z]+)(?s)(?P<code>\n.*)```"#)?.captures(args) {
-        let lang = input.name("lang").unwrap().as_str();
-        let code = input.name("code").unwrap().as_str();
-
-        let client = piston_rs::Client::new();
-        let executor = piston_rs::Executor::new()
-            .set_language(lang)
-            .set_version("*")
-            .add_file(piston_rs::File::default().set_content(code));
-
-        let mut final_msg = String::new();
-        match client.execute(&executor).await {
-            Ok(response) => {
-                if let Some(c) = response.compile {
-                    if c.code != Some(0) {
-                        final_msg.push_str(c.output.as_str());
-                    }
-                }
+    let blocks = piston::code_blocks(args);
+    // A block tagged `stdin` is the input, the first other one is the code
+    let stdin = blocks
+        .iter()
+        .find(|x| x.lang == "stdin")
+        .map(|x| x.code.clone())
+        .unwrap_or_default();
+    let code = blocks.iter().find(|x| x.lang != "stdin");
+    // Anything before the code is passed as command-line arguments
+    let cli_args: Vec<String> = args
+        .split("```")
+        .next()
+        .unwrap_or_default()
+        .split_whitespace()
+        .map(|x| x.to_string())
+        .collect();
 
-                if final_msg.is_empty() {
-                    final_msg.push_str(response.run.output.as_str());
-                }
-            }
-            Err(e) => {
-                final_msg.push_str(format!("Error: Something went wrong: {e}").as_str());
-            }
+    let mut files = Vec::new();
+    let mut lang = code.map(|x| x.lang.clone()).unwrap_or_default();
+    if let Some(code) = code {
+        files.push(piston_rs::File::default().set_content(&code.code));
+    }
+    for attachment in &_msg.attachments {
+        if attachment.size > MAX_ATTACHMENT_BYTES {
+            continue;
         }
-        if final_msg.is_empty() {
-            _msg.reply_ping(&ctx.http, "Error: No output received")
-                .await?;
-        } else {
-            _msg.channel_id
-                .send_message(&ctx.http, |m| {
-                    m.reference_message(_msg).embed(|e| {
-                        e.description(format!("```{}\n{}```", lang, final_msg.substring(0, 4070)))
-                    })
-                })
-                .await?;
+        let content = match String::from_utf8(attachment.download().await?) {
+            Ok(content) => content,
+            Err(_) => continue,
+        };
+        if lang.is_empty() {
+            lang = attachment
+                .filename
+                .rsplit_once('.')
+                .map(|(_, ext)| ext.to_string())
+                .unwrap_or_default();
         }
-    } else {
+        files.push(
+            piston_rs::File::default()
+                .set_name(&attachment.filename)
+                .set_content(&content),
+        );
+    }
+
+    if files.is_empty() || lang.is_empty() {
         let final_msg = MessageBuilder::new()
             .push_quote_line("Incorrect syntax, the correct syntax is:\n")
-            .push_line("gp exec")
+            .push_line("gp exec [args...]")
             .push_line("\\`\\`\\`<lang>")
             .push_line("		<code goes here>")
             .push_line("\\`\\`\\`")
+            .push_line("\\`\\`\\`stdin")
+            .push_line("		<optional input>")
+            .push_line("\\`\\`\\`")
+            .push_line("")
+            .push_line("More files can be attached, see `/runtimes` for the languages.")
             .build();
         _msg.reply_ping(&ctx.http, final_msg).await?;
+        typing.stop().unwrap();
+        return Ok(());
+    }
+
+    match piston::run(&lang, files, &stdin, &cli_args).await {
+        Ok(run) if run.output.is_empty() => {
+            _msg.reply_ping(&ctx.http, "Error: No output received")
+                .await?;
+        }
+        Ok(run) => {
+            let footer = format!("{} {}", run.language, run.version);
+            let color = if run.success { 0x2ecc71 } else { 0xe74c3c };
+            _msg.channel_id
+                .send_message(&ctx.http, |m| {
+                    m.reference_message(_msg);
+                    if run.output.chars().count() > piston::MAX_EMBED_OUTPUT {
+                        m.add_file((run.output.as_bytes(), "output.txt"));
+                        m.embed(|e| {
+                            e.description("The output is too long, it's attached as a file")
+                                .footer(|f| f.text(&footer))
+                                .color(color)
+                        })
+                    } else {
+                        m.embed(|e| {
+                            e.description(format!("```{}\n{}```", lang, run.output))
+                                .footer(|f| f.text(&footer))
+                                .color(color)
+                        })
+                    }
+                })
+                .await?;
+        }
+        Err(e) => {
+            _msg.reply_ping(&ctx.http, format!("Error: {}", e)).await?;
+        }
     }
     typing.stop().unwrap();
 
@@ -14,7 +14,7 @@ mod ping;
 mod say;
 mod some_long_command;
 mod status;
-// mod whois;
+mod whois;
 
 // Import commands
 use about::*;
@@ -30,7 +30,7 @@ use math::*;
 use owner_check::*;
 use say::*;
 use status::*;
-// use whois::*;
+use whois::*;
 mod exec;
 use exec::*;
 
@@ -89,7 +89,7 @@ impl TypeMapKey for CommandCounter {
     // bashget,
     // ping,
     latency,
-    // whois,
+    whois,
     av,
     status,
     invite,
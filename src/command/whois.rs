@@ -1,145 +1,229 @@
 use super::*;
+use serenity::model::{id::GuildId, Timestamp};
 
-#[command]
-#[aliases("whoami")]
-// #[sub_commands(fetch)]
-pub async fn whois(_ctx: &Context, _msg: &Message, mut _args: Args) -> CommandResult {
-    {
-        let typing = _ctx
-            .http
-            .start_typing(u64::try_from(_msg.channel_id).unwrap())
-            .unwrap();
-
-        let dbnode = Database::from("userid".to_string()).await;
-
-        let user = Parse::user(_ctx, _msg, &_args).await;
-
-        let guid = &_msg.guild_id.unwrap();
-
-        let prev_usernames = dbnode.get_user_info(&user.to_string()).await;
-
-        let user_data = &_ctx
-            .cache
-            .guild(guid)
-            .unwrap()
-            .member(&_ctx.http, user)
-            .await
-            .unwrap();
-
-        let user_date = &user_data.user.created_at().date().format("%a, %B %e, %Y");
-
-        let user_server_date = &user_data.joined_at.unwrap().date().format("%a, %B %e, %Y");
-
-        let user_time = &_msg.author.created_at().time().format("%H:%M:%S");
-
-        let user_avatar = &user_data.user.face();
-
-        let user_colors = {
-            let user = &user_data.colour(&_ctx.cache).await;
-            if user.is_some() {
-                user.unwrap().hex()
-            } else {
-                String::from("0000")
-            }
-        };
-
-        // let guild = &_msg.guild_id.unwrap();
-        // let messages = &_ctx.cache.guild_channels(guild).await.unwrap();
-
-        // let mut countmsg = 1;
-
-        // for (id, channel) in messages.iter() {
-        //     countmsg = countmsg + 1;
-        //     let chi = id.messages_iter(&_ctx.http).un;
-        // }
-
-        // let hmm = &_msg.channel(&_ctx.cache).await.unwrap().guild().unwrap().messages(http, builder)
+pub struct QuestionCounts {
+    pub asked: i64,
+    pub solved: i64,
+}
 
-        _msg.channel_id
-            .send_message(&_ctx.http, |m| {
-                m.embed(|e| {
-                    e.title("FreeBSD whois");
-                    // e.color()
-                    e.thumbnail(&user_avatar);
-                    e.url("https://www.freebsd.org/cgi/man.cgi?query=whois");
+pub struct SurveyAnswer {
+    pub question: String,
+    pub answer: String,
+}
 
-                    let intro = english_gen(1, 1);
+impl Db {
+    pub async fn get_question_counts(
+        &self,
+        guild_id: GuildId,
+        user_id: UserId,
+    ) -> Result<QuestionCounts> {
+        let guild_id = guild_id.0 as i64;
+        let user_id = user_id.0 as i64;
+        let q = sqlx::query_as!(
+            QuestionCounts,
+            r#"select count(*) as "asked!: i64", count(closed_at) as "solved!: i64" from question_threads where guild_id=? and user_id=?"#,
+            guild_id,
+            user_id
+        )
+        .fetch_one(&self.sqlitedb)
+        .await?;
+        Ok(q)
+    }
 
-                    e.fields(vec![
-                        (
-                            "Intoduction.exe",
-                            format!(
-                                "{} [{}](https://www.dictionary.com/browse/{})",
-                                vowel_gen(&intro),
-                                &intro,
-                                &intro
-                            ),
-                            true,
-                        ),
-                        (
-                            "Color.exe",
-                            format!(
-                                "[#{}](https://www.colorhexa.com/{}) (Heximal)",
-                                &user_colors, &user_colors
-                            ),
-                            true,
-                        ),
-                    ]);
+    pub async fn get_user_survey_answers(
+        &self,
+        guild_id: GuildId,
+        user_id: UserId,
+    ) -> Result<Vec<SurveyAnswer>> {
+        let guild_id = guild_id.0 as i64;
+        let user_id = user_id.0 as i64;
+        let q = sqlx::query_as!(
+            SurveyAnswer,
+            "select question, answer from survey_responses where guild_id=? and user_id=? order by question, answer",
+            guild_id,
+            user_id
+        )
+        .fetch_all(&self.sqlitedb)
+        .await?;
+        Ok(q)
+    }
 
-                    e.field("‎", "‎", false);
+    pub async fn get_onboarding_completed_at(
+        &self,
+        guild_id: GuildId,
+        user_id: UserId,
+    ) -> Result<Option<i64>> {
+        let guild_id = guild_id.0 as i64;
+        let user_id = user_id.0 as i64;
+        let q = sqlx::query!(
+            "select completed_at from onboarding_completions where guild_id=? and user_id=?",
+            guild_id,
+            user_id
+        )
+        .fetch_optional(&self.sqlitedb)
+        .await?;
+        Ok(q.map(|x| x.completed_at))
+    }
 
-                    e.fields(vec![
-                        ("JoinedAt.exe", format!("{}", &user_server_date), true),
-                        (
-                            "RegisteredAt.exe",
-                            format!("{} {}", &user_date, &user_time),
-                            true,
-                        ),
-                    ]);
+    pub async fn count_screening_hits(&self, guild_id: GuildId, user_id: UserId) -> Result<i64> {
+        let guild_id = guild_id.0 as i64;
+        let user_id = user_id.0 as i64;
+        let q = sqlx::query!(
+            r#"select count(*) as "count!: i64" from screening_hits where guild_id=? and user_id=?"#,
+            guild_id,
+            user_id
+        )
+        .fetch_one(&self.sqlitedb)
+        .await?;
+        Ok(q.count)
+    }
+}
 
-                    e.field(
-                        "Usernames.exe:",
-                        format!(
-                            "{}{}{}",
-                            "```\n",
-                            prev_usernames.as_str().substring(0, 1016),
-                            "```\n"
-                        ),
-                        false,
-                    );
+fn date(timestamp: i64) -> String {
+    format!("<t:{}:D> (<t:{}:R>)", timestamp, timestamp)
+}
 
-                    // e.image(&user_avatar);
+fn or_none(lines: Vec<String>) -> String {
+    if lines.is_empty() {
+        "-".to_string()
+    } else {
+        lines.join("\n").substring(0, 1024).to_string()
+    }
+}
 
-                    e
-                });
+#[command]
+#[aliases("whoami")]
+#[only_in(guilds)]
+#[description = "Profile of a member: dates, roles, onboarding, questions and past names"]
+pub async fn whois(_ctx: &Context, _msg: &Message, mut _args: Args) -> CommandResult {
+    let guild_id = _msg.guild_id.unwrap();
+
+    let user_id = match Parse::user(_ctx, _msg, &_args).await {
+        Some(user_id) => user_id,
+        None => {
+            _msg.reply(&_ctx.http, format!("No user found for `{}`", _args.rest()))
+                .await?;
+            return Ok(());
+        }
+    };
+    let user = match user_id.to_user(_ctx).await {
+        Ok(user) => user,
+        Err(_) => {
+            _msg.reply(&_ctx.http, format!("No user with the id {}", user_id))
+                .await?;
+            return Ok(());
+        }
+    };
+    let typing = _ctx.http.start_typing(_msg.channel_id.0)?;
+    let member = guild_id.member(_ctx, user_id).await.ok();
+    let is_moderator = match _msg.member(_ctx).await {
+        Ok(author) => author
+            .permissions(&_ctx.cache)
+            .is_ok_and(|x| x.kick_members() || x.moderate_members()),
+        Err(_) => false,
+    };
+
+    let db = _ctx.get_db().await;
+    let saved_roles = db
+        .get_user_roles(user_id)
+        .await?
+        .map(|x| x.roles)
+        .unwrap_or_default();
+    let completed_at = db.get_onboarding_completed_at(guild_id, user_id).await?;
+    let introduced = db.has_introduced(guild_id, user_id).await?;
+    let answers = db.get_user_survey_answers(guild_id, user_id).await?;
+    let questions = db.get_question_counts(guild_id, user_id).await?;
+    let names = db.get_name_history(user_id, guild_id, 0, 5).await?;
+
+    let mut onboarding = vec![match completed_at {
+        Some(at) => format!("Completed <t:{}:D>", at),
+        None => "Not completed".to_string(),
+    }];
+    onboarding.push(if introduced {
+        "Introduced themselves".to_string()
+    } else {
+        "No introduction".to_string()
+    });
+    for answer in &answers {
+        onboarding.push(format!("{}: {}", answer.question, answer.answer));
+    }
 
-                m
-            })
-            .await
-            .unwrap();
-        typing.stop();
+    let colour = member.as_ref().and_then(|x| x.colour(&_ctx.cache));
+    let joined_at = member
+        .as_ref()
+        .and_then(|x| x.joined_at)
+        .map_or_else(|| "Not a member".to_string(), |x| date(x.unix_timestamp()));
+    let mut fields = vec![
+        (
+            "Account created",
+            date(user.created_at().unix_timestamp()),
+            true,
+        ),
+        ("Joined", joined_at, true),
+        (
+            "Saved roles",
+            or_none(
+                saved_roles
+                    .iter()
+                    .map(|x| x.mention().to_string())
+                    .collect(),
+            ),
+            false,
+        ),
+        ("Onboarding", or_none(onboarding), false),
+        (
+            "Questions",
+            format!("{} asked, {} solved", questions.asked, questions.solved),
+            true,
+        ),
+    ];
+    if is_moderator {
+        let warnings = db.count_warnings(guild_id, user_id).await?;
+        let cases = db.get_user_mod_cases(guild_id, user_id).await?.len();
+        let hits = db.count_screening_hits(guild_id, user_id).await?;
+        fields.push((
+            "Moderation",
+            format!(
+                "{} warnings, {} cases, {} name screening hits",
+                warnings, cases, hits
+            ),
+            true,
+        ));
     }
+    fields.push((
+        "Names",
+        or_none(
+            names
+                .iter()
+                .map(|x| {
+                    format!(
+                        "<t:{}:d> {}: {}",
+                        x.recorded_at,
+                        x.kind,
+                        x.name.as_deref().unwrap_or("*removed*")
+                    )
+                })
+                .collect(),
+        ),
+        false,
+    ));
+
+    _msg.channel_id
+        .send_message(&_ctx.http, |m| {
+            m.embed(|e| {
+                e.title(user.tag())
+                    .description(format!("{} ({})", user.mention(), user.id))
+                    .thumbnail(user.face())
+                    .fields(fields)
+                    .footer(|f| f.text("Full name history with /names"))
+                    .timestamp(Timestamp::now());
+                if let Some(colour) = colour {
+                    e.colour(colour);
+                }
+                e
+            })
+        })
+        .await?;
+    typing.stop().unwrap();
     Ok(())
 }
-
-// #[command]
-// #[required_permissions(ADMINISTRATOR)]
-// pub async fn fetch(_ctx: &Context, _msg: &Message) -> CommandResult {
-//     {
-//         let typing = _ctx
-//             .http
-//             .start_typing(u64::try_from(_msg.channel_id).unwrap())
-//             .unwrap();
-//         let dbnode_userid = Database::from("userid".to_string()).await;
-//         let members = &_msg.guild(&_ctx.cache).await.unwrap().members;
-
-//         for (_user_id, _member) in members {
-//             dbnode_userid
-//                 .save_user_info(&_user_id, _member.user.tag())
-//                 .await;
-//         }
-
-//         typing.stop();
-//     }
-//     Ok(())
-// }
@@ -49,8 +49,8 @@ impl ClientContextExt for client::Context {
 }
 
 pub struct User {
-    userid: UserId,
-    roles: Vec<RoleId>,
+    pub userid: UserId,
+    pub roles: Vec<RoleId>,
 }
 
 impl Db {
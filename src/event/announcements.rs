@@ -0,0 +1,292 @@
+use super::*;
+use crate::utils::misc::format_duration;
+use serenity::model::{
+    application::interaction::{
+        application_command::{ApplicationCommandInteraction, CommandDataOptionValue},
+        MessageFlags,
+    },
+    Timestamp,
+};
+use serenity::utils::MessageBuilder;
+use std::{
+    collections::HashMap,
+    sync::{Mutex, OnceLock},
+};
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Announcement {
+    Join,
+    Leave,
+}
+
+impl Announcement {
+    fn name(self) -> &'static str {
+        match self {
+            Announcement::Join => "join",
+            Announcement::Leave => "leave",
+        }
+    }
+
+    fn key(self, setting: &str) -> String {
+        format!("announce_{}_{}", self.name(), setting)
+    }
+
+    fn default_template(self) -> &'static str {
+        match self {
+            Announcement::Join => "announce-join",
+            Announcement::Leave => "announce-leave",
+        }
+    }
+
+    // Leaving members were always announced, joins weren't
+    fn enabled_by_default(self) -> bool {
+        self == Announcement::Leave
+    }
+}
+
+/// Settings from `/welcome set`. Without a channel the guild's system channel is used.
+pub struct AnnouncementConfig {
+    pub enabled: bool,
+    pub channel: Option<ChannelId>,
+    pub template: String,
+}
+
+async fn get_config(ctx: &Context, guild_id: GuildId, kind: Announcement) -> AnnouncementConfig {
+    let db = ctx.get_db().await;
+    let enabled = db
+        .get_guild_config_as::<bool>(guild_id, &kind.key("enabled"))
+        .await
+        .ok()
+        .flatten()
+        .unwrap_or(kind.enabled_by_default());
+    let channel = db
+        .get_guild_config_as::<ChannelId>(guild_id, &kind.key("channel"))
+        .await
+        .ok()
+        .flatten()
+        .or_else(|| {
+            guild_id
+                .to_guild_cached(&ctx.cache)
+                .and_then(|x| x.system_channel_id)
+        });
+    let template = db
+        .get_guild_config(guild_id, &kind.key("template"))
+        .await
+        .ok()
+        .flatten()
+        .unwrap_or_else(|| kind.default_template().to_string());
+    AnnouncementConfig {
+        enabled,
+        channel,
+        template,
+    }
+}
+
+/// Fills in a template, which is either a message id from the locale catalogues or plain text.
+/// Both take the same placeholders, as `{ $name }` in the catalogues and `{name}` otherwise.
+fn render(locale: &str, template: &str, user: &User, member_count: u64, invite: &str) -> String {
+    let account_age =
+        format_duration(Timestamp::now().unix_timestamp() - user.created_at().unix_timestamp());
+    let args = [
+        ("tag", user.tag()),
+        ("mention", user.mention().to_string()),
+        ("id", user.id.to_string()),
+        ("account_age", account_age),
+        ("member_count", member_count.to_string()),
+        ("invite", invite.to_string()),
+    ];
+    let mut text = tr_args(locale, template, &args);
+    for (name, value) in &args {
+        text = text.replace(&format!("{{{}}}", name), value);
+    }
+    text
+}
+
+fn member_count(ctx: &Context, guild_id: GuildId) -> u64 {
+    guild_id
+        .to_guild_cached(&ctx.cache)
+        .map_or(0, |x| x.member_count)
+}
+
+// Invite uses per guild, compared on every join to tell which invite was used
+static INVITE_USES: OnceLock<Mutex<HashMap<GuildId, HashMap<String, u64>>>> = OnceLock::new();
+
+async fn fetch_invite_uses(ctx: &Context, guild_id: GuildId) -> Option<HashMap<String, u64>> {
+    // Needs the Manage Server permission
+    let invites = guild_id.invites(&ctx.http).await.ok()?;
+    Some(invites.into_iter().map(|x| (x.code, x.uses)).collect())
+}
+
+/// Remembers the current invite uses of every guild, so that the first joins can be matched too.
+pub async fn track_invites(ctx: Context) {
+    for guild_id in ctx.cache.guilds() {
+        if let Some(uses) = fetch_invite_uses(&ctx, guild_id).await {
+            INVITE_USES
+                .get_or_init(Default::default)
+                .lock()
+                .unwrap()
+                .insert(guild_id, uses);
+        }
+    }
+}
+
+/// The invite whose uses went up since the last check.
+async fn used_invite(ctx: &Context, guild_id: GuildId) -> Option<String> {
+    let uses = fetch_invite_uses(ctx, guild_id).await?;
+    let previous = INVITE_USES
+        .get_or_init(Default::default)
+        .lock()
+        .unwrap()
+        .insert(guild_id, uses.clone())?;
+    uses.into_iter()
+        .find(|(code, count)| previous.get(code).map_or(*count > 0, |x| count > x))
+        .map(|(code, _)| code)
+}
+
+async fn announce(ctx: &Context, guild_id: GuildId, kind: Announcement, user: &User, invite: &str) {
+    let config = get_config(ctx, guild_id, kind).await;
+    let channel = match (config.enabled, config.channel) {
+        (true, Some(channel)) => channel,
+        _ => return,
+    };
+    let locale = i18n::guild_locale(ctx, Some(guild_id)).await;
+    let text = render(
+        locale,
+        &config.template,
+        user,
+        member_count(ctx, guild_id),
+        invite,
+    );
+    if let Err(e) = channel.say(&ctx.http, text).await {
+        eprintln!(
+            "Failed to post the {} announcement in {}: {}",
+            kind.name(),
+            guild_id,
+            e
+        );
+    }
+}
+
+/// Members that are kept quiet still count, so the invite uses stay in step.
+pub async fn member_joined(ctx: &Context, member: &Member, public: bool) {
+    let invite = used_invite(ctx, member.guild_id)
+        .await
+        .map_or_else(|| "unknown".to_string(), |x| format!("discord.gg/{}", x));
+    if !public {
+        return;
+    }
+    announce(
+        ctx,
+        member.guild_id,
+        Announcement::Join,
+        &member.user,
+        &invite,
+    )
+    .await;
+}
+
+pub async fn member_left(ctx: &Context, guild_id: GuildId, user: &User) {
+    announce(ctx, guild_id, Announcement::Leave, user, "unknown").await;
+}
+
+fn describe(kind: Announcement, config: &AnnouncementConfig) -> String {
+    format!(
+        "**{}**: {}, in {}, template `{}`",
+        kind.name(),
+        if config.enabled {
+            "enabled"
+        } else {
+            "disabled"
+        },
+        config
+            .channel
+            .map_or_else(|| "no channel".to_string(), |x| x.mention().to_string()),
+        config.template
+    )
+}
+
+pub async fn responder(mci: &ApplicationCommandInteraction, ctx: &Context) {
+    let guild_id = mci.guild_id.unwrap();
+    let subcommand = match mci.data.options.first() {
+        Some(x) => x,
+        None => return,
+    };
+    let db = ctx.get_db().await;
+
+    let response = match subcommand.name.as_str() {
+        "set" => {
+            let mut kind = Announcement::Join;
+            let mut text = MessageBuilder::new();
+            for option in &subcommand.options {
+                if let ("event", Some(CommandDataOptionValue::String(s))) =
+                    (option.name.as_str(), &option.resolved)
+                {
+                    if s == "leave" {
+                        kind = Announcement::Leave;
+                    }
+                }
+            }
+            for option in &subcommand.options {
+                match (option.name.as_str(), &option.resolved) {
+                    ("enabled", Some(CommandDataOptionValue::Boolean(b))) => {
+                        db.set_guild_config(guild_id, &kind.key("enabled"), &b.to_string())
+                            .await
+                            .unwrap();
+                    }
+                    ("channel", Some(CommandDataOptionValue::Channel(c))) => {
+                        db.set_guild_config(guild_id, &kind.key("channel"), &c.id.to_string())
+                            .await
+                            .unwrap();
+                    }
+                    ("template", Some(CommandDataOptionValue::String(s))) if s == "default" => {
+                        db.remove_guild_config(guild_id, &kind.key("template"))
+                            .await
+                            .unwrap();
+                    }
+                    ("template", Some(CommandDataOptionValue::String(s))) => {
+                        db.set_guild_config(guild_id, &kind.key("template"), s)
+                            .await
+                            .unwrap();
+                    }
+                    _ => {}
+                }
+            }
+            text.push_line("Updated:")
+                .push(describe(kind, &get_config(ctx, guild_id, kind).await));
+            text.build()
+        }
+        "show" => {
+            let mut text = MessageBuilder::new();
+            for kind in [Announcement::Join, Announcement::Leave] {
+                text.push_line(describe(kind, &get_config(ctx, guild_id, kind).await));
+            }
+            text.push_line("")
+                .push_line("Placeholders: `{tag}`, `{mention}`, `{id}`, `{account_age}`, `{member_count}`, `{invite}`");
+            text.build()
+        }
+        "test" => {
+            let locale = i18n::guild_locale(ctx, Some(guild_id)).await;
+            let mut text = MessageBuilder::new();
+            for kind in [Announcement::Join, Announcement::Leave] {
+                let config = get_config(ctx, guild_id, kind).await;
+                text.push_line(describe(kind, &config)).push_line(render(
+                    locale,
+                    &config.template,
+                    &mci.user,
+                    member_count(ctx, guild_id),
+                    "discord.gg/example",
+                ));
+                text.push_line("");
+            }
+            text.build()
+        }
+        _ => return,
+    };
+
+    mci.create_interaction_response(&ctx.http, |r| {
+        r.kind(InteractionResponseType::ChannelMessageWithSource)
+            .interaction_response_data(|d| d.content(response).flags(MessageFlags::EPHEMERAL))
+    })
+    .await
+    .unwrap();
+}
@@ -0,0 +1,746 @@
+use super::*;
+use crate::db::Db;
+use crate::utils::misc::parse_duration;
+use moderation::CaseAction;
+use serde::{Deserialize, Serialize};
+use serenity::model::{
+    application::interaction::{
+        application_command::{ApplicationCommandInteraction, CommandDataOptionValue},
+        MessageFlags,
+    },
+    event::MessageUpdateEvent,
+    id::{RoleId, UserId},
+    invite::Invite,
+    Timestamp,
+};
+use serenity::utils::MessageBuilder;
+use std::{
+    collections::{hash_map::DefaultHasher, HashMap, VecDeque},
+    hash::{Hash, Hasher},
+    sync::{Arc, Mutex, OnceLock},
+};
+use substr::StringUtils;
+
+/// Starting point for `/filters export` until a policy is imported.
+const EXAMPLE_POLICY: &str = r#"# Filters are checked in order, the first one that matches acts on the message.
+# Every filter takes:
+#   channels / exclude_channels: ids of the channels it applies to or skips (default: all)
+#   exempt_roles: ids of roles it doesn't apply to
+#   actions: any of delete, warn, timeout, log, in the order to take them
+#   timeout: how long `timeout` lasts (default: 10m)
+#   max: the threshold, see each filter
+filters:
+  # Invites to other servers
+  - kind: invites
+    actions: [delete, log]
+  # Links, either only `allow`ed domains or anything but `block`ed ones
+  - kind: links
+    block: [grabify.link, iplogger.org]
+    actions: [delete, warn]
+  # More than `max` user and role mentions
+  - kind: mentions
+    max: 5
+    actions: [delete, timeout, log]
+    timeout: 1h
+  # At least `max` percent capitals in messages of `min_length` letters or more
+  - kind: caps
+    max: 70
+    min_length: 12
+    actions: [log]
+  # More than `max` emoji
+  - kind: emoji
+    max: 12
+    actions: [log]
+  # More than `max` combining marks stacked on a character
+  - kind: zalgo
+    max: 3
+    actions: [delete, log]
+  # The same message `max` times within `window_secs`
+  - kind: repeats
+    max: 3
+    window_secs: 30
+    actions: [delete, warn]
+"#;
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum FilterKind {
+    Invites,
+    Links,
+    Mentions,
+    Caps,
+    Emoji,
+    Zalgo,
+    Repeats,
+}
+
+impl FilterKind {
+    fn name(self) -> &'static str {
+        match self {
+            FilterKind::Invites => "invites",
+            FilterKind::Links => "links",
+            FilterKind::Mentions => "mentions",
+            FilterKind::Caps => "caps",
+            FilterKind::Emoji => "emoji",
+            FilterKind::Zalgo => "zalgo",
+            FilterKind::Repeats => "repeats",
+        }
+    }
+
+    fn default_max(self) -> u32 {
+        match self {
+            FilterKind::Mentions => 5,
+            FilterKind::Caps => 70,
+            FilterKind::Emoji => 12,
+            FilterKind::Zalgo => 3,
+            FilterKind::Repeats => 3,
+            FilterKind::Invites | FilterKind::Links => 0,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum FilterAction {
+    Delete,
+    Warn,
+    Timeout,
+    Log,
+}
+
+impl FilterAction {
+    fn name(self) -> &'static str {
+        match self {
+            FilterAction::Delete => "delete",
+            FilterAction::Warn => "warn",
+            FilterAction::Timeout => "timeout",
+            FilterAction::Log => "log",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct Filter {
+    pub kind: FilterKind,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub channels: Vec<ChannelId>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub exclude_channels: Vec<ChannelId>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub exempt_roles: Vec<RoleId>,
+    pub actions: Vec<FilterAction>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_length: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub window_secs: Option<i64>,
+    /// Domains, subdomains included
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub allow: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub block: Vec<String>,
+}
+
+impl Filter {
+    fn max(&self) -> u32 {
+        self.max.unwrap_or_else(|| self.kind.default_max())
+    }
+
+    fn timeout_secs(&self) -> i64 {
+        self.timeout
+            .as_deref()
+            .and_then(parse_duration)
+            .unwrap_or(600)
+    }
+
+    fn applies_to(&self, channel_id: ChannelId, roles: &[RoleId]) -> bool {
+        (self.channels.is_empty() || self.channels.contains(&channel_id))
+            && !self.exclude_channels.contains(&channel_id)
+            && !roles.iter().any(|x| self.exempt_roles.contains(x))
+    }
+}
+
+#[derive(Serialize, Deserialize, Default)]
+pub struct FilterPolicy {
+    #[serde(default)]
+    pub filters: Vec<Filter>,
+}
+
+impl FilterPolicy {
+    pub fn validate(&self) -> Result<()> {
+        for (i, filter) in self.filters.iter().enumerate() {
+            let name = format!("filter {} ({})", i + 1, filter.kind.name());
+            if filter.actions.is_empty() {
+                anyhow::bail!("{} has no actions", name);
+            }
+            if filter.kind == FilterKind::Links
+                && filter.allow.is_empty() == filter.block.is_empty()
+            {
+                anyhow::bail!("{} needs either `allow` or `block`", name);
+            }
+            if let Some(timeout) = &filter.timeout {
+                match parse_duration(timeout) {
+                    Some(secs) if secs <= 28 * 86400 => {}
+                    _ => anyhow::bail!("{} has an invalid timeout `{}`", name, timeout),
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Db {
+    pub async fn get_filter_policy(&self, guild_id: GuildId) -> Result<Option<String>> {
+        let guild_id = guild_id.0 as i64;
+        let q = sqlx::query!(
+            "select definition from automod_filters where guild_id=?",
+            guild_id
+        )
+        .fetch_optional(&self.sqlitedb)
+        .await?;
+        Ok(q.map(|x| x.definition))
+    }
+
+    pub async fn set_filter_policy(
+        &self,
+        guild_id: GuildId,
+        definition: &str,
+        updated_at: i64,
+    ) -> Result<()> {
+        let guild_id = guild_id.0 as i64;
+        sqlx::query!(
+            "insert into automod_filters (guild_id, definition, updated_at) values (?1, ?2, ?3) on conflict(guild_id) do update set definition=?2, updated_at=?3",
+            guild_id,
+            definition,
+            updated_at
+        )
+        .execute(&self.sqlitedb)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn remove_filter_policy(&self, guild_id: GuildId) -> Result<()> {
+        let guild_id = guild_id.0 as i64;
+        sqlx::query!("delete from automod_filters where guild_id=?", guild_id)
+            .execute(&self.sqlitedb)
+            .await?;
+        Ok(())
+    }
+}
+
+// Parsed policies, dropped when they change
+static POLICIES: OnceLock<Mutex<HashMap<GuildId, Arc<FilterPolicy>>>> = OnceLock::new();
+
+async fn get_policy(ctx: &Context, guild_id: GuildId) -> Arc<FilterPolicy> {
+    if let Some(policy) = POLICIES
+        .get_or_init(Default::default)
+        .lock()
+        .unwrap()
+        .get(&guild_id)
+    {
+        return policy.clone();
+    }
+    let db = ctx.get_db().await;
+    let policy: Arc<FilterPolicy> = Arc::new(
+        db.get_filter_policy(guild_id)
+            .await
+            .ok()
+            .flatten()
+            .and_then(|x| serde_yaml::from_str(&x).ok())
+            .unwrap_or_default(),
+    );
+    POLICIES
+        .get_or_init(Default::default)
+        .lock()
+        .unwrap()
+        .insert(guild_id, policy.clone());
+    policy
+}
+
+fn forget_policy(guild_id: GuildId) {
+    POLICIES
+        .get_or_init(Default::default)
+        .lock()
+        .unwrap()
+        .remove(&guild_id);
+}
+
+async fn is_dry_run(ctx: &Context, guild_id: GuildId) -> bool {
+    let db = ctx.get_db().await;
+    db.get_guild_config_as::<bool>(guild_id, "filters_dry_run")
+        .await
+        .ok()
+        .flatten()
+        .unwrap_or(false)
+}
+
+// Recent message fingerprints per author, for the repeats filter
+type Fingerprints = VecDeque<(i64, u64)>;
+static RECENT: OnceLock<Mutex<HashMap<(GuildId, UserId), Fingerprints>>> = OnceLock::new();
+
+fn fingerprint(content: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    content
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+        .hash(&mut hasher);
+    hasher.finish()
+}
+
+/// How many times the author sent this message within the window, this one included.
+fn count_repeats(guild_id: GuildId, msg: &Message, window_secs: i64) -> usize {
+    let now = msg.timestamp.unix_timestamp();
+    let print = fingerprint(&msg.content);
+    let mut recent = RECENT.get_or_init(Default::default).lock().unwrap();
+    let history = recent.entry((guild_id, msg.author.id)).or_default();
+    while history.front().is_some_and(|(at, _)| *at <= now - 300) {
+        history.pop_front();
+    }
+    history.push_back((now, print));
+    history
+        .iter()
+        .filter(|(at, x)| *x == print && *at > now - window_secs)
+        .count()
+}
+
+fn is_combining_mark(c: char) -> bool {
+    matches!(c as u32,
+        0x0300..=0x036F | 0x0483..=0x0489 | 0x1AB0..=0x1AFF | 0x1DC0..=0x1DFF | 0x20D0..=0x20FF | 0xFE20..=0xFE2F)
+}
+
+fn is_emoji(c: char) -> bool {
+    matches!(c as u32, 0x1F300..=0x1FAFF | 0x2600..=0x27BF | 0x1F1E6..=0x1F1FF)
+}
+
+static LINK_RE: OnceLock<Regex> = OnceLock::new();
+static INVITE_RE: OnceLock<Regex> = OnceLock::new();
+static CUSTOM_EMOJI_RE: OnceLock<Regex> = OnceLock::new();
+
+// How long an invite's guild is remembered, invites are checked on every message and edit
+const INVITE_CACHE_SECS: i64 = 600;
+type InviteCache = HashMap<String, (i64, Option<GuildId>)>;
+static INVITE_GUILDS: OnceLock<Mutex<InviteCache>> = OnceLock::new();
+
+/// The guild an invite leads to, `None` for unknown or expired invites.
+async fn invite_guild(ctx: &Context, code: &str) -> Option<GuildId> {
+    let now = Timestamp::now().unix_timestamp();
+    let cache = INVITE_GUILDS.get_or_init(Default::default);
+    if let Some((at, guild_id)) = cache.lock().unwrap().get(code) {
+        if *at > now - INVITE_CACHE_SECS {
+            return *guild_id;
+        }
+    }
+    let guild_id = Invite::get(&ctx.http, code, false, false, None)
+        .await
+        .ok()
+        .and_then(|x| x.guild.map(|g| g.id));
+    let mut cache = cache.lock().unwrap();
+    cache.retain(|_, (at, _)| *at > now - INVITE_CACHE_SECS);
+    cache.insert(code.to_string(), (now, guild_id));
+    guild_id
+}
+
+fn link_domains(content: &str) -> Vec<String> {
+    LINK_RE
+        .get_or_init(|| Regex::new(r"(?i)https?://([^/\s<>:]+)").unwrap())
+        .captures_iter(content)
+        .map(|x| x[1].trim_end_matches('.').to_lowercase())
+        .collect()
+}
+
+fn matches_domain(domain: &str, list: &[String]) -> bool {
+    list.iter().any(|x| {
+        let x = x.to_lowercase();
+        domain == x || domain.ends_with(&format!(".{}", x))
+    })
+}
+
+/// What the filter found wrong with the message, if anything.
+async fn check(
+    ctx: &Context,
+    guild_id: GuildId,
+    filter: &Filter,
+    msg: &Message,
+    edited: bool,
+) -> Option<String> {
+    let content = &msg.content;
+    let max = filter.max() as usize;
+    match filter.kind {
+        FilterKind::Invites => {
+            let re = INVITE_RE.get_or_init(|| {
+                Regex::new(r"(?i)(?:discord(?:app)?\.com/invite|discord\.gg)/([\w-]+)").unwrap()
+            });
+            for caps in re.captures_iter(content) {
+                let code = &caps[1];
+                let target = invite_guild(ctx, code).await;
+                // Unknown or expired invites are treated as foreign
+                if target != Some(guild_id) {
+                    return Some(format!("invite `{}` to another server", code));
+                }
+            }
+            None
+        }
+        FilterKind::Links => link_domains(content).into_iter().find_map(|domain| {
+            let blocked = if filter.allow.is_empty() {
+                matches_domain(&domain, &filter.block)
+            } else {
+                !matches_domain(&domain, &filter.allow)
+            };
+            blocked.then(|| format!("link to `{}`", domain))
+        }),
+        FilterKind::Mentions => {
+            let count = msg
+                .mentions
+                .iter()
+                .filter(|x| x.id != msg.author.id)
+                .count()
+                + msg.mention_roles.len()
+                + usize::from(msg.mention_everyone);
+            (count > max).then(|| format!("{} mentions", count))
+        }
+        FilterKind::Caps => {
+            let letters: Vec<char> = content.chars().filter(|x| x.is_alphabetic()).collect();
+            let caps = letters.iter().filter(|x| x.is_uppercase()).count();
+            (!letters.is_empty()
+                && letters.len() >= filter.min_length.unwrap_or(12)
+                && caps * 100 >= max * letters.len())
+            .then(|| format!("{}% capitals", caps * 100 / letters.len()))
+        }
+        FilterKind::Emoji => {
+            let custom = CUSTOM_EMOJI_RE
+                .get_or_init(|| Regex::new(r"<a?:\w+:\d+>").unwrap())
+                .find_iter(content)
+                .count();
+            let count = custom + content.chars().filter(|x| is_emoji(*x)).count();
+            (count > max).then(|| format!("{} emoji", count))
+        }
+        FilterKind::Zalgo => {
+            let mut stacked = 0;
+            let mut most = 0;
+            for c in content.chars() {
+                if is_combining_mark(c) {
+                    stacked += 1;
+                    most = most.max(stacked);
+                } else {
+                    stacked = 0;
+                }
+            }
+            (most > max).then(|| format!("{} stacked combining marks", most))
+        }
+        FilterKind::Repeats => {
+            // Edits don't send the message again
+            if edited || content.trim().is_empty() {
+                return None;
+            }
+            let count = count_repeats(guild_id, msg, filter.window_secs.unwrap_or(30));
+            (count >= max).then(|| format!("sent {} times", count))
+        }
+    }
+}
+
+async fn take_actions(
+    ctx: &Context,
+    guild_id: GuildId,
+    filter: &Filter,
+    msg: &Message,
+    detail: &str,
+    dry_run: bool,
+) -> bool {
+    let actions = filter
+        .actions
+        .iter()
+        .map(|x| x.name())
+        .collect::<Vec<_>>()
+        .join(", ");
+    let reason = format!("Automod: {} filter, {}", filter.kind.name(), detail);
+    if dry_run {
+        if let Err(e) = post_log(
+            ctx,
+            guild_id,
+            format!(
+                "> :test_tube: **Dry run**, would {} the message of {} in {}: {}\n> {}",
+                actions,
+                msg.author.mention(),
+                msg.channel_id.mention(),
+                reason,
+                msg.content.replace('\n', " ").as_str().substring(0, 300)
+            ),
+        )
+        .await
+        {
+            eprintln!("Failed to log an automod dry run in {}: {}", guild_id, e);
+        }
+        return false;
+    }
+
+    let bot_id = ctx.cache.current_user_id();
+    let mut deleted = false;
+    for action in &filter.actions {
+        match action {
+            FilterAction::Delete => match msg.delete(&ctx.http).await {
+                Ok(_) => deleted = true,
+                Err(e) => eprintln!("Failed to delete message {}: {}", msg.id, e),
+            },
+            FilterAction::Warn | FilterAction::Timeout => {
+                let (case_action, duration) = if *action == FilterAction::Warn {
+                    (CaseAction::Warn, None)
+                } else {
+                    (CaseAction::Timeout, Some(filter.timeout_secs()))
+                };
+                let evidence = if deleted {
+                    msg.channel_id.mention().to_string()
+                } else {
+                    msg.link()
+                };
+                if let Err(e) = moderation::open_case(
+                    ctx,
+                    guild_id,
+                    msg.author.id,
+                    bot_id,
+                    case_action,
+                    &reason,
+                    duration,
+                    &evidence,
+                    0,
+                )
+                .await
+                {
+                    eprintln!(
+                        "Failed to {} {} in {}: {}",
+                        case_action.name(),
+                        msg.author.id,
+                        guild_id,
+                        e
+                    );
+                }
+            }
+            FilterAction::Log => {
+                if let Err(e) = post_log(
+                    ctx,
+                    guild_id,
+                    format!(
+                        "> :no_entry: {} in {}: {}, took: {}\n> {}",
+                        msg.author.mention(),
+                        msg.channel_id.mention(),
+                        reason,
+                        actions,
+                        msg.content.replace('\n', " ").as_str().substring(0, 300)
+                    ),
+                )
+                .await
+                {
+                    eprintln!("Failed to log an automod action in {}: {}", guild_id, e);
+                }
+            }
+        }
+    }
+    deleted
+}
+
+/// Runs the guild's filters on a message, returns whether it was deleted.
+pub async fn check_message(ctx: &Context, msg: &Message, edited: bool) -> Result<bool> {
+    let guild_id = match msg.guild_id {
+        Some(guild_id) => guild_id,
+        None => return Ok(false),
+    };
+    if msg.author.bot || msg.webhook_id.is_some() {
+        return Ok(false);
+    }
+    let policy = get_policy(ctx, guild_id).await;
+    if policy.filters.is_empty() {
+        return Ok(false);
+    }
+    let roles = match &msg.member {
+        Some(member) => member.roles.clone(),
+        None => guild_id
+            .member(ctx, msg.author.id)
+            .await
+            .map(|x| x.roles)
+            .unwrap_or_default(),
+    };
+    // Threads share the scope of their parent channel
+    let channel_id = match msg.channel_id.to_channel_cached(&ctx.cache) {
+        Some(channel) => channel
+            .guild()
+            .and_then(|x| x.thread_metadata.and(x.parent_id))
+            .unwrap_or(msg.channel_id),
+        None => msg.channel_id,
+    };
+
+    for filter in policy
+        .filters
+        .iter()
+        .filter(|x| x.applies_to(channel_id, &roles))
+    {
+        if let Some(detail) = check(ctx, guild_id, filter, msg, edited).await {
+            let dry_run = is_dry_run(ctx, guild_id).await;
+            return Ok(take_actions(ctx, guild_id, filter, msg, &detail, dry_run).await);
+        }
+    }
+    Ok(false)
+}
+
+/// Edits can sneak content past the filters, so the new content goes through them too.
+pub async fn message_edited(ctx: &Context, new: Option<Message>, event: &MessageUpdateEvent) {
+    // Embeds being resolved also come as updates, without content
+    if event.content.is_none() {
+        return;
+    }
+    let msg = match new {
+        Some(msg) => msg,
+        None => match event.channel_id.message(&ctx.http, event.id).await {
+            Ok(mut msg) => {
+                msg.guild_id = event.guild_id;
+                msg
+            }
+            Err(_) => return,
+        },
+    };
+    if let Err(e) = check_message(ctx, &msg, true).await {
+        eprintln!("Failed to filter the edit of {}: {}", msg.id, e);
+    }
+}
+
+fn summary(policy: &FilterPolicy) -> String {
+    if policy.filters.is_empty() {
+        return "No filters".to_string();
+    }
+    let mut text = MessageBuilder::new();
+    for (i, filter) in policy.filters.iter().enumerate() {
+        text.push(format!(
+            "{}. **{}** → {}",
+            i + 1,
+            filter.kind.name(),
+            filter
+                .actions
+                .iter()
+                .map(|x| x.name())
+                .collect::<Vec<_>>()
+                .join(", ")
+        ));
+        if !filter.channels.is_empty() {
+            text.push(format!(
+                ", in {}",
+                filter
+                    .channels
+                    .iter()
+                    .map(|x| x.mention().to_string())
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            ));
+        }
+        if !filter.exempt_roles.is_empty() {
+            text.push(format!(", {} exempt roles", filter.exempt_roles.len()));
+        }
+        text.push_line("");
+    }
+    text.build()
+}
+
+/// `/filters`
+pub async fn responder(mci: &ApplicationCommandInteraction, ctx: &Context) {
+    let guild_id = mci.guild_id.unwrap();
+    let subcommand = match mci.data.options.first() {
+        Some(x) => x,
+        None => return,
+    };
+    let db = ctx.get_db().await;
+
+    if subcommand.name == "export" {
+        let yaml = db
+            .get_filter_policy(guild_id)
+            .await
+            .unwrap()
+            .unwrap_or_else(|| EXAMPLE_POLICY.to_string());
+        mci.create_interaction_response(&ctx.http, |r| {
+            r.kind(InteractionResponseType::ChannelMessageWithSource)
+                .interaction_response_data(|d| {
+                    d.add_file((yaml.as_bytes(), "filters.yaml"))
+                        .flags(MessageFlags::EPHEMERAL)
+                })
+        })
+        .await
+        .unwrap();
+        return;
+    }
+
+    let response = match subcommand.name.as_str() {
+        "show" => format!(
+            "**Content filters**{}\n{}",
+            if is_dry_run(ctx, guild_id).await {
+                " (dry run, only logged)"
+            } else {
+                ""
+            },
+            summary(&*get_policy(ctx, guild_id).await)
+        ),
+        "import" => {
+            let attachment = subcommand.options.iter().find_map(|x| match &x.resolved {
+                Some(CommandDataOptionValue::Attachment(a)) => Some(a),
+                _ => None,
+            });
+            let definition = match attachment {
+                Some(attachment) => attachment
+                    .download()
+                    .await
+                    .ok()
+                    .and_then(|x| String::from_utf8(x).ok()),
+                None => None,
+            };
+            match definition.map(|x| {
+                serde_yaml::from_str::<FilterPolicy>(&x)
+                    .map_err(anyhow::Error::from)
+                    .and_then(|policy| policy.validate().map(|_| (x, policy)))
+            }) {
+                Some(Ok((definition, policy))) => {
+                    db.set_filter_policy(guild_id, &definition, Timestamp::now().unix_timestamp())
+                        .await
+                        .unwrap();
+                    forget_policy(guild_id);
+                    format!("Saved\n{}", summary(&policy))
+                }
+                Some(Err(e)) => format!("Invalid filters: {}", e),
+                None => "Please attach the filters as a UTF-8 YAML file".to_string(),
+            }
+        }
+        "reset" => {
+            db.remove_filter_policy(guild_id).await.unwrap();
+            forget_policy(guild_id);
+            "Removed all filters".to_string()
+        }
+        "dry-run" => {
+            let enabled = subcommand
+                .options
+                .iter()
+                .find_map(|x| match x.resolved {
+                    Some(CommandDataOptionValue::Boolean(b)) => Some(b),
+                    _ => None,
+                })
+                .unwrap_or(true);
+            db.set_guild_config(guild_id, "filters_dry_run", &enabled.to_string())
+                .await
+                .unwrap();
+            if enabled {
+                "Dry run enabled, filter hits are only logged".to_string()
+            } else {
+                "Dry run disabled, filters take their actions".to_string()
+            }
+        }
+        _ => return,
+    };
+
+    mci.create_interaction_response(&ctx.http, |r| {
+        r.kind(InteractionResponseType::ChannelMessageWithSource)
+            .interaction_response_data(|d| {
+                d.content(response.as_str().substring(0, 2000))
+                    .flags(MessageFlags::EPHEMERAL)
+            })
+    })
+    .await
+    .unwrap();
+}
@@ -0,0 +1,913 @@
+use super::*;
+use crate::db::Db;
+use crate::utils::misc::{format_duration, parse_duration};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use serenity::http::{request::RequestBuilder, routing::RouteInfo};
+use serenity::model::{
+    application::interaction::{
+        application_command::{ApplicationCommandInteraction, CommandDataOptionValue},
+        MessageFlags,
+    },
+    guild::automod::{Action, ActionExecution, TriggerType},
+    id::{RoleId, RuleId},
+    Timestamp,
+};
+use std::{
+    collections::{hash_map::DefaultHasher, HashMap},
+    hash::{Hash, Hasher},
+    sync::{Mutex, OnceLock},
+};
+use substr::StringUtils;
+
+/// Starting point for `/automod list` until a policy is applied.
+const EXAMPLE_POLICY: &str = r#"# Discord's own AutoMod rules, matched to the server's rules by name.
+# Every rule takes:
+#   trigger: keyword, keyword_preset, spam or mention_spam
+#   actions: block (with an optional `message`), alert (to a `channel`), timeout (for a `duration`)
+#   exempt_roles / exempt_channels: ids the rule doesn't apply to
+#   enabled: whether Discord enforces it (default: true)
+# With `prune: true`, rules missing from this file are deleted when it's applied.
+prune: false
+rules:
+  # Words or phrases, `*` as a wildcard, plus up to 10 regexes
+  - name: Scam phrases
+    trigger: keyword
+    keywords: ["free nitro", "*steamcommunity.ru*"]
+    regex: ['disc[o0]rd[-.]?gift']
+    actions:
+      - kind: block
+        message: That looks like a scam
+      - kind: alert
+        channel: 000000000000000000 # replace with the id of the channel for alerts
+  # Discord's word lists: profanity, sexual_content, slurs
+  - name: Slurs
+    trigger: keyword_preset
+    presets: [slurs]
+    allow: []
+    actions:
+      - kind: block
+  # More than `limit` user and role mentions in a message
+  - name: Mention spam
+    trigger: mention_spam
+    limit: 8
+    actions:
+      - kind: block
+      - kind: timeout
+        duration: 1h
+  # Content Discord considers spam
+  - name: Spam
+    trigger: spam
+    actions:
+      - kind: block
+"#;
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum TriggerKind {
+    Keyword,
+    Spam,
+    KeywordPreset,
+    MentionSpam,
+}
+
+impl TriggerKind {
+    fn id(self) -> u64 {
+        match self {
+            TriggerKind::Keyword => 1,
+            TriggerKind::Spam => 3,
+            TriggerKind::KeywordPreset => 4,
+            TriggerKind::MentionSpam => 5,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            TriggerKind::Keyword => "keyword",
+            TriggerKind::Spam => "spam",
+            TriggerKind::KeywordPreset => "keyword_preset",
+            TriggerKind::MentionSpam => "mention_spam",
+        }
+    }
+}
+
+fn trigger_name(id: u64) -> &'static str {
+    match id {
+        1 => "keyword",
+        2 => "harmful_link",
+        3 => "spam",
+        4 => "keyword_preset",
+        5 => "mention_spam",
+        _ => "unknown",
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Preset {
+    Profanity,
+    SexualContent,
+    Slurs,
+}
+
+impl Preset {
+    fn id(self) -> u64 {
+        match self {
+            Preset::Profanity => 1,
+            Preset::SexualContent => 2,
+            Preset::Slurs => 3,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum RuleActionKind {
+    Block,
+    Alert,
+    Timeout,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct RuleAction {
+    pub kind: RuleActionKind,
+    /// Shown to the member when their message is blocked
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub channel: Option<ChannelId>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub duration: Option<String>,
+}
+
+fn enabled() -> bool {
+    true
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct RuleSpec {
+    pub name: String,
+    pub trigger: TriggerKind,
+    #[serde(default = "enabled")]
+    pub enabled: bool,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub keywords: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub regex: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub presets: Vec<Preset>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub allow: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub limit: Option<u32>,
+    pub actions: Vec<RuleAction>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub exempt_roles: Vec<RoleId>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub exempt_channels: Vec<ChannelId>,
+}
+
+impl RuleSpec {
+    /// The rule as Discord takes it, in the shape [`normalize`] gives live rules.
+    fn body(&self) -> Value {
+        let mut metadata = Map::new();
+        let mut list = |key: &str, values: Vec<Value>| {
+            if !values.is_empty() {
+                metadata.insert(key.to_string(), Value::Array(values));
+            }
+        };
+        match self.trigger {
+            TriggerKind::Keyword => {
+                list(
+                    "keyword_filter",
+                    self.keywords.iter().map(|x| json!(x)).collect(),
+                );
+                list(
+                    "regex_patterns",
+                    self.regex.iter().map(|x| json!(x)).collect(),
+                );
+                list("allow_list", self.allow.iter().map(|x| json!(x)).collect());
+            }
+            TriggerKind::KeywordPreset => {
+                list(
+                    "presets",
+                    self.presets.iter().map(|x| json!(x.id())).collect(),
+                );
+                list("allow_list", self.allow.iter().map(|x| json!(x)).collect());
+            }
+            TriggerKind::MentionSpam => {
+                metadata.insert(
+                    "mention_total_limit".to_string(),
+                    json!(self.limit.unwrap_or(5)),
+                );
+            }
+            TriggerKind::Spam => {}
+        }
+
+        let actions: Vec<Value> = self
+            .actions
+            .iter()
+            .map(|action| match action.kind {
+                RuleActionKind::Block => match &action.message {
+                    Some(message) => json!({"type": 1, "metadata": {"custom_message": message}}),
+                    None => json!({"type": 1, "metadata": {}}),
+                },
+                RuleActionKind::Alert => json!({
+                    "type": 2,
+                    "metadata": {"channel_id": action.channel.unwrap_or_default().to_string()}
+                }),
+                RuleActionKind::Timeout => json!({
+                    "type": 3,
+                    "metadata": {
+                        "duration_seconds": action.duration.as_deref().and_then(parse_duration).unwrap_or(600)
+                    }
+                }),
+            })
+            .collect();
+
+        let mut exempt_roles: Vec<String> =
+            self.exempt_roles.iter().map(|x| x.to_string()).collect();
+        exempt_roles.sort();
+        let mut exempt_channels: Vec<String> =
+            self.exempt_channels.iter().map(|x| x.to_string()).collect();
+        exempt_channels.sort();
+        json!({
+            "name": self.name,
+            "event_type": 1,
+            "trigger_type": self.trigger.id(),
+            "trigger_metadata": metadata,
+            "actions": actions,
+            "enabled": self.enabled,
+            "exempt_roles": exempt_roles,
+            "exempt_channels": exempt_channels,
+        })
+    }
+}
+
+/// A rule fetched from Discord, reduced to the fields [`RuleSpec::body`] sets.
+fn normalize(rule: &Value) -> Value {
+    let metadata: Map<String, Value> = rule["trigger_metadata"]
+        .as_object()
+        .map(|x| {
+            x.iter()
+                .filter(|(key, value)| {
+                    [
+                        "keyword_filter",
+                        "regex_patterns",
+                        "allow_list",
+                        "presets",
+                        "mention_total_limit",
+                    ]
+                    .contains(&key.as_str())
+                        && !value.is_null()
+                        && value.as_array().is_none_or(|x| !x.is_empty())
+                })
+                .map(|(key, value)| (key.clone(), value.clone()))
+                .collect()
+        })
+        .unwrap_or_default();
+    let actions: Vec<Value> = rule["actions"]
+        .as_array()
+        .map(|x| {
+            x.iter()
+                .map(|action| {
+                    let metadata: Map<String, Value> = action["metadata"]
+                        .as_object()
+                        .map(|x| {
+                            x.iter()
+                                .filter(|(_, value)| !value.is_null() && value.as_str() != Some(""))
+                                .map(|(key, value)| (key.clone(), value.clone()))
+                                .collect()
+                        })
+                        .unwrap_or_default();
+                    json!({"type": action["type"], "metadata": metadata})
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    let sorted = |key: &str| {
+        let mut ids: Vec<String> = rule[key]
+            .as_array()
+            .map(|x| {
+                x.iter()
+                    .filter_map(|x| x.as_str().map(String::from))
+                    .collect()
+            })
+            .unwrap_or_default();
+        ids.sort();
+        ids
+    };
+    json!({
+        "name": rule["name"],
+        "event_type": rule["event_type"],
+        "trigger_type": rule["trigger_type"],
+        "trigger_metadata": metadata,
+        "actions": actions,
+        "enabled": rule["enabled"],
+        "exempt_roles": sorted("exempt_roles"),
+        "exempt_channels": sorted("exempt_channels"),
+    })
+}
+
+#[derive(Serialize, Deserialize, Default)]
+pub struct RulePolicy {
+    /// Delete rules that aren't in the policy
+    #[serde(default)]
+    pub prune: bool,
+    #[serde(default)]
+    pub rules: Vec<RuleSpec>,
+}
+
+impl RulePolicy {
+    pub fn validate(&self) -> Result<()> {
+        let mut names = Vec::new();
+        for (i, rule) in self.rules.iter().enumerate() {
+            let name = format!("rule {} ({})", i + 1, rule.name);
+            if rule.name.is_empty() || rule.name.chars().count() > 100 {
+                anyhow::bail!("{} needs a name of 1 to 100 characters", name);
+            }
+            if names.contains(&rule.name.to_lowercase()) {
+                anyhow::bail!("{} has the same name as another rule", name);
+            }
+            names.push(rule.name.to_lowercase());
+            if rule.actions.is_empty() {
+                anyhow::bail!("{} has no actions", name);
+            }
+            match rule.trigger {
+                TriggerKind::Keyword if rule.keywords.is_empty() && rule.regex.is_empty() => {
+                    anyhow::bail!("{} needs `keywords` or `regex`", name)
+                }
+                TriggerKind::Keyword if rule.keywords.len() > 1000 || rule.regex.len() > 10 => {
+                    anyhow::bail!("{} has more than 1000 keywords or 10 regexes", name)
+                }
+                TriggerKind::KeywordPreset if rule.presets.is_empty() => {
+                    anyhow::bail!("{} needs `presets`", name)
+                }
+                TriggerKind::MentionSpam if rule.limit.is_some_and(|x| x == 0 || x > 50) => {
+                    anyhow::bail!("{} needs a `limit` of 1 to 50", name)
+                }
+                _ => {}
+            }
+            for action in &rule.actions {
+                match action.kind {
+                    RuleActionKind::Alert if action.channel.is_none_or(|x| x.0 == 0) => {
+                        anyhow::bail!("{} has an alert without a `channel` id", name)
+                    }
+                    RuleActionKind::Timeout => {
+                        if matches!(rule.trigger, TriggerKind::Spam | TriggerKind::KeywordPreset) {
+                            anyhow::bail!(
+                                "{}: Discord only times out on keyword and mention_spam rules",
+                                name
+                            );
+                        }
+                        if let Some(duration) = &action.duration {
+                            match parse_duration(duration) {
+                                Some(secs) if secs <= 28 * 86400 => {}
+                                _ => {
+                                    anyhow::bail!("{} has an invalid timeout `{}`", name, duration)
+                                }
+                            }
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Db {
+    pub async fn get_automod_policy(&self, guild_id: GuildId) -> Result<Option<String>> {
+        let guild_id = guild_id.0 as i64;
+        let q = sqlx::query!(
+            "select definition from automod_rules where guild_id=?",
+            guild_id
+        )
+        .fetch_optional(&self.sqlitedb)
+        .await?;
+        Ok(q.map(|x| x.definition))
+    }
+
+    pub async fn set_automod_policy(
+        &self,
+        guild_id: GuildId,
+        definition: &str,
+        updated_at: i64,
+    ) -> Result<()> {
+        let guild_id = guild_id.0 as i64;
+        sqlx::query!(
+            "insert into automod_rules (guild_id, definition, updated_at) values (?1, ?2, ?3) on conflict(guild_id) do update set definition=?2, updated_at=?3",
+            guild_id,
+            definition,
+            updated_at
+        )
+        .execute(&self.sqlitedb)
+        .await?;
+        Ok(())
+    }
+}
+
+// Serenity's rule model drops metadata it doesn't know, so rules go over the wire as JSON
+async fn live_rules(ctx: &Context, guild_id: GuildId) -> Result<Vec<Value>> {
+    let request = RequestBuilder::new(RouteInfo::GetAutoModRules {
+        guild_id: guild_id.0,
+    })
+    .build();
+    Ok(ctx.http.fire(request).await?)
+}
+
+async fn live_rule(ctx: &Context, guild_id: GuildId, rule_id: RuleId) -> Result<Value> {
+    let request = RequestBuilder::new(RouteInfo::GetAutoModRule {
+        guild_id: guild_id.0,
+        rule_id: rule_id.0,
+    })
+    .build();
+    Ok(ctx.http.fire(request).await?)
+}
+
+fn rule_id(rule: &Value) -> Option<RuleId> {
+    rule["id"].as_str().and_then(|x| x.parse().ok()).map(RuleId)
+}
+
+enum Change<'a> {
+    Create(&'a RuleSpec),
+    Update(RuleId, &'a RuleSpec, Vec<String>),
+    // The trigger of a rule can't be edited
+    Replace(RuleId, &'a RuleSpec),
+    Delete(RuleId, String),
+}
+
+struct Plan<'a> {
+    changes: Vec<Change<'a>>,
+    unchanged: Vec<String>,
+    unmanaged: Vec<String>,
+}
+
+fn differing_fields(current: &Value, wanted: &Value) -> Vec<String> {
+    let mut fields = Vec::new();
+    for key in ["enabled", "actions", "exempt_roles", "exempt_channels"] {
+        if current[key] != wanted[key] {
+            fields.push(key.to_string());
+        }
+    }
+    if let (Some(current), Some(wanted)) = (
+        current["trigger_metadata"].as_object(),
+        wanted["trigger_metadata"].as_object(),
+    ) {
+        let mut keys: Vec<&String> = current.keys().chain(wanted.keys()).collect();
+        keys.sort();
+        keys.dedup();
+        for key in keys {
+            if current.get(key) != wanted.get(key) {
+                fields.push(key.clone());
+            }
+        }
+    }
+    fields
+}
+
+fn plan<'a>(policy: &'a RulePolicy, live: &[Value]) -> Plan<'a> {
+    let mut plan = Plan {
+        changes: Vec::new(),
+        unchanged: Vec::new(),
+        unmanaged: Vec::new(),
+    };
+    for spec in &policy.rules {
+        let wanted = spec.body();
+        let current = live.iter().find(|x| {
+            x["name"]
+                .as_str()
+                .is_some_and(|name| name.eq_ignore_ascii_case(&spec.name))
+        });
+        match current.and_then(|x| rule_id(x).map(|id| (id, normalize(x)))) {
+            None => plan.changes.push(Change::Create(spec)),
+            Some((id, current)) if current["trigger_type"] != wanted["trigger_type"] => {
+                plan.changes.push(Change::Replace(id, spec))
+            }
+            Some((id, current)) => {
+                let fields = differing_fields(&current, &wanted);
+                if fields.is_empty() && current["name"] == wanted["name"] {
+                    plan.unchanged.push(spec.name.clone());
+                } else {
+                    plan.changes.push(Change::Update(id, spec, fields));
+                }
+            }
+        }
+    }
+    for rule in live {
+        let name = rule["name"].as_str().unwrap_or_default().to_string();
+        if policy
+            .rules
+            .iter()
+            .any(|x| x.name.eq_ignore_ascii_case(&name))
+        {
+            continue;
+        }
+        match rule_id(rule) {
+            Some(id) if policy.prune => plan.changes.push(Change::Delete(id, name)),
+            _ => plan.unmanaged.push(name),
+        }
+    }
+    plan
+}
+
+fn describe_plan(plan: &Plan) -> String {
+    let mut lines: Vec<String> = plan
+        .changes
+        .iter()
+        .map(|change| match change {
+            Change::Create(spec) => {
+                format!("➕ create **{}** ({})", spec.name, spec.trigger.name())
+            }
+            Change::Update(_, spec, fields) if fields.is_empty() => {
+                format!("✏️ rename **{}**", spec.name)
+            }
+            Change::Update(_, spec, fields) => {
+                format!("✏️ update **{}**: {}", spec.name, fields.join(", "))
+            }
+            Change::Replace(_, spec) => format!(
+                "♻️ recreate **{}** as {}, the trigger changed",
+                spec.name,
+                spec.trigger.name()
+            ),
+            Change::Delete(_, name) => format!("➖ delete **{}**", name),
+        })
+        .collect();
+    if lines.is_empty() {
+        lines.push("No changes".to_string());
+    }
+    if !plan.unchanged.is_empty() {
+        lines.push(format!("Unchanged: {}", plan.unchanged.join(", ")));
+    }
+    if !plan.unmanaged.is_empty() {
+        lines.push(format!(
+            "Not in the policy, left alone: {}",
+            plan.unmanaged.join(", ")
+        ));
+    }
+    lines.join("\n")
+}
+
+async fn execute_plan(ctx: &Context, guild_id: GuildId, plan: &Plan<'_>) -> Vec<String> {
+    let mut failures = Vec::new();
+    for change in &plan.changes {
+        let result = match change {
+            Change::Create(spec) => create_rule(ctx, guild_id, spec).await,
+            Change::Update(id, spec, _) => {
+                let mut body = spec.body();
+                if let Some(body) = body.as_object_mut() {
+                    body.remove("trigger_type");
+                }
+                let body = serde_json::to_vec(&body).unwrap();
+                let mut request = RequestBuilder::new(RouteInfo::EditAutoModRule {
+                    guild_id: guild_id.0,
+                    rule_id: id.0,
+                });
+                request.body(Some(&body));
+                ctx.http
+                    .fire::<Value>(request.build())
+                    .await
+                    .map(|_| ())
+                    .map_err(anyhow::Error::from)
+            }
+            // Created first so a failure leaves the old rule in place
+            Change::Replace(id, spec) => match create_rule(ctx, guild_id, spec).await {
+                Ok(_) => ctx
+                    .http
+                    .delete_automod_rule(guild_id.0, id.0)
+                    .await
+                    .map_err(|e| anyhow::anyhow!("created, but the old rule is left: {}", e)),
+                Err(e) => Err(e),
+            },
+            Change::Delete(id, _) => ctx
+                .http
+                .delete_automod_rule(guild_id.0, id.0)
+                .await
+                .map_err(anyhow::Error::from),
+        };
+        if let Err(e) = result {
+            let name = match change {
+                Change::Create(spec) | Change::Update(_, spec, _) | Change::Replace(_, spec) => {
+                    spec.name.clone()
+                }
+                Change::Delete(_, name) => name.clone(),
+            };
+            failures.push(format!("**{}**: {}", name, e));
+        }
+    }
+    failures
+}
+
+async fn create_rule(ctx: &Context, guild_id: GuildId, spec: &RuleSpec) -> Result<()> {
+    let body = serde_json::to_vec(&spec.body())?;
+    let mut request = RequestBuilder::new(RouteInfo::CreateAutoModRule {
+        guild_id: guild_id.0,
+    });
+    request.body(Some(&body));
+    ctx.http.fire::<Value>(request.build()).await?;
+    Ok(())
+}
+
+/// The actions of a rule as Discord returns it, e.g. `block, alert #mods`.
+fn describe_live_actions(rule: &Value) -> String {
+    rule["actions"]
+        .as_array()
+        .map(|x| {
+            x.iter()
+                .map(|action| match action["type"].as_u64() {
+                    Some(1) => "block".to_string(),
+                    Some(2) => match action["metadata"]["channel_id"].as_str() {
+                        Some(channel) => format!("alert <#{}>", channel),
+                        None => "alert".to_string(),
+                    },
+                    Some(3) => format!(
+                        "timeout {}",
+                        format_duration(
+                            action["metadata"]["duration_seconds"]
+                                .as_i64()
+                                .unwrap_or_default()
+                        )
+                    ),
+                    _ => "unknown".to_string(),
+                })
+                .collect::<Vec<_>>()
+                .join(", ")
+        })
+        .unwrap_or_default()
+}
+
+fn describe_live(rule: &Value, managed: bool) -> String {
+    let trigger = trigger_name(rule["trigger_type"].as_u64().unwrap_or_default());
+    format!(
+        "{} **{}** ({}{}): {}{}",
+        if rule["enabled"].as_bool() == Some(true) {
+            "🟢"
+        } else {
+            "⚪"
+        },
+        rule["name"].as_str().unwrap_or_default(),
+        trigger,
+        match rule["trigger_metadata"]["mention_total_limit"].as_u64() {
+            Some(limit) => format!(", over {} mentions", limit),
+            None => String::new(),
+        },
+        describe_live_actions(rule),
+        if managed {
+            ""
+        } else {
+            " *(not in the policy)*"
+        }
+    )
+}
+
+// Discord sends one execution per action of a rule, they're logged once
+static LOGGED: OnceLock<Mutex<HashMap<u64, i64>>> = OnceLock::new();
+
+/// Mirrors what AutoMod did into the mod log.
+pub async fn action_executed(ctx: &Context, execution: ActionExecution) -> Result<()> {
+    let now = Timestamp::now().unix_timestamp();
+    let mut hasher = DefaultHasher::new();
+    (
+        execution.rule_id,
+        execution.user_id,
+        execution.channel_id,
+        &execution.content,
+    )
+        .hash(&mut hasher);
+    let key = hasher.finish();
+    {
+        let mut logged = LOGGED.get_or_init(Default::default).lock().unwrap();
+        logged.retain(|_, at| *at > now - 10);
+        if logged.insert(key, now).is_some() {
+            return Ok(());
+        }
+    }
+
+    let guild_id = execution.guild_id;
+    let channel_id = match moderation::mod_log_channel(ctx, guild_id).await? {
+        Some(channel_id) => channel_id,
+        None => return Ok(()),
+    };
+    let rule = live_rule(ctx, guild_id, execution.rule_id).await.ok();
+    let rule_name = rule
+        .as_ref()
+        .and_then(|x| x["name"].as_str())
+        .unwrap_or("deleted rule")
+        .to_string();
+    let trigger = match execution.trigger_type {
+        TriggerType::Unknown(id) => trigger_name(id.into()),
+        kind => trigger_name(u8::from(kind).into()),
+    };
+    let actions = match &rule {
+        Some(rule) => describe_live_actions(rule),
+        None => match execution.action {
+            Action::BlockMessage => "block".to_string(),
+            Action::Alert(channel) => format!("alert {}", channel.mention()),
+            Action::Timeout(duration) => {
+                format!("timeout {}", format_duration(duration.as_secs() as i64))
+            }
+            _ => "unknown".to_string(),
+        },
+    };
+
+    let db = ctx.get_db().await;
+    let warnings = db.count_warnings(guild_id, execution.user_id).await?;
+    let created_at = execution.user_id.created_at().unix_timestamp();
+    let mut lines = vec![
+        format!(
+            "🛡️ AutoMod rule **{}** ({}) caught {} (account created <t:{}:R>, {} warnings)",
+            rule_name,
+            trigger,
+            execution.user_id.mention(),
+            created_at,
+            warnings
+        ),
+        format!(
+            "Actions: {}{}",
+            actions,
+            match execution.channel_id {
+                Some(channel) => format!(" in {}", channel.mention()),
+                None => String::new(),
+            }
+        ),
+    ];
+    if let Some(keyword) = &execution.matched_keyword {
+        lines.push(format!(
+            "Matched `{}`{}",
+            keyword.replace('`', "'"),
+            match &execution.matched_content {
+                Some(content) => format!(" on `{}`", content.replace('`', "'")),
+                None => String::new(),
+            }
+        ));
+    }
+    if !execution.content.is_empty() {
+        lines.push(format!(
+            "> {}",
+            execution
+                .content
+                .replace('\n', " ")
+                .as_str()
+                .substring(0, 1000)
+        ));
+    }
+    channel_id
+        .send_message(&ctx.http, |m| {
+            m.content(lines.join("\n"))
+                .allowed_mentions(|a| a.empty_parse())
+        })
+        .await?;
+    Ok(())
+}
+
+/// `/automod`
+pub async fn responder(mci: &ApplicationCommandInteraction, ctx: &Context) {
+    let guild_id = mci.guild_id.unwrap();
+    let subcommand = match mci.data.options.first() {
+        Some(x) => x,
+        None => return,
+    };
+    mci.create_interaction_response(&ctx.http, |r| {
+        r.kind(InteractionResponseType::DeferredChannelMessageWithSource)
+            .interaction_response_data(|d| d.flags(MessageFlags::EPHEMERAL))
+    })
+    .await
+    .unwrap();
+    let db = ctx.get_db().await;
+
+    // `apply` and `diff` take a new policy, otherwise they use the saved one
+    let attachment = subcommand.options.iter().find_map(|x| match &x.resolved {
+        Some(CommandDataOptionValue::Attachment(a)) => Some(a),
+        _ => None,
+    });
+    let definition = match attachment {
+        Some(attachment) => match attachment
+            .download()
+            .await
+            .ok()
+            .and_then(|x| String::from_utf8(x).ok())
+        {
+            Some(definition) => Some(definition),
+            None => {
+                mci.edit_original_interaction_response(&ctx.http, |d| {
+                    d.content("Please attach the policy as a UTF-8 YAML file")
+                })
+                .await
+                .unwrap();
+                return;
+            }
+        },
+        None => db.get_automod_policy(guild_id).await.unwrap(),
+    };
+    let policy = match definition.as_deref().map(|x| {
+        serde_yaml::from_str::<RulePolicy>(x)
+            .map_err(anyhow::Error::from)
+            .and_then(|policy| policy.validate().map(|_| policy))
+    }) {
+        Some(Ok(policy)) => Some(policy),
+        Some(Err(e)) => {
+            mci.edit_original_interaction_response(&ctx.http, |d| {
+                d.content(format!("Invalid policy: {}", e))
+            })
+            .await
+            .unwrap();
+            return;
+        }
+        None => None,
+    };
+
+    let live = match live_rules(ctx, guild_id).await {
+        Ok(live) => live,
+        Err(e) => {
+            mci.edit_original_interaction_response(&ctx.http, |d| {
+                d.content(format!("Couldn't fetch the AutoMod rules: {}", e))
+            })
+            .await
+            .unwrap();
+            return;
+        }
+    };
+
+    let response = match (subcommand.name.as_str(), &policy) {
+        ("list", _) => {
+            let lines: Vec<String> = live
+                .iter()
+                .map(|rule| {
+                    let name = rule["name"].as_str().unwrap_or_default();
+                    let managed = policy.as_ref().is_some_and(|policy| {
+                        policy
+                            .rules
+                            .iter()
+                            .any(|x| x.name.eq_ignore_ascii_case(name))
+                    });
+                    describe_live(rule, managed)
+                })
+                .collect();
+            let yaml = definition.unwrap_or_else(|| EXAMPLE_POLICY.to_string());
+            mci.edit_original_interaction_response(&ctx.http, |d| {
+                d.content(
+                    format!(
+                        "**AutoMod rules**\n{}",
+                        if lines.is_empty() {
+                            "None".to_string()
+                        } else {
+                            lines.join("\n")
+                        }
+                    )
+                    .as_str()
+                    .substring(0, 2000),
+                )
+            })
+            .await
+            .unwrap();
+            mci.create_followup_message(&ctx.http, |d| {
+                d.add_file((yaml.as_bytes(), "automod.yaml"))
+                    .flags(MessageFlags::EPHEMERAL)
+            })
+            .await
+            .unwrap();
+            return;
+        }
+        (_, None) => {
+            "No policy yet, attach one, starting from the file `/automod list` gives".to_string()
+        }
+        ("diff", Some(policy)) => describe_plan(&plan(policy, &live)),
+        ("apply", Some(policy)) => {
+            let plan = plan(policy, &live);
+            let failures = execute_plan(ctx, guild_id, &plan).await;
+            if let Some(definition) = attachment.and(definition.as_deref()) {
+                db.set_automod_policy(guild_id, definition, Timestamp::now().unix_timestamp())
+                    .await
+                    .unwrap();
+            }
+            post_log(
+                ctx,
+                guild_id,
+                format!(
+                    "🛡️ {} applied the AutoMod policy, {} changes",
+                    mci.user.mention(),
+                    plan.changes.len() - failures.len()
+                ),
+            )
+            .await
+            .ok();
+            if failures.is_empty() {
+                format!("Applied\n{}", describe_plan(&plan))
+            } else {
+                format!(
+                    "Applied with errors\n{}\n\n**Failed**\n{}",
+                    describe_plan(&plan),
+                    failures.join("\n")
+                )
+            }
+        }
+        _ => format!("Unknown subcommand `{}`", subcommand.name),
+    };
+
+    mci.edit_original_interaction_response(&ctx.http, |d| {
+        d.content(response.as_str().substring(0, 2000))
+    })
+    .await
+    .unwrap();
+}
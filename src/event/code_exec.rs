@@ -0,0 +1,364 @@
+use super::*;
+use crate::db::Db;
+use crate::utils::piston;
+use serenity::builder::{CreateComponents, CreateEmbed};
+use serenity::model::{
+    application::interaction::{
+        application_command::{ApplicationCommandInteraction, CommandDataOptionValue},
+        message_component::MessageComponentInteraction,
+        MessageFlags,
+    },
+    event::MessageUpdateEvent,
+    Permissions, Timestamp,
+};
+
+// Each block gets an embed, Discord allows 10 per message
+const MAX_BLOCKS: usize = 5;
+// Discord's limit for all embeds of a message together
+const MAX_TOTAL_EMBED_CHARS: usize = 5500;
+
+impl Db {
+    pub async fn set_code_run(
+        &self,
+        message_id: MessageId,
+        channel_id: ChannelId,
+        result_message_id: MessageId,
+    ) -> Result<()> {
+        let message_id = message_id.0 as i64;
+        let channel_id = channel_id.0 as i64;
+        let result_message_id = result_message_id.0 as i64;
+        let created_at = Timestamp::now().unix_timestamp();
+        sqlx::query!(
+            "insert into code_runs (message_id, channel_id, result_message_id, created_at) values (?1, ?2, ?3, ?4) on conflict(message_id) do update set result_message_id=?3",
+            message_id,
+            channel_id,
+            result_message_id,
+            created_at
+        )
+        .execute(&self.sqlitedb)
+        .await?;
+        Ok(())
+    }
+
+    /// The bot's result message for a message whose code was run.
+    pub async fn get_code_run(&self, message_id: MessageId) -> Result<Option<MessageId>> {
+        let message_id = message_id.0 as i64;
+        let q = sqlx::query!(
+            "select result_message_id from code_runs where message_id=?",
+            message_id
+        )
+        .fetch_optional(&self.sqlitedb)
+        .await?;
+        Ok(q.map(|x| MessageId(x.result_message_id as u64)))
+    }
+}
+
+fn describe(runtime: &piston_rs::Runtime) -> String {
+    if runtime.aliases.is_empty() {
+        format!("**{}** {}", runtime.language, runtime.version)
+    } else {
+        format!(
+            "**{}** {} ({})",
+            runtime.language,
+            runtime.version,
+            runtime.aliases.join(", ")
+        )
+    }
+}
+
+/// `/runtimes`, the languages `exec` can run.
+pub async fn runtimes(mci: &ApplicationCommandInteraction, ctx: &Context) {
+    let filter = mci.data.options.iter().find_map(|x| match &x.resolved {
+        Some(CommandDataOptionValue::String(s)) if x.name == "language" => Some(s.to_lowercase()),
+        _ => None,
+    });
+    let (title, text) = match piston::runtimes().await {
+        Ok(mut runtimes) => {
+            runtimes.sort_by(|a, b| a.language.cmp(&b.language));
+            let lines: Vec<String> = match &filter {
+                Some(filter) => runtimes
+                    .iter()
+                    .filter(|x| {
+                        x.language.contains(filter.as_str())
+                            || piston::resolve(std::slice::from_ref(x), filter).is_some()
+                    })
+                    .map(describe)
+                    .collect(),
+                None => runtimes.iter().map(describe).collect(),
+            };
+            if lines.is_empty() {
+                (
+                    "Runtimes".to_string(),
+                    format!("Nothing matches `{}`", filter.unwrap_or_default()),
+                )
+            } else {
+                (format!("{} runtimes", lines.len()), lines.join("\n"))
+            }
+        }
+        Err(e) => (
+            "Runtimes".to_string(),
+            format!("Couldn't reach the code runner: {}", e),
+        ),
+    };
+
+    let plain = text.replace("**", "");
+    mci.create_interaction_response(&ctx.http, |r| {
+        r.kind(InteractionResponseType::ChannelMessageWithSource)
+            .interaction_response_data(|d| {
+                if text.chars().count() > 4096 {
+                    d.add_file((plain.as_bytes(), "runtimes.txt"))
+                        .content(title)
+                } else {
+                    d.embed(|e| {
+                        e.title(title)
+                            .description(&text)
+                            .footer(|f| f.text("Use an alias as the code block language"))
+                    })
+                }
+                .flags(MessageFlags::EPHEMERAL)
+            })
+    })
+    .await
+    .unwrap();
+}
+
+/// The results of every code block in a message, as embeds and the files too long for them.
+struct Rendered {
+    embeds: Vec<CreateEmbed>,
+    files: Vec<(Vec<u8>, String)>,
+}
+
+/// Runs each fenced block of the message, a block tagged `stdin` is the input for all of them.
+async fn run_blocks(content: &str) -> Option<Rendered> {
+    let blocks = piston::code_blocks(content);
+    let stdin = blocks
+        .iter()
+        .find(|x| x.lang == "stdin")
+        .map(|x| x.code.clone())
+        .unwrap_or_default();
+    let blocks: Vec<_> = blocks
+        .into_iter()
+        .filter(|x| x.lang != "stdin")
+        .take(MAX_BLOCKS)
+        .collect();
+    if blocks.is_empty() {
+        return None;
+    }
+
+    let budget = (MAX_TOTAL_EMBED_CHARS / blocks.len()).min(piston::MAX_EMBED_OUTPUT);
+    let mut rendered = Rendered {
+        embeds: Vec::new(),
+        files: Vec::new(),
+    };
+    for (i, block) in blocks.iter().enumerate() {
+        let mut embed = CreateEmbed::default();
+        if block.lang.is_empty() {
+            embed
+                .description("This block has no language, tag it like \\`\\`\\`python")
+                .color(0xe74c3c);
+            rendered.embeds.push(embed);
+            continue;
+        }
+        let file = piston_rs::File::default().set_content(&block.code);
+        match piston::run(&block.lang, vec![file], &stdin, &[]).await {
+            Ok(run) => {
+                if run.output.is_empty() {
+                    embed.description("No output");
+                } else if run.output.chars().count() > budget {
+                    let filename = format!("output-{}.txt", i + 1);
+                    embed.description(format!(
+                        "The output is too long, it's attached as `{}`",
+                        filename
+                    ));
+                    rendered.files.push((run.output.into_bytes(), filename));
+                } else {
+                    embed.description(format!("```{}\n{}```", block.lang, run.output));
+                }
+                embed
+                    .footer(|f| f.text(format!("{} {}", run.language, run.version)))
+                    .color(if run.success { 0x2ecc71 } else { 0xe74c3c });
+            }
+            Err(e) => {
+                embed.description(format!("Error: {}", e)).color(0xe74c3c);
+            }
+        }
+        rendered.embeds.push(embed);
+    }
+    Some(rendered)
+}
+
+fn rerun_button(message_id: MessageId) -> CreateComponents {
+    let mut components = CreateComponents::default();
+    components.create_action_row(|ar| {
+        ar.create_button(|b| {
+            b.custom_id(format!("run_code:{}", message_id))
+                .label("Re-run")
+                .style(ButtonStyle::Secondary)
+                .emoji(ReactionType::Unicode("🔁".to_string()))
+        })
+    });
+    components
+}
+
+/// Replaces the previous result, including any output files it had.
+async fn update_result(
+    ctx: &Context,
+    channel_id: ChannelId,
+    result_id: MessageId,
+    message_id: MessageId,
+    rendered: &Rendered,
+) -> Result<()> {
+    let previous = channel_id.message(&ctx.http, result_id).await?;
+    channel_id
+        .edit_message(&ctx.http, result_id, |m| {
+            // An empty list of kept attachments drops the old output files
+            for attachment in &previous.attachments {
+                m.add_existing_attachment(attachment.id)
+                    .remove_existing_attachment(attachment.id);
+            }
+            for (data, filename) in &rendered.files {
+                m.attachment((data.as_slice(), filename.as_str()));
+            }
+            m.set_embeds(rendered.embeds.clone())
+                .set_components(rerun_button(message_id))
+        })
+        .await?;
+    Ok(())
+}
+
+fn is_not_found(error: &anyhow::Error) -> bool {
+    matches!(
+        error.downcast_ref::<serenity::Error>(),
+        Some(serenity::Error::Http(e)) if e.status_code() == Some(reqwest::StatusCode::NOT_FOUND)
+    )
+}
+
+/// Runs the message's code, updating its earlier result when there is one.
+async fn run_message(ctx: &Context, msg: &Message) -> Result<Option<MessageId>> {
+    let rendered = match run_blocks(&msg.content).await {
+        Some(rendered) => rendered,
+        None => return Ok(None),
+    };
+    let db = ctx.get_db().await;
+    if let Some(result_id) = db.get_code_run(msg.id).await? {
+        // Falls through to posting a new result when the old one was deleted
+        match update_result(ctx, msg.channel_id, result_id, msg.id, &rendered).await {
+            Ok(_) => return Ok(Some(result_id)),
+            Err(e) if is_not_found(&e) => {}
+            Err(e) => return Err(e),
+        }
+    }
+    let result = msg
+        .channel_id
+        .send_message(&ctx.http, |m| {
+            m.reference_message(msg)
+                .allowed_mentions(|a| a.empty_parse().replied_user(false));
+            for (data, filename) in &rendered.files {
+                m.add_file((data.as_slice(), filename.as_str()));
+            }
+            m.set_embeds(rendered.embeds)
+                .set_components(rerun_button(msg.id))
+        })
+        .await?;
+    db.set_code_run(msg.id, msg.channel_id, result.id).await?;
+    Ok(Some(result.id))
+}
+
+/// The "Run code" message context menu.
+pub async fn run_from_menu(mci: &ApplicationCommandInteraction, ctx: &Context) {
+    mci.create_interaction_response(&ctx.http, |r| {
+        r.kind(InteractionResponseType::DeferredChannelMessageWithSource)
+            .interaction_response_data(|d| d.flags(MessageFlags::EPHEMERAL))
+    })
+    .await
+    .unwrap();
+    let msg = match mci.data.resolved.messages.values().next() {
+        Some(msg) => msg,
+        None => return,
+    };
+    let response = match run_message(ctx, msg).await {
+        Ok(Some(result_id)) => format!(
+            "Done: https://discord.com/channels/{}/{}/{}",
+            mci.guild_id.unwrap(),
+            msg.channel_id,
+            result_id
+        ),
+        Ok(None) => "That message has no fenced code blocks".to_string(),
+        Err(e) => format!("Couldn't post the result: {}", e),
+    };
+    mci.edit_original_interaction_response(&ctx.http, |d| d.content(response))
+        .await
+        .unwrap();
+}
+
+/// The Re-run button under a result.
+pub async fn handle_button(mci: &MessageComponentInteraction, ctx: &Context) {
+    let message_id = match mci
+        .data
+        .custom_id
+        .trim_start_matches("run_code:")
+        .parse::<u64>()
+    {
+        Ok(id) => MessageId(id),
+        Err(_) => return,
+    };
+    let msg = match mci.channel_id.message(&ctx.http, message_id).await {
+        Ok(msg) => msg,
+        Err(_) => {
+            mci.create_interaction_response(&ctx.http, |r| {
+                r.kind(InteractionResponseType::ChannelMessageWithSource)
+                    .interaction_response_data(|d| {
+                        d.content("The code's message is gone")
+                            .flags(MessageFlags::EPHEMERAL)
+                    })
+            })
+            .await
+            .unwrap();
+            return;
+        }
+    };
+    // Re-running costs a trip to the code runner, so it's up to the author and moderators
+    let moderator = mci
+        .member
+        .as_ref()
+        .and_then(|x| x.permissions)
+        .is_some_and(Permissions::manage_messages);
+    if msg.author.id != mci.user.id && !moderator {
+        mci.create_interaction_response(&ctx.http, |r| {
+            r.kind(InteractionResponseType::ChannelMessageWithSource)
+                .interaction_response_data(|d| {
+                    d.content("Only the author of the code can re-run it")
+                        .flags(MessageFlags::EPHEMERAL)
+                })
+        })
+        .await
+        .unwrap();
+        return;
+    }
+    mci.create_interaction_response(&ctx.http, |r| {
+        r.kind(InteractionResponseType::DeferredUpdateMessage)
+    })
+    .await
+    .unwrap();
+    if let Err(e) = run_message(ctx, &msg).await {
+        eprintln!("Failed to re-run the code of {}: {}", msg.id, e);
+    }
+}
+
+/// Keeps a result up to date when the author edits their code.
+pub async fn message_edited(ctx: &Context, event: &MessageUpdateEvent) {
+    if event.content.is_none() || event.author.as_ref().is_some_and(|x| x.bot) {
+        return;
+    }
+    let db = ctx.get_db().await;
+    if !matches!(db.get_code_run(event.id).await, Ok(Some(_))) {
+        return;
+    }
+    let msg = match event.channel_id.message(&ctx.http, event.id).await {
+        Ok(msg) => msg,
+        Err(_) => return,
+    };
+    if let Err(e) = run_message(ctx, &msg).await {
+        eprintln!("Failed to re-run the code of {}: {}", msg.id, e);
+    }
+}
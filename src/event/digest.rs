@@ -0,0 +1,249 @@
+use super::*;
+use crate::db::{ClientContextExt, Db};
+use chrono::{TimeZone, Utc};
+use cron::Schedule;
+use serenity::builder::CreateEmbed;
+use serenity::model::{
+    application::interaction::{
+        application_command::{ApplicationCommandInteraction, CommandDataOptionValue},
+        MessageFlags,
+    },
+    Timestamp,
+};
+use serenity::utils::MessageBuilder;
+use std::str::FromStr;
+use substr::StringUtils;
+
+const DIGEST_PERIOD_SECS: i64 = 7 * 86400;
+const OLDEST_UNANSWERED_LIMIT: i64 = 10;
+
+pub struct ChannelDigest {
+    pub channel_id: i64,
+    pub new_count: i64,
+    pub closed_count: i64,
+    pub open_count: i64,
+}
+
+pub struct UnansweredThread {
+    pub thread_id: i64,
+    pub created_at: i64,
+}
+
+impl Db {
+    pub async fn get_channel_digests(
+        &self,
+        guild_id: GuildId,
+        since: i64,
+    ) -> Result<Vec<ChannelDigest>> {
+        let guild_id = guild_id.0 as i64;
+        let q = sqlx::query_as!(
+            ChannelDigest,
+            r#"select channel_id,
+                coalesce(sum(created_at >= ?2), 0) as "new_count!: i64",
+                coalesce(sum(closed_at >= ?2), 0) as "closed_count!: i64",
+                coalesce(sum(closed_at is null), 0) as "open_count!: i64"
+            from question_threads where guild_id=?1 group by channel_id"#,
+            guild_id,
+            since
+        )
+        .fetch_all(&self.sqlitedb)
+        .await?;
+        Ok(q)
+    }
+
+    pub async fn get_oldest_unanswered(
+        &self,
+        guild_id: GuildId,
+        limit: i64,
+    ) -> Result<Vec<UnansweredThread>> {
+        let guild_id = guild_id.0 as i64;
+        let q = sqlx::query_as!(
+            UnansweredThread,
+            "select thread_id, created_at from question_threads where guild_id=? and first_response_at is null and closed_at is null order by created_at limit ?",
+            guild_id,
+            limit
+        )
+        .fetch_all(&self.sqlitedb)
+        .await?;
+        Ok(q)
+    }
+}
+
+/// Parses a cron expression in UTC, the seconds field is optional.
+pub fn parse_schedule(expr: &str) -> Result<Schedule> {
+    let expr = if expr.split_whitespace().count() == 5 {
+        format!("0 {}", expr)
+    } else {
+        expr.to_string()
+    };
+    Ok(Schedule::from_str(&expr)?)
+}
+
+pub async fn build_digest(ctx: &Context, guild_id: GuildId) -> Result<CreateEmbed> {
+    let db = ctx.get_db().await;
+    let now = Timestamp::now().unix_timestamp();
+    let digests = db
+        .get_channel_digests(guild_id, now - DIGEST_PERIOD_SECS)
+        .await?;
+    let unanswered = db
+        .get_oldest_unanswered(guild_id, OLDEST_UNANSWERED_LIMIT)
+        .await?;
+
+    let mut text = MessageBuilder::new();
+    text.push_bold_line("Questions in the last 7 days");
+    if digests.is_empty() {
+        text.push_line("No questions were tracked yet");
+    }
+    for digest in &digests {
+        text.push_line(format!(
+            "{} — {} new · {} closed · {} still open",
+            ChannelId(digest.channel_id as u64).mention(),
+            digest.new_count,
+            digest.closed_count,
+            digest.open_count
+        ));
+    }
+
+    text.push_line("")
+        .push_bold_line("Oldest unanswered questions");
+    if unanswered.is_empty() {
+        text.push_line("None, great job everyone 🎉");
+    }
+    for thread in &unanswered {
+        text.push_line(format!(
+            "• {} ([jump](https://discord.com/channels/{}/{})) — asked <t:{}:R>",
+            ChannelId(thread.thread_id as u64).mention(),
+            guild_id,
+            thread.thread_id,
+            thread.created_at
+        ));
+    }
+
+    let mut embed = CreateEmbed::default();
+    embed
+        .title("Weekly questions digest")
+        .description(text.build().as_str().substring(0, 4096))
+        .timestamp(Timestamp::now());
+    Ok(embed)
+}
+
+async fn post_if_due(ctx: &Context, guild_id: GuildId, expr: &str) -> Result<()> {
+    let db = ctx.get_db().await;
+    let schedule = parse_schedule(expr)?;
+    let now = Utc::now();
+    let last_run = match db
+        .get_guild_config_as::<i64>(guild_id, "digest_last_run")
+        .await?
+        .and_then(|x| Utc.timestamp_opt(x, 0).single())
+    {
+        Some(last_run) => last_run,
+        None => {
+            // Start counting from now
+            db.set_guild_config(guild_id, "digest_last_run", &now.timestamp().to_string())
+                .await?;
+            return Ok(());
+        }
+    };
+
+    if schedule.after(&last_run).next().is_some_and(|x| x <= now) {
+        db.set_guild_config(guild_id, "digest_last_run", &now.timestamp().to_string())
+            .await?;
+        if let Some(channel_id) = db
+            .get_guild_config_as::<ChannelId>(guild_id, "digest_channel")
+            .await?
+        {
+            let embed = build_digest(ctx, guild_id).await?;
+            channel_id
+                .send_message(&ctx.http, |m| m.set_embed(embed))
+                .await?;
+        }
+    }
+    Ok(())
+}
+
+/// Checks every minute for guilds whose digest schedule is due.
+pub async fn run_schedule(ctx: Context) {
+    loop {
+        let db = ctx.get_db().await;
+        if let Ok(schedules) = db.get_guild_configs("digest_schedule").await {
+            for (guild_id, expr) in schedules {
+                if let Err(e) = post_if_due(&ctx, guild_id, &expr).await {
+                    eprintln!("Failed to post the digest for {}: {}", guild_id, e);
+                }
+            }
+        }
+        tokio::time::sleep(Duration::from_secs(60)).await;
+    }
+}
+
+pub async fn responder(mci: &ApplicationCommandInteraction, ctx: &Context) {
+    let guild_id = mci.guild_id.unwrap();
+    let subcommand = match mci.data.options.first() {
+        Some(x) => x,
+        None => return,
+    };
+
+    match subcommand.name.as_str() {
+        "now" => {
+            let embed = build_digest(ctx, guild_id).await.unwrap();
+            mci.create_interaction_response(&ctx.http, |r| {
+                r.kind(InteractionResponseType::ChannelMessageWithSource)
+                    .interaction_response_data(|d| d.add_embed(embed))
+            })
+            .await
+            .unwrap();
+        }
+        "configure" => {
+            let mut channel_id: Option<ChannelId> = None;
+            let mut expr = String::new();
+            for option in &subcommand.options {
+                match (option.name.as_str(), &option.resolved) {
+                    ("channel", Some(CommandDataOptionValue::Channel(c))) => {
+                        channel_id = Some(c.id)
+                    }
+                    ("schedule", Some(CommandDataOptionValue::String(s))) => {
+                        expr = s.trim().to_string()
+                    }
+                    _ => {}
+                }
+            }
+
+            let response = match (channel_id, parse_schedule(&expr)) {
+                (Some(channel_id), Ok(schedule)) => {
+                    let db = ctx.get_db().await;
+                    let now = Timestamp::now().unix_timestamp().to_string();
+                    db.set_guild_config(guild_id, "digest_channel", &channel_id.to_string())
+                        .await
+                        .unwrap();
+                    db.set_guild_config(guild_id, "digest_schedule", &expr)
+                        .await
+                        .unwrap();
+                    db.set_guild_config(guild_id, "digest_last_run", &now)
+                        .await
+                        .unwrap();
+                    let next = schedule
+                        .upcoming(Utc)
+                        .next()
+                        .map(|x| format!("<t:{}:F>", x.timestamp()))
+                        .unwrap_or_else(|| "never".to_string());
+                    format!(
+                        "The digest will be posted in {}, next run: {}",
+                        channel_id.mention(),
+                        next
+                    )
+                }
+                (_, Err(e)) => format!("Invalid schedule `{}`: {}", expr, e),
+                (None, _) => "Please pick a channel".to_string(),
+            };
+            mci.create_interaction_response(&ctx.http, |r| {
+                r.kind(InteractionResponseType::ChannelMessageWithSource)
+                    .interaction_response_data(|d| {
+                        d.content(response).flags(MessageFlags::EPHEMERAL)
+                    })
+            })
+            .await
+            .unwrap();
+        }
+        _ => {}
+    }
+}
@@ -1,74 +1,127 @@
 use super::*;
+use crate::db::ClientContextExt;
+use serenity::model::{id::RoleId, Timestamp};
 
-pub async fn responder(_ctx: Context, _guild_id: GuildId, _new_member: Member) {
-    let user_date = _new_member.user.created_at().date().format("%a, %B %e, %Y");
-    let user_time = _new_member.user.created_at().time().format("%H:%M:%S %p");
-    let _system_channel_id = _ctx
-        .cache
-        .guild(&_guild_id)
+/// Role ids from `gp config restore_excluded_roles`, never given back on rejoin.
+async fn excluded_roles(ctx: &Context, guild_id: GuildId) -> Vec<RoleId> {
+    let db = ctx.get_db().await;
+    db.get_guild_config(guild_id, "restore_excluded_roles")
         .await
-        .map(|x| x.system_channel_id)
-        .unwrap()
-        .unwrap();
+        .ok()
+        .flatten()
+        .map(|x| x.split(',').filter_map(|x| x.trim().parse().ok()).collect())
+        .unwrap_or_default()
+}
 
-    let intro = english_gen(1, 1);
+/// Gives a returning member their saved roles back, and lets them skip "Let's go"
+/// when they went through it before.
+async fn restore_roles(ctx: &Context, guild_id: GuildId, member: &mut Member) -> Result<()> {
+    let db = ctx.get_db().await;
+    let completed = db
+        .has_completed_onboarding(guild_id, member.user.id)
+        .await?;
+    let saved = db
+        .get_user_roles(member.user.id)
+        .await?
+        .map(|x| x.roles)
+        .unwrap_or_default();
+    if !completed && saved.is_empty() {
+        return Ok(());
+    }
 
-    _ctx.http
-        .send_message(
-            u64::try_from(_system_channel_id).unwrap(),
-            &json!({
-                "content":
-                    format!(
-                        "> :arrow_forward: {} _{}_  **{}** came in (reg Date: **{}**; Time: **{}**)",
-                        vowel_gen(&intro),
-                        &intro,
-                        _new_member.display_name(),
-                        &user_date,
-                        &user_time
-                    )
-            }),
-        )
-        .await
-        .unwrap();
+    let excluded = excluded_roles(ctx, guild_id).await;
+    let guild_roles = guild_id.roles(&ctx.http).await?;
+    let mut restored: Vec<RoleId> = saved
+        .into_iter()
+        .filter(|x| !excluded.contains(x))
+        // Saved roles aren't per guild, and may have been deleted since
+        .filter(|x| guild_roles.get(x).is_some_and(|r| !r.managed))
+        .collect();
+    if completed {
+        if let Some(role) = guild_roles.values().find(|x| x.name == "Member") {
+            if !excluded.contains(&role.id) && !restored.contains(&role.id) {
+                restored.push(role.id);
+            }
+        }
+    }
+    if restored.is_empty() {
+        return Ok(());
+    }
+    member.add_roles(&ctx.http, &restored).await?;
 
-    let jailbreak_channel = _ctx
-        .cache
-        .guild(_guild_id)
-        .await
-        .unwrap()
-        .channel_id_from_name(&_ctx.cache, "waiting-lobby")
-        .await;
+    let names = restored
+        .iter()
+        .filter_map(|x| guild_roles.get(x))
+        .map(|x| x.name.as_str())
+        .collect::<Vec<_>>()
+        .join(", ");
+    println!(
+        "Restored roles of {} ({}) in {}: {}",
+        member.user.tag(),
+        member.user.id,
+        guild_id,
+        names
+    );
+    post_log(
+        ctx,
+        guild_id,
+        format!(
+            "> :leftwards_arrow_with_hook: {} (**{}**) rejoined, restored: {}",
+            member.user.mention(),
+            member.user.tag(),
+            restored
+                .iter()
+                .map(|x| x.mention().to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+    )
+    .await?;
+    Ok(())
+}
 
-    if jailbreak_channel.is_some() {
-        jailbreak_channel
-            .unwrap()
-            .send_message(&_ctx.http, |x| x.content(format!("> {} welcome to our server, you will be given full access after a few minutes as the verification process proceeds, wait patiently..", _new_member.mention())))
-            .await
-            .unwrap();
+pub async fn responder(_ctx: Context, _guild_id: GuildId, mut _new_member: Member) {
+    if let Err(e) = raid::member_joined(&_ctx, &_new_member).await {
+        eprintln!("Raid protection failed in {}: {}", _guild_id, e);
     }
+    name_history::member_updated(&_ctx, &_new_member).await;
 
-    let blacklist = format!(
-        "{}/db/blacklisted_names",
-        env::current_exe()
-            .unwrap()
-            .parent()
-            .unwrap()
-            .to_string_lossy()
-    );
+    let passed = match screening::screen_member(&_ctx, &_new_member, "join").await {
+        Ok(action) => action.is_none_or(|x| x == screening::ScreenAction::Flag),
+        Err(e) => {
+            eprintln!(
+                "Failed to screen the name of {} in {}: {}",
+                _new_member.user.id, _guild_id, e
+            );
+            false
+        }
+    };
 
-    if path::Path::new(&blacklist).exists() {
-        let blacklist = fs::read_to_string(blacklist).await.unwrap();
+    // Only members who passed screening are welcomed and get their roles back, and not
+    // while a raid is handled
+    let welcome = passed && !raid::is_locked_down(&_ctx, _guild_id).await;
+    announcements::member_joined(&_ctx, &_new_member, welcome).await;
 
-        if blacklist.contains(&_new_member.display_name().to_ascii_uppercase()) {
-            _new_member
-                .user
-                .direct_message(&_ctx.http, |m| m.content("Lacks a Brain"))
-                .await
-                .unwrap();
-            _new_member
-                .ban_with_reason(&_ctx.http, 0, "Missing Brain.exe")
-                .await
-                .unwrap();
+    if !_new_member.user.bot && welcome {
+        if let Err(e) = restore_roles(&_ctx, _guild_id, &mut _new_member).await {
+            eprintln!(
+                "Failed to restore the roles of {} in {}: {}",
+                _new_member.user.id, _guild_id, e
+            );
         }
     }
 }
+
+/// Members who left with the "Member" role went through onboarding before,
+/// possibly before completions were recorded.
+pub async fn remember_completion(ctx: &Context, guild_id: GuildId, member: &Member) {
+    let is_member = member
+        .roles(&ctx.cache)
+        .is_some_and(|roles| roles.iter().any(|x| x.name == "Member"));
+    if is_member {
+        let db = ctx.get_db().await;
+        db.set_onboarding_completed(guild_id, member.user.id, Timestamp::now().unix_timestamp())
+            .await
+            .ok();
+    }
+}
@@ -8,16 +8,16 @@ use meilisearch_sdk::{client::Client as MeiliClient, settings::Settings};
 use serde::{Deserialize, Serialize};
 
 use serenity::{
-    futures::StreamExt,
     // http::AttachmentType,
     model::{
-        self,
-        application::interaction::{message_component::MessageComponentInteraction, MessageFlags},
+        application::interaction::{
+            message_component::MessageComponentInteraction, modal::ModalSubmitInteraction,
+            MessageFlags,
+        },
         channel::{AttachmentType, Embed},
-        guild::{Emoji, Role},
-        id::RoleId,
+        guild::Emoji,
         prelude::component::Button,
-        Permissions,
+        Timestamp,
     },
     utils::{read_image, MessageBuilder},
 };
@@ -32,14 +32,6 @@ struct Thread {
     title: String,
     history: String,
 }
-#[derive(Clone, Copy)]
-struct SelectMenuSpec<'a> {
-    value: &'a str,
-    label: &'a str,
-    display_emoji: &'a str,
-    description: &'a str,
-}
-
 const SELF_HOSTED_TEXT: &str = "self-hosted-questions";
 const SELF_HOSTED_KUBECTL_COMMAND_PLACEHOLDER: &str = "# Run: kubectl get pods -n <namespace>";
 
@@ -55,43 +47,6 @@ async fn safe_text(_ctx: &Context, _input: &String) -> String {
     )
 }
 
-async fn get_role(
-    mci: &model::application::interaction::message_component::MessageComponentInteraction,
-    ctx: &Context,
-    name: &str,
-) -> Role {
-    let role = {
-        if let Some(result) = mci
-            .guild_id
-            .unwrap()
-            .to_guild_cached(&ctx.cache)
-            .unwrap()
-            .role_by_name(name)
-        {
-            result.clone()
-        } else {
-            let r = mci
-                .guild_id
-                .unwrap()
-                .create_role(&ctx.http, |r| {
-                    r.name(&name);
-                    r.mentionable(false);
-                    r.hoist(false);
-                    r
-                })
-                .await
-                .unwrap();
-            r.clone()
-        }
-    };
-    if role.name != "Member" && role.name != "Gitpodders" && !role.permissions.is_empty() {
-        role.edit(&ctx.http, |r| r.permissions(Permissions::empty()))
-            .await
-            .unwrap();
-    }
-    role
-}
-
 async fn save_and_fetch_links(
     sites: &[&str],
     thread_id: u64,
@@ -216,7 +171,12 @@ async fn close_issue(mci: &MessageComponentInteraction, ctx: &Context) {
         }
     };
     let action_user_mention = mci.member.as_ref().unwrap().mention();
-    let response = format!("This {} was closed by {}", thread_type, action_user_mention);
+    let locale = i18n::guild_locale(ctx, mci.guild_id).await;
+    let response = tr_args(
+        locale,
+        &format!("{}-closed-by", thread_type),
+        &[("user", action_user_mention.to_string())],
+    );
     mci.channel_id.say(&ctx.http, &response).await.unwrap();
     mci.create_interaction_response(&ctx.http, |r| {
         r.kind(InteractionResponseType::UpdateMessage);
@@ -225,47 +185,21 @@ async fn close_issue(mci: &MessageComponentInteraction, ctx: &Context) {
     .await
     .unwrap();
 
+    let db = &ctx.get_db().await;
+    db.close_question_thread(mci.channel_id, Timestamp::now().unix_timestamp())
+        .await
+        .ok();
+    db.close_private_ticket(mci.channel_id, Timestamp::now().unix_timestamp())
+        .await
+        .ok();
+
     mci.channel_id
         .edit_thread(&ctx.http, |t| t.archived(true).name(thread_name))
         .await
         .unwrap();
 }
 
-async fn assign_roles(
-    mci: &MessageComponentInteraction,
-    ctx: &Context,
-    role_choices: Vec<String>,
-    member: &mut Member,
-    temp_role: &Role,
-    member_role: &Role,
-) {
-    if role_choices.len() > 1 || !role_choices.iter().any(|x| x == "none") {
-        // Is bigger than a single choice or doesnt contain none
-
-        let mut role_ids: Vec<RoleId> = Vec::new();
-        for role_name in role_choices {
-            if role_name == "none" {
-                continue;
-            }
-            let role = get_role(mci, ctx, role_name.as_str()).await;
-            role_ids.push(role.id);
-        }
-        member.add_roles(&ctx.http, &role_ids).await.unwrap();
-        let db = &ctx.get_db().await;
-        db.set_user_roles(mci.user.id, role_ids).await.unwrap();
-    }
-
-    // Remove the temp role from user
-    if member.roles.iter().any(|x| x == &temp_role.id) {
-        member.remove_role(&ctx.http, temp_role.id).await.unwrap();
-    }
-    // Add member role if missing
-    if !member.roles.iter().any(|x| x == &member_role.id) {
-        member.add_role(&ctx.http, member_role.id).await.unwrap();
-    }
-}
-
-async fn show_issue_form(mci: &MessageComponentInteraction, ctx: &Context) {
+async fn show_issue_form(mci: &MessageComponentInteraction, ctx: &Context, private: bool) {
     let db = &ctx.get_db().await;
     let desc = {
         if let Ok(result) = db
@@ -282,18 +216,23 @@ async fn show_issue_form(mci: &MessageComponentInteraction, ctx: &Context) {
     };
 
     let channel_name = mci.channel_id.name(&ctx.cache).await.unwrap();
+    let locale = i18n::user_locale(ctx, mci.guild_id, &mci.locale).await;
     mci.create_interaction_response(&ctx, |r| {
         r.kind(InteractionResponseType::Modal);
         r.interaction_response_data(|d| {
-            d.custom_id("gitpod_help_button_press");
-            d.title("Template");
+            d.custom_id(if private {
+                "gitpod_private_help_button_press"
+            } else {
+                "gitpod_help_button_press"
+            });
+            d.title(tr(locale, "question-form-title"));
             d.components(|c| {
                 c.create_action_row(|ar| {
                     ar.create_input_text(|it| {
                         it.style(InputTextStyle::Short)
                             .custom_id("input_title")
                             .required(true)
-                            .label("Title")
+                            .label(tr(locale, "question-form-title-label"))
                             .max_length(98)
                     })
                 });
@@ -301,7 +240,7 @@ async fn show_issue_form(mci: &MessageComponentInteraction, ctx: &Context) {
                     ar.create_input_text(|it| {
                         it.style(InputTextStyle::Paragraph)
                             .custom_id("input_description")
-                            .label("Description")
+                            .label(tr(locale, "question-form-description-label"))
                             .required(true)
                             .max_length(4000)
                             .value(desc)
@@ -312,13 +251,13 @@ async fn show_issue_form(mci: &MessageComponentInteraction, ctx: &Context) {
                         if channel_name != SELF_HOSTED_TEXT {
                             it.style(InputTextStyle::Short)
                                 .custom_id("input_workspace")
-                                .label("Workspace affected")
+                                .label(tr(locale, "question-form-workspace-label"))
                                 .required(false)
                                 .max_length(100)
                         } else {
                             it.style(InputTextStyle::Paragraph)
                                 .custom_id("input_config_yaml")
-                                .label("Your config.yaml contents")
+                                .label(tr(locale, "question-form-config-yaml-label"))
                                 .required(false)
                                 .max_length(1000)
                         }
@@ -329,13 +268,13 @@ async fn show_issue_form(mci: &MessageComponentInteraction, ctx: &Context) {
                         if channel_name != SELF_HOSTED_TEXT {
                             it.style(InputTextStyle::Short)
                                 .custom_id("input_example_repo")
-                                .label("Example repo")
+                                .label(tr(locale, "question-form-example-repo-label"))
                                 .required(false)
                                 .max_length(100)
                         } else {
                             it.style(InputTextStyle::Paragraph)
                                 .custom_id("input_kubectl_result")
-                                .label("Result of `kubectl get pods -n <namespace>`")
+                                .label(tr(locale, "question-form-kubectl-label"))
                                 .required(false)
                                 .max_length(1000)
                                 .value(SELF_HOSTED_KUBECTL_COMMAND_PLACEHOLDER)
@@ -349,513 +288,210 @@ async fn show_issue_form(mci: &MessageComponentInteraction, ctx: &Context) {
     .unwrap();
 }
 
+/// The question template, as filled in the form, posted in its thread.
+async fn send_question_details(
+    ctx: &Context,
+    thread_id: ChannelId,
+    locale: &str,
+    channel_name: &str,
+    description: &str,
+    optional_one: &str,
+    optional_two: &str,
+) {
+    let desc_safe = safe_text(ctx, &description.to_string()).await;
+    thread_id
+        .send_message(&ctx.http, |m| {
+            if description.chars().count() < 1960 {
+                m.content(
+                    MessageBuilder::new()
+                        .push_underline_line(format!(
+                            "**{}**",
+                            tr(locale, "question-description-heading")
+                        ))
+                        .push_line(&desc_safe)
+                        .push_bold("---------------")
+                        .build(),
+                );
+            } else {
+                m.add_embed(|e| {
+                    e.title(tr(locale, "question-description-heading"))
+                        .description(desc_safe)
+                });
+            }
+            if channel_name != SELF_HOSTED_TEXT {
+                if !optional_one.is_empty() || !optional_two.is_empty() {
+                    m.add_embed(|e| {
+                        if !optional_one.is_empty() {
+                            e.field(tr(locale, "question-workspace-field"), optional_one, false);
+                        }
+                        if !optional_two.is_empty() {
+                            e.field(
+                                tr(locale, "question-example-repo-field"),
+                                optional_two,
+                                false,
+                            );
+                        }
+                        e
+                    });
+                }
+            } else if channel_name == SELF_HOSTED_TEXT {
+                if !optional_one.is_empty() {
+                    m.add_embed(|e| {
+                        e.title(tr(locale, "question-config-yaml-heading"))
+                            .description(format!("```yaml\n{}\n```", optional_one))
+                    });
+                }
+                if optional_two != SELF_HOSTED_KUBECTL_COMMAND_PLACEHOLDER
+                    && !optional_two.is_empty()
+                {
+                    m.add_embed(|e| {
+                        e.title(tr(locale, "question-kubectl-heading"))
+                            .description(format!("```javascript\n{}\n```", optional_two))
+                    });
+                }
+            }
+
+            m
+        })
+        .await
+        .unwrap();
+}
+
+/// The greeting with the close button that ends every question thread setup.
+async fn send_question_welcome(
+    ctx: &Context,
+    thread_id: ChannelId,
+    locale: &str,
+    greeting: String,
+) {
+    thread_id
+        .send_message(&ctx, |m| {
+            m.content(MessageBuilder::new().push_quote(greeting).build())
+                .components(|c| {
+                    c.create_action_row(|ar| {
+                        ar.create_button(|button| {
+                            button
+                                .style(ButtonStyle::Danger)
+                                .label(tr(locale, "question-close-button"))
+                                .custom_id("gitpod_close_issue")
+                                .emoji(ReactionType::Unicode("🔒".to_string()))
+                        })
+                    })
+                })
+        })
+        .await
+        .unwrap();
+}
+
+/// The private variant of a question, a thread only the asker and the support role see.
+async fn ask_privately(
+    mci: &ModalSubmitInteraction,
+    ctx: &Context,
+    title: &str,
+    description: &str,
+    optional_one: &str,
+    optional_two: &str,
+) {
+    mci.create_interaction_response(&ctx.http, |r| {
+        r.kind(InteractionResponseType::DeferredChannelMessageWithSource)
+            .interaction_response_data(|d| d.flags(MessageFlags::EPHEMERAL))
+    })
+    .await
+    .unwrap();
+    let user_locale = i18n::user_locale(ctx, mci.guild_id, &mci.locale).await;
+    let thread = match private_tickets::open(ctx, mci, title).await {
+        Ok(thread) => thread,
+        Err(e) => {
+            eprintln!("Failed to open a private ticket for {}: {}", mci.user.id, e);
+            mci.edit_original_interaction_response(&ctx.http, |d| {
+                d.content(tr(user_locale, "private-ticket-failed"))
+            })
+            .await
+            .unwrap();
+            return;
+        }
+    };
+
+    let locale = i18n::guild_locale(ctx, mci.guild_id).await;
+    let channel_name = mci.channel_id.name(&ctx.cache).await.unwrap();
+    send_question_details(
+        ctx,
+        thread.id,
+        locale,
+        &channel_name,
+        description,
+        optional_one,
+        optional_two,
+    )
+    .await;
+    // Mentioning the role is what adds its members to the private thread
+    let greeting = match private_tickets::get_support_role(ctx, mci.guild_id.unwrap()).await {
+        Some(role_id) => tr_args(
+            locale,
+            "private-ticket-welcome",
+            &[
+                ("user", mci.user.mention().to_string()),
+                ("role", role_id.mention().to_string()),
+            ],
+        ),
+        None => tr_args(
+            locale,
+            "question-thread-welcome",
+            &[("user", mci.user.mention().to_string())],
+        ),
+    };
+    send_question_welcome(ctx, thread.id, locale, greeting).await;
+
+    mci.edit_original_interaction_response(&ctx.http, |d| {
+        d.content(tr_args(
+            user_locale,
+            "private-ticket-opened",
+            &[("thread", thread.mention().to_string())],
+        ))
+    })
+    .await
+    .unwrap();
+}
+
 pub async fn responder(ctx: Context, interaction: Interaction) {
     let ctx = &ctx.clone();
 
     match interaction {
         Interaction::MessageComponent(mci) => {
             match mci.data.custom_id.as_str() {
-                "gitpod_create_issue" => show_issue_form(&mci, ctx).await,
-                "gitpod_close_issue" => close_issue(&mci, ctx).await,
-                "getting_started_letsgo" => {
-                    let mut additional_roles: Vec<SelectMenuSpec> = Vec::from([
-                        SelectMenuSpec {
-                            value: "JetBrainsIDEs",
-                            description: "Discuss about Jetbrains IDEs for Gitpod!",
-                            label: "JetBrains (BETA)",
-                            display_emoji: "🧠",
-                        },
-                        SelectMenuSpec {
-                            value: "DevX",
-                            description: "All things about DevX",
-                            label: "Developer Experience",
-                            display_emoji: "✨",
-                        },
-                        SelectMenuSpec {
-                            value: "SelfHosted",
-                            description: "Do you selfhost Gitpod? Then you need this!",
-                            label: "Self Hosted Gitpod",
-                            display_emoji: "🏡",
-                        },
-                        SelectMenuSpec {
-                            value: "OnMobile",
-                            description: "Talk about using Gitpod on mobile devices",
-                            label: "Mobile and tablets",
-                            display_emoji: "📱",
-                        },
-                    ]);
-
-                    let mut poll_entries: Vec<SelectMenuSpec> = Vec::from([
-                        SelectMenuSpec {
-                            value: "Found: FromFriend",
-                            label: "Friend or colleague",
-                            description: "A friend or colleague of mine introduced Gitpod to me",
-                            display_emoji: "🫂",
-                        },
-                        SelectMenuSpec {
-                            value: "Found: FromGoogle",
-                            label: "Google",
-                            description: "I found Gitpod from a Google search",
-                            display_emoji: "🔎",
-                        },
-                        SelectMenuSpec {
-                            value: "Found: FromYouTube",
-                            label: "YouTube",
-                            description: "Saw Gitpod on a Youtube Video",
-                            display_emoji: "📺",
-                        },
-                        SelectMenuSpec {
-                            value: "Found: FromTwitter",
-                            label: "Twitter",
-                            description: "Saw people talking about Gitpod on a Tweet",
-                            display_emoji: "🐦",
-                        },
-                        SelectMenuSpec {
-                            value: "Found: FromGitRepo",
-                            label: "Git Repository",
-                            description: "Found Gitpod on a Git repository",
-                            display_emoji: "✨",
-                        },
-                    ]);
-
-                    for prog_role in [
-                        "Bash", "C", "CPP", "CSharp", "Docker", "Go", "Haskell", "Java", "Js",
-                        "Kotlin", "Lua", "Nim", "Nix", "Node", "Perl", "Php", "Python", "Ruby",
-                        "Rust",
-                    ]
-                    .iter()
+                "gitpod_create_issue" => show_issue_form(&mci, ctx, false).await,
+                "gitpod_create_private_issue" => {
+                    if private_tickets::get_support_role(ctx, mci.guild_id.unwrap())
+                        .await
+                        .is_some()
                     {
-                        additional_roles.push(SelectMenuSpec {
-                            label: prog_role,
-                            description: "Discussions",
-                            display_emoji: "📜",
-                            value: prog_role,
-                        });
-                    }
-                    let mut role_choices: Vec<String> = Vec::new();
-                    let mut join_reason = String::new();
-
-                    mci.create_interaction_response(&ctx.http, |r| {
-                    r.kind(InteractionResponseType::ChannelMessageWithSource);
-                    r.interaction_response_data(|d| {
-                        d.content(
-                            "**[1/4]:** Which additional channels would you like to have access to?",
-                        );
-                        d.components(|c| {
-                            c.create_action_row(|a| {
-                                a.create_select_menu(|s| {
-                                    s.placeholder("Select channels (Optional)");
-                                    s.options(|o| {
-										for spec in &additional_roles {
-											o.create_option(|opt| {
-												opt.label(spec.label);
-												opt.description(spec.description);
-												opt.emoji(ReactionType::Unicode(spec.display_emoji.to_string()));
-												opt.value(spec.value)
-											});
-										}
-                                        o.create_option(|opt| {
-                                            opt.label("[Skip] I don't want any!")
-                                                .description("Nopes, I ain't need more.")
-                                                .emoji(ReactionType::Unicode("⏭".to_string()))
-                                                .value("none");
-                                            opt
-                                        });
-                                        o
-                                    });
-                                    s.custom_id("channel_choice").max_values(24)
-                                });
-                                a
-                            });
-                            c
-                        });
-                        d.custom_id("bruh")
-                            .flags(MessageFlags::EPHEMERAL)
-                    });
-                    r
-                })
-                .await
-                .unwrap();
-
-                    let mut interactions = mci
-                        .get_interaction_response(&ctx.http)
+                        show_issue_form(&mci, ctx, true).await
+                    } else {
+                        let locale = i18n::user_locale(ctx, mci.guild_id, &mci.locale).await;
+                        mci.create_interaction_response(&ctx.http, |r| {
+                            r.kind(InteractionResponseType::ChannelMessageWithSource)
+                                .interaction_response_data(|d| {
+                                    d.content(tr(locale, "private-ticket-unavailable"))
+                                        .flags(MessageFlags::EPHEMERAL)
+                                })
+                        })
                         .await
-                        .unwrap()
-                        .await_component_interactions(&ctx)
-                        .timeout(Duration::from_secs(60 * 5))
-                        .build();
-
-                    while let Some(interaction) = interactions.next().await {
-                        match interaction.data.custom_id.as_str() {
-                            "channel_choice" => {
-                                interaction.create_interaction_response(&ctx.http, |r| {
-									r.kind(InteractionResponseType::UpdateMessage).interaction_response_data(|d|{
-										d.content("**[2/4]:** Would you like to get notified for announcements and community events?");
-										d.components(|c| {
-											c.create_action_row(|a| {
-												a.create_button(|b|{
-													b.label("Yes!").custom_id("subscribed").style(ButtonStyle::Success)
-												});
-												a.create_button(|b|{
-													b.label("No, thank you!").custom_id("not_subscribed").style(ButtonStyle::Danger)
-												});
-												a
-											})
-										});
-										d
-									})
-								}).await.unwrap();
-
-                                // Save the choices of last interaction
-                                interaction
-                                    .data
-                                    .values
-                                    .iter()
-                                    .for_each(|x| role_choices.push(x.to_string()));
-                            }
-                            "subscribed" | "not_subscribed" => {
-                                interaction.create_interaction_response(&ctx.http, |r| {
-									r.kind(InteractionResponseType::UpdateMessage).interaction_response_data(|d| {
-										d.content("**[3/4]:** Why did you join our community?\nI will point you to the correct channels with this info.").components(|c| {
-											c.create_action_row(|a| {
-												a.create_button(|b|{
-													b.label("To hangout with others");
-													b.style(ButtonStyle::Secondary);
-													b.emoji(ReactionType::Unicode("🏄".to_string()));
-													b.custom_id("hangout")
-												});
-												a.create_button(|b|{
-													b.label("To get help with Gitpod.io");
-													b.style(ButtonStyle::Secondary);
-													b.emoji(ReactionType::Unicode("✌️".to_string()));
-													b.custom_id("gitpodio_help")
-												});
-												a.create_button(|b|{
-													b.label("To get help with my selfhosted installation");
-													b.style(ButtonStyle::Secondary);
-													b.emoji(ReactionType::Unicode("🏡".to_string()));
-													b.custom_id("selfhosted_help")
-												});
-												a
-											})
-										})
-									})
-								}).await.unwrap();
-
-                                // Save the choices of last interaction
-                                let subscribed_role = SelectMenuSpec {
-                                    label: "Subscribed",
-                                    description: "Subscribed to pings",
-                                    display_emoji: "",
-                                    value: "Subscriber",
-                                };
-                                if interaction.data.custom_id == "subscribed" {
-                                    role_choices.push(subscribed_role.value.to_string());
-                                }
-                                additional_roles.push(subscribed_role);
-                            }
-                            "hangout" | "gitpodio_help" | "selfhosted_help" => {
-                                interaction.create_interaction_response(&ctx.http, |r| {
-									r.kind(InteractionResponseType::UpdateMessage).interaction_response_data(|d| {
-										d.content("**[3/4]**: You have personalized the server, congrats!").components(|c|c)
-									})
-								}).await.unwrap();
-
-                                // Save join reason
-                                join_reason.push_str(interaction.data.custom_id.as_str());
-
-                                let mut member = mci.member.clone().unwrap();
-                                let member_role = get_role(&mci, ctx, "Member").await;
-                                let never_introduced = {
-                                    let mut status = true;
-                                    if let Some(roles) = member.roles(&ctx.cache) {
-                                        let gitpodder_role =
-                                            get_role(&mci, ctx, "Gitpodders").await;
-                                        status = !roles
-                                            .into_iter()
-                                            .any(|x| x == member_role || x == gitpodder_role);
-                                    }
-                                    if status {
-                                        let mut count = 0;
-                                        if let Ok(intro_msgs) = &ctx
-                                            .http
-                                            .get_messages(*INTRODUCTION_CHANNEL.as_u64(), "")
-                                            .await
-                                        {
-                                            intro_msgs.iter().for_each(|x| {
-                                                if x.author == interaction.user {
-                                                    count += 1;
-                                                }
-                                            });
-                                        }
-
-                                        status = count < 1;
-                                    }
-                                    status
-                                };
-
-                                let followup = interaction
-                                    .create_followup_message(&ctx.http, |d| {
-                                        d.content("**[4/4]:** How did you find Gitpod?");
-                                        d.components(|c| {
-                                            c.create_action_row(|a| {
-                                                a.create_select_menu(|s| {
-                                                    s.placeholder(
-                                                        "[Poll]: Select sources (Optional)",
-                                                    );
-                                                    s.options(|o| {
-                                                        for spec in &poll_entries {
-                                                            o.create_option(|opt| {
-                                                                opt.label(spec.label);
-                                                                opt.description(spec.description);
-                                                                opt.emoji(ReactionType::Unicode(
-                                                                    spec.display_emoji.to_string(),
-                                                                ));
-                                                                opt.value(spec.value);
-                                                                opt
-                                                            });
-                                                        }
-                                                        o.create_option(|opt| {
-                                                            opt.label("[Skip] Prefer not to share")
-                                                                .value("none")
-                                                                .emoji(ReactionType::Unicode(
-                                                                    "⏭".to_string(),
-                                                                ));
-                                                            opt
-                                                        });
-                                                        o
-                                                    });
-                                                    s.custom_id("found_gitpod_from").max_values(5)
-                                                });
-                                                a
-                                            });
-                                            c
-                                        });
-                                        d.flags(MessageFlags::EPHEMERAL)
-                                    })
-                                    .await
-                                    .unwrap();
-
-                                let temp_role = get_role(&mci, ctx, "Temp").await;
-                                let followup_results = match followup
-                                    .await_component_interaction(&ctx)
-                                    .timeout(Duration::from_secs(60 * 5))
-                                    .await
-                                {
-                                    Some(ci) => {
-                                        member.add_role(&ctx.http, temp_role.id).await.unwrap();
-                                        let final_msg = {
-                                            if never_introduced {
-                                                MessageBuilder::new()
-												.push_line(format!(
-													"Thank you {}! To unlock the server, drop by {} :wave:",
-													interaction.user.mention(),
-													INTRODUCTION_CHANNEL.mention()
-												))
-												.push_line("\nWe’d love to get to know you better and hear about:")
-												.push_quote_line("🔧 what you’re working on!")
-												.push_quote_line("🛑 what blocks you most in your daily dev workflow")
-												.push_quote_line("🌈 your favourite Gitpod feature")
-												.push_quote_line("✨ your favourite emoji").build()
-                                            } else {
-                                                "Awesome, your server profile will be updated now!"
-                                                    .to_owned()
-                                            }
-                                        };
-                                        ci.create_interaction_response(&ctx.http, |r| {
-                                            r.kind(InteractionResponseType::UpdateMessage)
-                                                .interaction_response_data(|d| {
-                                                    d.content(final_msg).components(|c| c)
-                                                })
-                                        })
-                                        .await
-                                        .unwrap();
-                                        ci
-                                    }
-                                    None => return,
-                                };
-
-                                // if let Some(interaction) = interaction
-                                //     .get_interaction_response(&ctx.http)
-                                //     .await
-                                //     .unwrap()
-                                //     .await_component_interaction(&ctx)
-                                //     .timeout(Duration::from_secs(60 * 5))
-                                //     .await
-                                // {
-
-                                if never_introduced {
-                                    // Wait for the submittion on INTRODUCTION_CHANNEL
-                                    if let Some(msg) = mci
-                                        .user
-                                        .await_reply(&ctx)
-                                        .timeout(Duration::from_secs(60 * 30))
-                                        .await
-                                    {
-                                        // Watch intro channel
-                                        if msg.channel_id == INTRODUCTION_CHANNEL {
-                                            // let mut count = 0;
-                                            // intro_msgs.iter().for_each(|x| {
-                                            // 	if x.author == msg.author {
-                                            // 		count += 1;
-                                            // 	}
-                                            // });
-
-                                            // if count <= 1 {
-                                            let thread = msg
-                                                .channel_id
-                                                .create_public_thread(&ctx.http, &msg.id, |t| {
-                                                    t.auto_archive_duration(1440).name(format!(
-                                                        "Welcome {}!",
-                                                        msg.author.name
-                                                    ))
-                                                })
-                                                .await
-                                                .unwrap();
-
-                                            if words_count::count(&msg.content).words > 5 {
-                                                msg.react(
-                                                    &ctx.http,
-                                                    ReactionType::Unicode("🔥".to_string()),
-                                                )
-                                                .await
-                                                .unwrap();
-                                            }
-                                            msg.react(
-                                                &ctx.http,
-                                                ReactionType::Unicode("👋".to_string()),
-                                            )
-                                            .await
-                                            .unwrap();
-
-                                            let general_channel = if cfg!(debug_assertions) {
-                                                ChannelId(947769443516284943)
-                                            } else {
-                                                ChannelId(839379835662368768)
-                                            };
-                                            let offtopic_channel = if cfg!(debug_assertions) {
-                                                ChannelId(947769443793141769)
-                                            } else {
-                                                ChannelId(972510491933032508)
-                                            };
-                                            let db = &ctx.get_db().await;
-                                            let questions_channel =
-                                                db.get_question_channels().await.unwrap();
-                                            let questions_channel =
-                                                questions_channel.into_iter().next().unwrap().id;
-
-                                            let selfhosted_questions_channel =
-                                                if cfg!(debug_assertions) {
-                                                    ChannelId(947769443793141761)
-                                                } else {
-                                                    ChannelId(879915120510267412)
-                                                };
-
-                                            let mut prepared_msg = MessageBuilder::new();
-                                            prepared_msg.push_line(format!(
-                                                "Welcome to the Gitpod community {} 🙌\n",
-                                                &msg.author.mention()
-                                            ));
-                                            match join_reason.as_str() {
-                                                "gitpodio_help" => {
-                                                    prepared_msg.push_line(
-														format!("**You mentioned that** you need help with Gitpod.io, please ask in {}\n",
-																	&questions_channel.mention())
-													);
-                                                }
-                                                "selfhosted_help" => {
-                                                    let selfhosted_role =
-                                                        get_role(&mci, ctx, "SelfHosted").await;
-                                                    member
-                                                        .add_role(&ctx.http, selfhosted_role.id)
-                                                        .await
-                                                        .unwrap();
-                                                    prepared_msg.push_line(
-														format!("**You mentioned that** you need help with selfhosted, please ask in {}\n",
-																	&selfhosted_questions_channel.mention())
-													);
-                                                }
-                                                _ => {}
-                                            }
-                                            prepared_msg.push_bold_line("Here are some channels that you should check out:")
-											.push_quote_line(format!("• {} - for tech, programming and anything related 🖥", &general_channel.mention()))
-											.push_quote_line(format!("• {} - for any random discussions ☕️", &offtopic_channel.mention()))
-											.push_quote_line(format!("• {} - have a question about Gitpod? this is the place to ask! ❓\n", &questions_channel.mention()))
-											.push_line("…And there’s more! Take your time to explore :)\n")
-											.push_bold_line("Feel free to check out the following pages to learn more about Gitpod:")
-											.push_quote_line("• https://www.gitpod.io/community")
-											.push_quote_line("• https://www.gitpod.io/about");
-                                            let mut thread_msg = thread
-                                                .send_message(&ctx.http, |t| {
-                                                    t.content(prepared_msg)
-                                                })
-                                                .await
-                                                .unwrap();
-                                            thread_msg.suppress_embeds(&ctx.http).await.unwrap();
-                                            // } else {
-                                            // 	let warn_msg = msg
-                                            // 	.reply_mention(
-                                            // 		&ctx.http,
-                                            // 		"Please reply in threads above instead of here",
-                                            // 	)
-                                            // 	.await
-                                            // 	.unwrap();
-                                            // 	sleep(Duration::from_secs(10)).await;
-                                            // 	warn_msg.delete(&ctx.http).await.unwrap();
-                                            // 	msg.delete(&ctx.http).await.ok();
-                                            // }
-                                        }
-                                        // }
-                                    }
-                                }
-
-                                // save the found from data
-                                followup_results
-                                    .data
-                                    .values
-                                    .iter()
-                                    .for_each(|x| role_choices.push(x.to_string()));
-
-                                // Remove old roles
-                                if let Some(roles) = member.roles(&ctx.cache) {
-                                    // Remove all assignable roles first
-                                    let mut all_assignable_roles: Vec<SelectMenuSpec> = Vec::new();
-                                    all_assignable_roles.append(&mut additional_roles);
-                                    all_assignable_roles.append(&mut poll_entries);
-                                    let mut removeable_roles: Vec<RoleId> = Vec::new();
-
-                                    for role in roles {
-                                        if all_assignable_roles.iter().any(|x| x.value == role.name)
-                                        {
-                                            removeable_roles.push(role.id);
-                                        }
-                                    }
-                                    if !removeable_roles.is_empty() {
-                                        member
-                                            .remove_roles(&ctx.http, &removeable_roles)
-                                            .await
-                                            .unwrap();
-                                    }
-                                }
-
-                                assign_roles(
-                                    &mci,
-                                    ctx,
-                                    role_choices,
-                                    &mut member,
-                                    &temp_role,
-                                    &member_role,
-                                )
-                                .await;
-                                break;
-                            }
-                            _ => {}
-                        }
+                        .unwrap();
                     }
                 }
+                "gitpod_close_issue" => close_issue(&mci, ctx).await,
+                "getting_started_letsgo" => onboarding::start(&mci, ctx).await,
+                x if x.starts_with("onboarding_") => onboarding::handle_component(&mci, ctx).await,
+                x if x.starts_with("role_menu:") => role_menu::toggle(&mci, ctx).await,
+                "raid_lift" | "raid_kick" => raid::handle_button(&mci, ctx).await,
+                x if x.starts_with("names:") => name_history::handle_page(&mci, ctx).await,
+                x if x.starts_with("spam_") => spam::handle_button(&mci, ctx).await,
+                x if x.starts_with("report:") => reports::handle_button(&mci, ctx).await,
+                x if x.starts_with("run_code:") => code_exec::handle_button(&mci, ctx).await,
                 _ => {
                     // If a Question thread suggestion was clicked
                     if mci.data.custom_id.starts_with("http") {
+                        let locale = i18n::user_locale(ctx, mci.guild_id, &mci.locale).await;
                         let button_label = &mci
                             .message
                             .components
@@ -881,7 +517,7 @@ pub async fn responder(ctx: Context, interaction: Interaction) {
                                         .components(|c| {
                                             c.create_action_row(|a| {
                                                 a.create_button(|b| {
-                                                    b.label("Open link")
+                                                    b.label(tr(locale, "question-open-link"))
                                                         .url(&mci.data.custom_id)
                                                         .style(ButtonStyle::Link)
                                                 })
@@ -902,6 +538,9 @@ pub async fn responder(ctx: Context, interaction: Interaction) {
             }
         }
         Interaction::ApplicationCommand(mci) => match mci.data.name.as_str() {
+            "close" if modmail::is_modmail_thread(ctx, mci.channel_id).await => {
+                modmail::close(&mci, ctx).await
+            }
             "close" => {
                 let _thread = mci.channel_id.edit_thread(&ctx.http, |t| t).await.unwrap();
                 let thread_type = {
@@ -911,10 +550,11 @@ pub async fn responder(ctx: Context, interaction: Interaction) {
                         "thread"
                     }
                 };
+                let locale = i18n::guild_locale(ctx, mci.guild_id).await;
                 mci.create_interaction_response(&ctx.http, |r| {
                     r.kind(InteractionResponseType::ChannelMessageWithSource);
                     r.interaction_response_data(|d| {
-                        d.content(format!("This {} was closed", thread_type))
+                        d.content(tr(locale, &format!("{}-closed", thread_type)))
                     })
                 })
                 .await
@@ -927,11 +567,41 @@ pub async fn responder(ctx: Context, interaction: Interaction) {
                         format!("✅ {}", thread_node.name.trim_start_matches("❓ "))
                     }
                 };
+                let db = &ctx.get_db().await;
+                db.close_question_thread(mci.channel_id, Timestamp::now().unix_timestamp())
+                    .await
+                    .ok();
+                db.close_private_ticket(mci.channel_id, Timestamp::now().unix_timestamp())
+                    .await
+                    .ok();
                 mci.channel_id
                     .edit_thread(&ctx.http, |t| t.archived(true).name(thread_name))
                     .await
                     .unwrap();
             }
+            "support-stats" => support_stats::responder(&mci, ctx).await,
+            "digest" => digest::responder(&mci, ctx).await,
+            "oncall" => oncall::responder(&mci, ctx).await,
+            "onboarding" => onboarding::responder(&mci, ctx).await,
+            "survey" => survey::responder(&mci, ctx).await,
+            "welcome" => announcements::responder(&mci, ctx).await,
+            "raid" => raid::responder(&mci, ctx).await,
+            "screen" => screening::responder(&mci, ctx).await,
+            "names" => name_history::responder(&mci, ctx).await,
+            "filters" => automod::responder(&mci, ctx).await,
+            "automod" => automod_rules::responder(&mci, ctx).await,
+            "scam-domains" => spam::responder(&mci, ctx).await,
+            "purge" => purge::responder(&mci, ctx).await,
+            "Report message" => reports::show_form(&mci, ctx).await,
+            "reply" | "areply" => modmail::reply(&mci, ctx).await,
+            "modmail" => modmail::responder(&mci, ctx).await,
+            "runtimes" => code_exec::runtimes(&mci, ctx).await,
+            "Run code" => code_exec::run_from_menu(&mci, ctx).await,
+            "warn" | "timeout" | "kick" | "ban" | "unban" | "case" | "history" => {
+                moderation::responder(&mci, ctx).await
+            }
+            "roles" => role_menu::responder(&mci, ctx).await,
+            "role-menu" => role_menu::post_menu(&mci, ctx).await,
             "nothing_to_see_here" => {
                 let input = mci
                     .data
@@ -941,10 +611,11 @@ pub async fn responder(ctx: Context, interaction: Interaction) {
                     .value
                     .as_ref()
                     .unwrap();
+                let locale = i18n::user_locale(ctx, mci.guild_id, &mci.locale).await;
                 mci.create_interaction_response(&ctx.http, |r| {
                     r.kind(InteractionResponseType::ChannelMessageWithSource)
                         .interaction_response_data(|d| {
-                            d.content("Posted message on this channel")
+                            d.content(tr(locale, "message-posted"))
                                 .flags(MessageFlags::EPHEMERAL)
                         })
                 })
@@ -966,6 +637,18 @@ pub async fn responder(ctx: Context, interaction: Interaction) {
             _ => {}
         },
         Interaction::ModalSubmit(mci) => {
+            if mci.data.custom_id.starts_with("onboarding_") {
+                onboarding::handle_form(&mci, ctx).await;
+                return;
+            }
+            if mci.data.custom_id.starts_with("report_form:") {
+                reports::handle_form(&mci, ctx).await;
+                return;
+            }
+            if mci.data.custom_id.starts_with("report_case:") {
+                reports::handle_case_form(&mci, ctx).await;
+                return;
+            }
             let typing = mci.channel_id.start_typing(&ctx.http).unwrap();
             let title = match mci
                 .data
@@ -1016,6 +699,20 @@ pub async fn responder(ctx: Context, interaction: Interaction) {
                 _ => return,
             };
 
+            if mci.data.custom_id == "gitpod_private_help_button_press" {
+                typing.stop().unwrap();
+                ask_privately(
+                    &mci,
+                    ctx,
+                    &title.value,
+                    &description.value,
+                    &optional_one.value,
+                    &optional_two.value,
+                )
+                .await;
+                return;
+            }
+
             mci.create_interaction_response(ctx, |r| {
                 if mci.data.custom_id == "gitpod_help_button_press" {
                     r.kind(InteractionResponseType::ChannelMessageWithSource);
@@ -1083,72 +780,39 @@ pub async fn responder(ctx: Context, interaction: Interaction) {
                 .await
                 .unwrap();
 
-            let desc_safe = safe_text(ctx, &description.value).await;
-            thread
-                .send_message(&ctx.http, |m| {
-                    if description.value.chars().count() < 1960 {
-                        m.content(
-                            MessageBuilder::new()
-                                .push_underline_line("**Description**")
-                                .push_line(&desc_safe)
-                                .push_bold("---------------")
-                                .build(),
-                        );
-                    } else {
-                        m.add_embed(|e| e.title("Description").description(desc_safe));
-                    }
-                    if channel_name != SELF_HOSTED_TEXT {
-                        if !optional_one.value.is_empty() || !optional_two.value.is_empty() {
-                            m.add_embed(|e| {
-                                if !optional_one.value.is_empty() {
-                                    e.field("Workspace affected", &optional_one.value, false);
-                                }
-                                if !optional_two.value.is_empty() {
-                                    e.field("Example Repository", &optional_two.value, false);
-                                }
-                                e
-                            });
-                        }
-                    } else if channel_name == SELF_HOSTED_TEXT {
-                        if !optional_one.value.is_empty() {
-                            m.add_embed(|e| {
-                                e.title("config.yaml contents")
-                                    .description(format!("```yaml\n{}\n```", &optional_one.value))
-                            });
-                        }
-                        if optional_two.value != SELF_HOSTED_KUBECTL_COMMAND_PLACEHOLDER
-                            && !optional_two.value.is_empty()
-                        {
-                            m.add_embed(|e| {
-                                e.title("Result of kubectl").description(format!(
-                                    "```javascript\n{}\n```",
-                                    &optional_two.value
-                                ))
-                            });
-                        }
-                    }
-
-                    m
-                })
-                .await
-                .unwrap();
+            let db = &ctx.get_db().await;
+            db.add_question_thread(
+                thread.id,
+                mci.guild_id.unwrap(),
+                mci.channel_id,
+                mci.user.id,
+                thread.id.created_at().unix_timestamp(),
+            )
+            .await
+            .ok();
 
-            thread
-                .send_message(&ctx, |m| {
-                    m.content( MessageBuilder::new().push_quote(format!("Hey {}! Thank you for raising this — please hang tight as someone from our community may help you out. Meanwhile, feel free to add anymore information in this thread!", user_mention)).build()).components(|c| {
-                        c.create_action_row(|ar| {
-                            ar.create_button(|button| {
-                                button
-                                    .style(ButtonStyle::Danger)
-                                    .label("Close")
-                                    .custom_id("gitpod_close_issue")
-                                    .emoji(ReactionType::Unicode("🔒".to_string()))
-                            })
-                        })
-                    })
-                })
-                .await
-                .unwrap();
+            let locale = i18n::guild_locale(ctx, mci.guild_id).await;
+            send_question_details(
+                ctx,
+                thread.id,
+                locale,
+                channel_name,
+                &description.value,
+                &optional_one.value,
+                &optional_two.value,
+            )
+            .await;
+            send_question_welcome(
+                ctx,
+                thread.id,
+                locale,
+                tr_args(
+                    locale,
+                    "question-thread-welcome",
+                    &[("user", user_mention.to_string())],
+                ),
+            )
+            .await;
 
             questions_thread::responder(ctx).await;
 
@@ -1206,54 +870,63 @@ pub async fn responder(ctx: Context, interaction: Interaction) {
                 }
 
                 let mut suggested_count = 1;
-                thread.send_message(&ctx.http, |m| {
-				m.content(format!("{} I also found some relevant links which might answer your question, please do check them out below 🙏:", &user_mention));
-					m.components(|c| {
-						loop {
-							if suggested_count > 10 || relevant_links.is_empty() {
-								break;
-							}
-							c.create_action_row(|a|
-								{
-									let mut i = 1;
-									for (title, url) in relevant_links.clone() {
-										if i > 5 {
-											break;
-										} else {
-											i += 1;
-											relevant_links.remove(&title);
-										}
-										let emoji = {
-											if url.starts_with("https://www.gitpod.io") {
-												prefix_emojis.get("gitpod").unwrap()
-											} else if url.starts_with("https://github.com") {
-												prefix_emojis.get("github").unwrap()
-											} else {
-												prefix_emojis.get("discord").unwrap()
-											}
-										};
+                thread
+                    .send_message(&ctx.http, |m| {
+                        m.content(tr_args(
+                            locale,
+                            "question-relevant-links",
+                            &[("user", user_mention.to_string())],
+                        ));
+                        m.components(|c| {
+                            loop {
+                                if suggested_count > 10 || relevant_links.is_empty() {
+                                    break;
+                                }
+                                c.create_action_row(|a| {
+                                    let mut i = 1;
+                                    for (title, url) in relevant_links.clone() {
+                                        if i > 5 {
+                                            break;
+                                        } else {
+                                            i += 1;
+                                            relevant_links.remove(&title);
+                                        }
+                                        let emoji = {
+                                            if url.starts_with("https://www.gitpod.io") {
+                                                prefix_emojis.get("gitpod").unwrap()
+                                            } else if url.starts_with("https://github.com") {
+                                                prefix_emojis.get("github").unwrap()
+                                            } else {
+                                                prefix_emojis.get("discord").unwrap()
+                                            }
+                                        };
 
-										a.create_button(|b|b.label(&title.as_str().substring(0, 80)).custom_id(&url.as_str().substring(0, 100)).style(ButtonStyle::Secondary).emoji(ReactionType::Custom {
-											id: emoji.id,
-											name: Some(emoji.name.clone()),
-											animated: false,
-										}));
-									}
-										a
-									}
-								);
-								suggested_count += 1;
-						}
-							c
-						});
-						m
-					}
-				).await.unwrap();
+                                        a.create_button(|b| {
+                                            b.label(&title.as_str().substring(0, 80))
+                                                .custom_id(&url.as_str().substring(0, 100))
+                                                .style(ButtonStyle::Secondary)
+                                                .emoji(ReactionType::Custom {
+                                                    id: emoji.id,
+                                                    name: Some(emoji.name.clone()),
+                                                    animated: false,
+                                                })
+                                        });
+                                    }
+                                    a
+                                });
+                                suggested_count += 1;
+                            }
+                            c
+                        });
+                        m
+                    })
+                    .await
+                    .unwrap();
                 thread_typing.stop().unwrap();
             }
             // if !relevant_links.is_empty() {
             //     thread
-            //         .send_message(&ctx.http, |m| 
+            //         .send_message(&ctx.http, |m|
             //             m.content(format!(
             //                 "{} I also found some relevant links which might answer your question, please do check them out below 🙏:",
             //                 &user_mention
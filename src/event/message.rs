@@ -63,7 +63,24 @@ pub async fn responder(ctx: Context, mut _msg: Message) -> Result<()> {
     //
     // Log messages
     //
+    // Direct messages go to modmail, except prefix commands
+    if _msg.guild_id.is_none() && !_msg.author.bot && !_msg.content.starts_with("gp ") {
+        if let Err(e) = modmail::relay_from_user(&ctx, &_msg).await {
+            eprintln!("Modmail relay failed for {}: {}", _msg.author.id, e);
+        }
+        return Ok(());
+    }
+
     if !_msg.is_own(&ctx.cache) {
+        // Spam bursts and scam links, then content filters; nothing else should see
+        // a message they removed
+        if spam::check_message(&ctx, &_msg).await {
+            return Ok(());
+        }
+        if automod::check_message(&ctx, &_msg, false).await? {
+            return Ok(());
+        }
+
         // let dbnode_msgcache = Database::from("msgcache".to_string()).await;
 
         // let attc = &_msg.attachments;
@@ -96,17 +113,23 @@ pub async fn responder(ctx: Context, mut _msg: Message) -> Result<()> {
                     db.add_pending_question(&_msg.author.id, &_msg.channel_id, &_msg.content)
                         .await?;
                     _msg.delete(&ctx.http).await?;
+                    let locale = i18n::guild_locale(&ctx, _msg.guild_id).await;
                     let r = _msg
-                        .reply_mention(
-                            &ctx.http,
-                            "☝️ Please click on **`💡 Ask a Question`** button to complete your question",
-                        )
+                        .reply_mention(&ctx.http, tr(locale, "questions-use-ask-button"))
                         .await?;
                     sleep(Duration::from_secs(15)).await;
                     r.delete(&ctx.http).await?;
                 }
             }
 
+            // First responses on question threads
+            support_stats::record_reply(&ctx, &_msg).await?;
+
+            // Introductions of members that went through onboarding
+            if _msg.channel_id == INTRODUCTION_CHANNEL {
+                onboarding::welcome_introduced(&ctx, &_msg).await?;
+            }
+
             //
             // Moderate "showcase" and "feedback" type channel
             //
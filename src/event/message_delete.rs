@@ -8,6 +8,11 @@ pub async fn responder(
     _deleted_message_id: MessageId,
     _guild_id: Option<GuildId>,
 ) {
+    // Archived by /purge, which posts its own summary
+    if purge::is_purged(_deleted_message_id) {
+        return;
+    }
+
     let db = &_ctx.get_db().await;
     if let Ok(qc) = db.get_question_channels().await {
         if qc.iter().any(|x| x.id == _channel_id) {
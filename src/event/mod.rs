@@ -1,16 +1,37 @@
 // mod getting_started;
+mod announcements;
+mod automod;
+mod automod_rules;
+mod code_exec;
+mod digest;
 mod guild_create;
-// mod guild_member_addition;
+mod guild_member_addition;
 mod guild_member_removal;
 mod interaction_create;
 mod message;
 mod message_delete;
+pub mod moderation;
+mod modmail;
+mod name_history;
+mod onboarding;
+mod oncall;
+mod private_tickets;
+mod purge;
 // mod message_update;
 pub mod questions_thread;
+mod raid;
 mod reaction_add;
 mod ready;
+mod reports;
+mod role_menu;
+mod screening;
+mod spam;
+mod support_stats;
+mod survey;
 mod thread_update;
 
+use crate::db::ClientContextExt;
+use crate::i18n::{self, tr, tr_args};
 use crate::utils::{db::*, /*misc::vowel_gen,*/ substr};
 
 use regex::Regex;
@@ -22,9 +43,9 @@ use serenity::model::{
         interaction::Interaction,
     },
     channel::{GuildChannel, Message, Reaction},
-    // event::MessageUpdateEvent,
+    event::MessageUpdateEvent,
     gateway::{Activity, Ready},
-    guild::{Guild, Member},
+    guild::{automod::ActionExecution, Guild, Member},
     id::{ChannelId, GuildId, MessageId},
     prelude::User,
 };
@@ -70,6 +91,18 @@ const INTRODUCTION_CHANNEL: ChannelId = if cfg!(debug_assertions) {
     ChannelId(816249489911185418)
 };
 
+/// Posts to the channel set with `gp config log_channel`, if there is one.
+async fn post_log(ctx: &Context, guild_id: GuildId, content: String) -> Result<()> {
+    let db = ctx.get_db().await;
+    if let Some(log_channel) = db
+        .get_guild_config_as::<ChannelId>(guild_id, "log_channel")
+        .await?
+    {
+        log_channel.say(&ctx.http, content).await?;
+    }
+    Ok(())
+}
+
 pub struct Listener {
     pub is_loop_running: AtomicBool,
 }
@@ -96,15 +129,23 @@ impl EventHandler for Listener {
         message_delete::responder(_ctx, _channel_id, _deleted_message_id, _guild_id).await;
     }
 
-    // async fn message_update(
-    //     &self,
-    //     _ctx: Context,
-    //     _old_if_available: Option<Message>,
-    //     _new: Option<Message>,
-    //     _event: MessageUpdateEvent,
-    // ) {
-    //     message_update::responder(_ctx, _old_if_available, _new, _event).await;
-    // }
+    async fn message_update(
+        &self,
+        _ctx: Context,
+        _old_if_available: Option<Message>,
+        _new: Option<Message>,
+        _event: MessageUpdateEvent,
+    ) {
+        // message_update::responder(_ctx, _old_if_available, _new, _event).await;
+        automod::message_edited(&_ctx, _new, &_event).await;
+        code_exec::message_edited(&_ctx, &_event).await;
+    }
+
+    async fn auto_moderation_action_execution(&self, _ctx: Context, _execution: ActionExecution) {
+        if let Err(e) = automod_rules::action_executed(&_ctx, _execution).await {
+            eprintln!("Failed to log an AutoMod action: {}", e);
+        }
+    }
 
     async fn thread_create(&self, _ctx: Context, _thread: GuildChannel) {
         _thread.id.join_thread(&_ctx.http).await.unwrap();
@@ -119,9 +160,19 @@ impl EventHandler for Listener {
         ready::responder(&_ctx, ready).await;
     }
 
-    // async fn guild_member_addition(&self, _ctx: Context, _guild_id: GuildId, _new_member: Member) {
-    //     guild_member_addition::responder(_ctx, _guild_id, _new_member).await;
-    // }
+    async fn guild_member_addition(&self, _ctx: Context, _new_member: Member) {
+        guild_member_addition::responder(_ctx, _new_member.guild_id, _new_member).await;
+    }
+
+    async fn guild_member_update(
+        &self,
+        _ctx: Context,
+        _old_if_available: Option<Member>,
+        _new: Member,
+    ) {
+        name_history::member_updated(&_ctx, &_new).await;
+        screening::member_updated(&_ctx, _old_if_available.as_ref(), &_new).await;
+    }
 
     async fn guild_member_removal(
         &self,
@@ -153,37 +204,12 @@ impl EventHandler for Listener {
         // An AtomicBool is used because it doesn't require a mutable reference to be changed, as
         // we don't have one due to self being an immutable reference.
         if !self.is_loop_running.load(Ordering::Relaxed) {
-            // We have to clone the Arc, as it gets moved into the new thread.
-            // let ctx1 = Arc::clone(&ctx);
-            // tokio::spawn creates a new green thread that can run in parallel with the rest of
-            // the application.
-            // tokio::spawn(async move {
-            //     loop {
-            //         // We clone Context again here, because Arc is owned, so it moves to the
-            //         // new function.
-            //         // log_system_load(Arc::clone(&ctx1)).await;
-            //         let dbnode_userid = Database::from("userid".to_string()).await;
-            //         let guilds = &ctx.cache.guilds().await;
-
-            //         for guild in guilds.iter() {
-            //             let members = &ctx1.cache.guild(guild).await.unwrap().members;
-
-            //             for (_user_id, _member) in members {
-            //                 // tokio::time::sleep(Duration::from_secs(2)).await;
-            //                 dbnode_userid
-            //                     .save_user_info(_user_id, _member.user.tag())
-            //                     .await;
-            //             }
-            //         }
-
-            //         // Workaround process uptime limit on free google server
-            //         // tokio::time::sleep(Duration::from_secs(3 * (24 * (60 * 60)))).await;
-            //         // std::process::Command::new(env::current_exe().unwrap())
-            //         //     .spawn()
-            //         //     .unwrap();
-            //         // std::process::exit(0);
-            //     }
-            // });
+            tokio::spawn(digest::run_schedule(_ctx.clone()));
+            tokio::spawn(oncall::run_escalations(_ctx.clone()));
+            tokio::spawn(onboarding::run_expiry(_ctx.clone()));
+            tokio::spawn(announcements::track_invites(_ctx.clone()));
+            tokio::spawn(name_history::backfill(_ctx.clone()));
+            tokio::spawn(moderation::run_expiry(_ctx.clone()));
 
             // Now that the loop is running, we set the bool to true
             self.is_loop_running.swap(true, Ordering::Relaxed);
@@ -0,0 +1,789 @@
+use super::*;
+use crate::db::Db;
+use crate::utils::misc::{format_duration, parse_duration};
+use serenity::model::{
+    application::interaction::{
+        application_command::{ApplicationCommandInteraction, CommandDataOptionValue},
+        MessageFlags,
+    },
+    id::UserId,
+    Timestamp,
+};
+use serenity::utils::MessageBuilder;
+
+// Discord doesn't allow longer timeouts
+pub const MAX_TIMEOUT_SECS: i64 = 28 * 86400;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum CaseAction {
+    Warn,
+    Timeout,
+    Kick,
+    Ban,
+    Unban,
+}
+
+impl CaseAction {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "warn" => Some(CaseAction::Warn),
+            "timeout" => Some(CaseAction::Timeout),
+            "kick" => Some(CaseAction::Kick),
+            "ban" => Some(CaseAction::Ban),
+            "unban" => Some(CaseAction::Unban),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            CaseAction::Warn => "warn",
+            CaseAction::Timeout => "timeout",
+            CaseAction::Kick => "kick",
+            CaseAction::Ban => "ban",
+            CaseAction::Unban => "unban",
+        }
+    }
+
+    fn title(self) -> &'static str {
+        match self {
+            CaseAction::Warn => "Warning",
+            CaseAction::Timeout => "Timeout",
+            CaseAction::Kick => "Kick",
+            CaseAction::Ban => "Ban",
+            CaseAction::Unban => "Unban",
+        }
+    }
+
+    fn emoji(self) -> &'static str {
+        match self {
+            CaseAction::Warn => "⚠️",
+            CaseAction::Timeout => "🔇",
+            CaseAction::Kick => "👢",
+            CaseAction::Ban => "🔨",
+            CaseAction::Unban => "🕊️",
+        }
+    }
+}
+
+pub struct ModCase {
+    pub id: i64,
+    pub guild_id: i64,
+    pub case_number: i64,
+    pub user_id: i64,
+    pub moderator_id: i64,
+    pub action: String,
+    pub reason: String,
+    pub duration_secs: Option<i64>,
+    pub expires_at: Option<i64>,
+    pub expired_at: Option<i64>,
+    pub evidence: String,
+    pub created_at: i64,
+    pub log_channel_id: Option<i64>,
+    pub log_message_id: Option<i64>,
+}
+
+impl ModCase {
+    pub fn action(&self) -> CaseAction {
+        CaseAction::parse(&self.action).unwrap_or(CaseAction::Warn)
+    }
+}
+
+impl Db {
+    /// Opens the next case of the guild.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn add_mod_case(
+        &self,
+        guild_id: GuildId,
+        user_id: UserId,
+        moderator_id: UserId,
+        action: CaseAction,
+        reason: &str,
+        duration_secs: Option<i64>,
+        evidence: &str,
+    ) -> Result<ModCase> {
+        let guild_id = guild_id.0 as i64;
+        let user_id = user_id.0 as i64;
+        let moderator_id = moderator_id.0 as i64;
+        let action = action.name();
+        let created_at = Timestamp::now().unix_timestamp();
+        let expires_at = duration_secs.map(|x| created_at + x);
+        let id = sqlx::query!(
+            "insert into mod_cases (guild_id, case_number, user_id, moderator_id, action, reason, duration_secs, expires_at, evidence, created_at)
+            values (?1, (select coalesce(max(case_number), 0) + 1 from mod_cases where guild_id=?1), ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+            guild_id,
+            user_id,
+            moderator_id,
+            action,
+            reason,
+            duration_secs,
+            expires_at,
+            evidence,
+            created_at
+        )
+        .execute(&self.sqlitedb)
+        .await?
+        .last_insert_rowid();
+        let q = sqlx::query_as!(
+            ModCase,
+            r#"select id as "id!", guild_id, case_number, user_id, moderator_id, action, reason, duration_secs, expires_at, expired_at, evidence, created_at, log_channel_id, log_message_id
+            from mod_cases where id=?"#,
+            id
+        )
+        .fetch_one(&self.sqlitedb)
+        .await?;
+        Ok(q)
+    }
+
+    pub async fn get_mod_case(
+        &self,
+        guild_id: GuildId,
+        case_number: i64,
+    ) -> Result<Option<ModCase>> {
+        let guild_id = guild_id.0 as i64;
+        let q = sqlx::query_as!(
+            ModCase,
+            r#"select id as "id!", guild_id, case_number, user_id, moderator_id, action, reason, duration_secs, expires_at, expired_at, evidence, created_at, log_channel_id, log_message_id
+            from mod_cases where guild_id=? and case_number=?"#,
+            guild_id,
+            case_number
+        )
+        .fetch_optional(&self.sqlitedb)
+        .await?;
+        Ok(q)
+    }
+
+    pub async fn get_user_mod_cases(
+        &self,
+        guild_id: GuildId,
+        user_id: UserId,
+    ) -> Result<Vec<ModCase>> {
+        let guild_id = guild_id.0 as i64;
+        let user_id = user_id.0 as i64;
+        let q = sqlx::query_as!(
+            ModCase,
+            r#"select id as "id!", guild_id, case_number, user_id, moderator_id, action, reason, duration_secs, expires_at, expired_at, evidence, created_at, log_channel_id, log_message_id
+            from mod_cases where guild_id=? and user_id=? order by case_number desc"#,
+            guild_id,
+            user_id
+        )
+        .fetch_all(&self.sqlitedb)
+        .await?;
+        Ok(q)
+    }
+
+    /// Temporary actions whose time is up.
+    pub async fn get_expired_mod_cases(&self, now: i64) -> Result<Vec<ModCase>> {
+        let q = sqlx::query_as!(
+            ModCase,
+            r#"select id as "id!", guild_id, case_number, user_id, moderator_id, action, reason, duration_secs, expires_at, expired_at, evidence, created_at, log_channel_id, log_message_id
+            from mod_cases where expires_at<=? and expired_at is null"#,
+            now
+        )
+        .fetch_all(&self.sqlitedb)
+        .await?;
+        Ok(q)
+    }
+
+    pub async fn update_mod_case(&self, case: &ModCase) -> Result<()> {
+        sqlx::query!(
+            "update mod_cases set reason=?, evidence=?, log_channel_id=?, log_message_id=? where id=?",
+            case.reason,
+            case.evidence,
+            case.log_channel_id,
+            case.log_message_id,
+            case.id
+        )
+        .execute(&self.sqlitedb)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn set_mod_case_expired(&self, id: i64, at: i64) -> Result<()> {
+        sqlx::query!(
+            "update mod_cases set expired_at=? where id=? and expired_at is null",
+            at,
+            id
+        )
+        .execute(&self.sqlitedb)
+        .await?;
+        Ok(())
+    }
+
+    /// Ends the user's temporary actions of that kind early, e.g. bans on unban.
+    pub async fn expire_user_mod_cases(
+        &self,
+        guild_id: GuildId,
+        user_id: UserId,
+        action: CaseAction,
+        at: i64,
+    ) -> Result<()> {
+        let guild_id = guild_id.0 as i64;
+        let user_id = user_id.0 as i64;
+        let action = action.name();
+        sqlx::query!(
+            "update mod_cases set expired_at=? where guild_id=? and user_id=? and action=? and expires_at is not null and expired_at is null",
+            at,
+            guild_id,
+            user_id,
+            action
+        )
+        .execute(&self.sqlitedb)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn count_warnings(&self, guild_id: GuildId, user_id: UserId) -> Result<i64> {
+        let guild_id = guild_id.0 as i64;
+        let user_id = user_id.0 as i64;
+        let q = sqlx::query!(
+            r#"select count(*) as "count!: i64" from mod_cases where guild_id=? and user_id=? and action='warn'"#,
+            guild_id,
+            user_id
+        )
+        .fetch_one(&self.sqlitedb)
+        .await?;
+        Ok(q.count)
+    }
+}
+
+/// `gp config mod_log_channel`, or the general log channel.
+pub async fn mod_log_channel(ctx: &Context, guild_id: GuildId) -> Result<Option<ChannelId>> {
+    let db = ctx.get_db().await;
+    Ok(db
+        .get_guild_config_as::<ChannelId>(guild_id, "mod_log_channel")
+        .await?
+        .or(db
+            .get_guild_config_as::<ChannelId>(guild_id, "log_channel")
+            .await?))
+}
+
+pub fn describe_case(case: &ModCase) -> String {
+    let action = case.action();
+    let mut text = MessageBuilder::new();
+    text.push(format!(
+        "{} **Case #{} · {}**",
+        action.emoji(),
+        case.case_number,
+        action.title()
+    ));
+    if let (Some(duration), Some(expires_at)) = (case.duration_secs, case.expires_at) {
+        text.push(format!(
+            " for {}, until <t:{}:f>",
+            format_duration(duration),
+            expires_at
+        ));
+        if case.expired_at.is_some() {
+            text.push(" (ended)");
+        }
+    }
+    text.push_line("")
+        .push_line(format!(
+            "**User:** {} ({})",
+            UserId(case.user_id as u64).mention(),
+            case.user_id
+        ))
+        .push_line(format!(
+            "**Moderator:** {}",
+            UserId(case.moderator_id as u64).mention()
+        ))
+        .push_line(format!("**Reason:** {}", case.reason));
+    if !case.evidence.is_empty() {
+        text.push_line(format!(
+            "**Evidence:** {}",
+            case.evidence.lines().collect::<Vec<_>>().join(" ")
+        ));
+    }
+    text.push(format!("<t:{}:f>", case.created_at));
+    text.build()
+}
+
+/// Posts the case to the mod log, or updates the post when there already is one.
+pub async fn log_case(ctx: &Context, case: &mut ModCase) -> Result<()> {
+    let content = describe_case(case);
+    if let (Some(channel_id), Some(message_id)) = (case.log_channel_id, case.log_message_id) {
+        ChannelId(channel_id as u64)
+            .edit_message(&ctx.http, message_id as u64, |m| m.content(content))
+            .await?;
+        return Ok(());
+    }
+    let guild_id = GuildId(case.guild_id as u64);
+    if let Some(channel_id) = mod_log_channel(ctx, guild_id).await? {
+        let message = channel_id
+            .send_message(&ctx.http, |m| {
+                m.content(content).allowed_mentions(|a| a.empty_parse())
+            })
+            .await?;
+        case.log_channel_id = Some(channel_id.0 as i64);
+        case.log_message_id = Some(message.id.0 as i64);
+        let db = ctx.get_db().await;
+        db.update_mod_case(case).await?;
+    }
+    Ok(())
+}
+
+/// DMs the user about the action, returns whether it went through.
+async fn notify(
+    ctx: &Context,
+    guild_id: GuildId,
+    user_id: UserId,
+    action: CaseAction,
+    reason: &str,
+    duration_secs: Option<i64>,
+) -> bool {
+    let key = match (action, duration_secs) {
+        (CaseAction::Warn, _) => "mod-notice-warn",
+        (CaseAction::Timeout, _) => "mod-notice-timeout",
+        (CaseAction::Kick, _) => "mod-notice-kick",
+        (CaseAction::Ban, Some(_)) => "mod-notice-ban-temporary",
+        (CaseAction::Ban, None) => "mod-notice-ban",
+        (CaseAction::Unban, _) => return false,
+    };
+    let locale = i18n::guild_locale(ctx, Some(guild_id)).await;
+    let guild_name = guild_id
+        .name(&ctx.cache)
+        .unwrap_or_else(|| guild_id.to_string());
+    let content = tr_args(
+        locale,
+        key,
+        &[
+            ("guild", guild_name),
+            ("reason", reason.to_string()),
+            (
+                "duration",
+                duration_secs.map(format_duration).unwrap_or_default(),
+            ),
+        ],
+    );
+    match user_id.create_dm_channel(&ctx.http).await {
+        Ok(channel) => channel.say(&ctx.http, content).await.is_ok(),
+        Err(_) => false,
+    }
+}
+
+/// Takes back the ban notice when the ban didn't go through.
+async fn notify_failed(ctx: &Context, guild_id: GuildId, user_id: UserId) {
+    let locale = i18n::guild_locale(ctx, Some(guild_id)).await;
+    let guild_name = guild_id
+        .name(&ctx.cache)
+        .unwrap_or_else(|| guild_id.to_string());
+    if let Ok(channel) = user_id.create_dm_channel(&ctx.http).await {
+        channel
+            .say(
+                &ctx.http,
+                tr_args(locale, "mod-notice-ban-failed", &[("guild", guild_name)]),
+            )
+            .await
+            .ok();
+    }
+}
+
+/// Carries out the action on Discord.
+async fn execute(
+    ctx: &Context,
+    guild_id: GuildId,
+    user_id: UserId,
+    action: CaseAction,
+    reason: &str,
+    duration_secs: Option<i64>,
+    delete_message_days: u8,
+) -> Result<()> {
+    match action {
+        CaseAction::Warn => {}
+        CaseAction::Timeout => {
+            let until = Timestamp::from_unix_timestamp(
+                Timestamp::now().unix_timestamp() + duration_secs.unwrap_or(0),
+            )?;
+            guild_id
+                .edit_member(&ctx.http, user_id, |m| {
+                    m.disable_communication_until_datetime(until)
+                })
+                .await?;
+        }
+        CaseAction::Kick => {
+            guild_id
+                .kick_with_reason(&ctx.http, user_id, reason)
+                .await?
+        }
+        CaseAction::Ban => {
+            guild_id
+                .ban_with_reason(&ctx.http, user_id, delete_message_days, reason)
+                .await?
+        }
+        CaseAction::Unban => guild_id.unban(&ctx.http, user_id).await?,
+    }
+    Ok(())
+}
+
+/// The highest role position of a member, the owner ranks above every role.
+async fn rank(ctx: &Context, guild_id: GuildId, user_id: UserId) -> Option<i64> {
+    let guild = guild_id.to_guild_cached(&ctx.cache)?;
+    if guild.owner_id == user_id {
+        return Some(i64::MAX);
+    }
+    let member = guild_id.member(ctx, user_id).await.ok()?;
+    Some(
+        member
+            .roles
+            .iter()
+            .filter_map(|r| guild.roles.get(r))
+            .map(|r| r.position)
+            .max()
+            .unwrap_or(0),
+    )
+}
+
+/// Refuses acting on members that rank at or above the moderator or the bot.
+async fn check_hierarchy(
+    ctx: &Context,
+    guild_id: GuildId,
+    user_id: UserId,
+    moderator_id: Option<UserId>,
+) -> Result<()> {
+    // Not a member, nothing to outrank
+    let target = match rank(ctx, guild_id, user_id).await {
+        Some(rank) => rank,
+        None => return Ok(()),
+    };
+    if let Some(moderator_id) = moderator_id {
+        if rank(ctx, guild_id, moderator_id).await.unwrap_or(0) <= target {
+            anyhow::bail!("they rank at or above you");
+        }
+    }
+    if rank(ctx, guild_id, ctx.cache.current_user_id())
+        .await
+        .unwrap_or(0)
+        <= target
+    {
+        anyhow::bail!("they rank at or above me");
+    }
+    Ok(())
+}
+
+/// Takes the action, notifies the user and records the case. Shared with the automated
+/// moderation features, which act as the bot.
+#[allow(clippy::too_many_arguments)]
+pub async fn open_case(
+    ctx: &Context,
+    guild_id: GuildId,
+    user_id: UserId,
+    moderator_id: UserId,
+    action: CaseAction,
+    reason: &str,
+    duration_secs: Option<i64>,
+    evidence: &str,
+    delete_message_days: u8,
+) -> Result<(ModCase, bool)> {
+    if matches!(
+        action,
+        CaseAction::Timeout | CaseAction::Kick | CaseAction::Ban
+    ) {
+        // People acting through the bot can't reach past their own rank
+        let moderator = (moderator_id != ctx.cache.current_user_id()).then_some(moderator_id);
+        check_hierarchy(ctx, guild_id, user_id, moderator).await?;
+    }
+    // Once banned they may not share a server with the bot anymore
+    let notified_first = action == CaseAction::Ban
+        && notify(ctx, guild_id, user_id, action, reason, duration_secs).await;
+    if let Err(e) = execute(
+        ctx,
+        guild_id,
+        user_id,
+        action,
+        reason,
+        duration_secs,
+        delete_message_days,
+    )
+    .await
+    {
+        if notified_first {
+            notify_failed(ctx, guild_id, user_id).await;
+        }
+        return Err(e);
+    }
+    let notified = notified_first
+        || (action != CaseAction::Ban
+            && notify(ctx, guild_id, user_id, action, reason, duration_secs).await);
+
+    let db = ctx.get_db().await;
+    // A new ban replaces earlier ones, or their expiry would lift it
+    if matches!(action, CaseAction::Unban | CaseAction::Ban) {
+        db.expire_user_mod_cases(
+            guild_id,
+            user_id,
+            CaseAction::Ban,
+            Timestamp::now().unix_timestamp(),
+        )
+        .await?;
+    }
+    let mut case = db
+        .add_mod_case(
+            guild_id,
+            user_id,
+            moderator_id,
+            action,
+            reason,
+            duration_secs,
+            evidence,
+        )
+        .await?;
+    if let Err(e) = log_case(ctx, &mut case).await {
+        eprintln!(
+            "Failed to log case #{} in {}: {}",
+            case.case_number, guild_id, e
+        );
+    }
+    Ok((case, notified))
+}
+
+async fn expire(ctx: &Context, case: &ModCase) -> Result<()> {
+    let guild_id = GuildId(case.guild_id as u64);
+    let user_id = UserId(case.user_id as u64);
+    // Discord lifts timeouts by itself
+    if case.action() == CaseAction::Ban {
+        guild_id.unban(&ctx.http, user_id).await.ok();
+    }
+    let db = ctx.get_db().await;
+    db.set_mod_case_expired(case.id, Timestamp::now().unix_timestamp())
+        .await?;
+    if let Some(channel_id) = mod_log_channel(ctx, guild_id).await? {
+        channel_id
+            .send_message(&ctx.http, |m| {
+                m.content(format!(
+                    "⌛ The {} of {} from case #{} ended",
+                    case.action().name(),
+                    user_id.mention(),
+                    case.case_number
+                ))
+                .allowed_mentions(|a| a.empty_parse())
+            })
+            .await?;
+    }
+    Ok(())
+}
+
+/// Lifts temporary bans and closes timeouts once their time is up, the expiry
+/// times are in the database so restarts don't lose them.
+pub async fn run_expiry(ctx: Context) {
+    loop {
+        let db = ctx.get_db().await;
+        match db
+            .get_expired_mod_cases(Timestamp::now().unix_timestamp())
+            .await
+        {
+            Ok(cases) => {
+                for case in &cases {
+                    if let Err(e) = expire(&ctx, case).await {
+                        eprintln!(
+                            "Failed to end case #{} in {}: {}",
+                            case.case_number, case.guild_id, e
+                        );
+                    }
+                }
+            }
+            Err(e) => eprintln!("Failed to get expired cases: {}", e),
+        }
+        tokio::time::sleep(Duration::from_secs(60)).await;
+    }
+}
+
+/// Message links separated by spaces or new lines.
+fn parse_evidence(s: &str) -> String {
+    s.split_whitespace().collect::<Vec<_>>().join("\n")
+}
+
+async fn take_action(
+    mci: &ApplicationCommandInteraction,
+    ctx: &Context,
+    action: CaseAction,
+) -> String {
+    let guild_id = mci.guild_id.unwrap();
+    let mut user: Option<&User> = None;
+    let mut reason = "No reason given".to_string();
+    let mut duration = None;
+    let mut evidence = String::new();
+    let mut delete_message_days = 0;
+    for option in &mci.data.options {
+        match (option.name.as_str(), &option.resolved) {
+            ("user", Some(CommandDataOptionValue::User(u, _))) => user = Some(u),
+            ("reason", Some(CommandDataOptionValue::String(s))) => reason = s.clone(),
+            ("duration", Some(CommandDataOptionValue::String(s))) => match parse_duration(s) {
+                Some(secs) => duration = Some(secs),
+                None => return format!("Invalid duration `{}`, use e.g. `30m`, `12h` or `7d`", s),
+            },
+            ("evidence", Some(CommandDataOptionValue::String(s))) => evidence = parse_evidence(s),
+            ("delete_messages", Some(CommandDataOptionValue::Integer(i))) => {
+                delete_message_days = (*i).clamp(0, 7) as u8
+            }
+            _ => {}
+        }
+    }
+    let user = match user {
+        Some(user) => user,
+        None => return "No user given".to_string(),
+    };
+    if user.id == mci.user.id {
+        return "You can't do that to yourself".to_string();
+    }
+    if user.id == ctx.cache.current_user_id() {
+        return "I won't do that to myself".to_string();
+    }
+    match (action, duration) {
+        (CaseAction::Timeout, None) => return "A timeout needs a duration".to_string(),
+        (CaseAction::Timeout, Some(secs)) if secs > MAX_TIMEOUT_SECS => {
+            return "Timeouts can't be longer than 28 days".to_string()
+        }
+        (CaseAction::Warn | CaseAction::Kick | CaseAction::Unban, Some(_)) => duration = None,
+        _ => {}
+    }
+
+    match open_case(
+        ctx,
+        guild_id,
+        user.id,
+        mci.user.id,
+        action,
+        &reason,
+        duration,
+        &evidence,
+        delete_message_days,
+    )
+    .await
+    {
+        Ok((case, notified)) => {
+            let mut text = format!(
+                "Case #{}: {} {} ({})",
+                case.case_number,
+                action.title(),
+                user.mention(),
+                user.tag()
+            );
+            if let Some(secs) = duration {
+                text.push_str(&format!(" for {}", format_duration(secs)));
+            }
+            if action != CaseAction::Unban && !notified {
+                text.push_str(", I couldn't DM them");
+            }
+            text
+        }
+        Err(e) => format!("Failed to {} {}: {}", action.name(), user.tag(), e),
+    }
+}
+
+async fn case_command(mci: &ApplicationCommandInteraction, ctx: &Context) -> String {
+    let guild_id = mci.guild_id.unwrap();
+    let subcommand = match mci.data.options.first() {
+        Some(x) => x,
+        None => return "No subcommand".to_string(),
+    };
+    let mut number = 0;
+    let mut reason = None;
+    let mut evidence = None;
+    for option in &subcommand.options {
+        match (option.name.as_str(), &option.resolved) {
+            ("number", Some(CommandDataOptionValue::Integer(i))) => number = *i,
+            ("reason", Some(CommandDataOptionValue::String(s))) => reason = Some(s.clone()),
+            ("evidence", Some(CommandDataOptionValue::String(s))) => {
+                evidence = Some(parse_evidence(s))
+            }
+            _ => {}
+        }
+    }
+    let db = ctx.get_db().await;
+    let mut case = match db.get_mod_case(guild_id, number).await.unwrap() {
+        Some(case) => case,
+        None => return format!("There is no case #{}", number),
+    };
+
+    match subcommand.name.as_str() {
+        "view" => describe_case(&case),
+        "edit" => {
+            if reason.is_none() && evidence.is_none() {
+                return "Nothing to change, give a reason or evidence".to_string();
+            }
+            if let Some(reason) = reason {
+                case.reason = reason;
+            }
+            if let Some(evidence) = evidence {
+                case.evidence = evidence;
+            }
+            db.update_mod_case(&case).await.unwrap();
+            if let Err(e) = log_case(ctx, &mut case).await {
+                eprintln!(
+                    "Failed to update the log of case #{} in {}: {}",
+                    case.case_number, guild_id, e
+                );
+            }
+            format!("Updated:\n{}", describe_case(&case))
+        }
+        _ => "Unknown subcommand".to_string(),
+    }
+}
+
+async fn history(mci: &ApplicationCommandInteraction, ctx: &Context) -> String {
+    let guild_id = mci.guild_id.unwrap();
+    let user = match mci.data.options.first().and_then(|x| x.resolved.as_ref()) {
+        Some(CommandDataOptionValue::User(user, _)) => user,
+        _ => return "No user given".to_string(),
+    };
+    let db = ctx.get_db().await;
+    let cases = db.get_user_mod_cases(guild_id, user.id).await.unwrap();
+    let mut text = MessageBuilder::new();
+    text.push_line(format!(
+        "**{} cases of** {} ({})",
+        cases.len(),
+        user.mention(),
+        user.tag()
+    ));
+    for case in &cases {
+        let action = case.action();
+        let mut line = format!(
+            "{} #{} <t:{}:d> **{}**",
+            action.emoji(),
+            case.case_number,
+            case.created_at,
+            action.name()
+        );
+        if let Some(secs) = case.duration_secs {
+            line.push_str(&format!(" ({})", format_duration(secs)));
+        }
+        line.push_str(&format!(
+            " by {}: {}",
+            UserId(case.moderator_id as u64).mention(),
+            case.reason
+        ));
+        // Keep to a single message
+        if text.0.len() + line.len() > 1900 {
+            text.push_line("…");
+            break;
+        }
+        text.push_line(line);
+    }
+    text.build()
+}
+
+/// `/warn`, `/timeout`, `/kick`, `/ban`, `/unban`, `/case` and `/history`.
+pub async fn responder(mci: &ApplicationCommandInteraction, ctx: &Context) {
+    // Kicking and banning wait on DMs and the mod log
+    mci.create_interaction_response(&ctx.http, |r| {
+        r.kind(InteractionResponseType::DeferredChannelMessageWithSource)
+            .interaction_response_data(|d| d.flags(MessageFlags::EPHEMERAL))
+    })
+    .await
+    .unwrap();
+
+    let response = match mci.data.name.as_str() {
+        "case" => case_command(mci, ctx).await,
+        "history" => history(mci, ctx).await,
+        name => match CaseAction::parse(name) {
+            Some(action) => take_action(mci, ctx, action).await,
+            None => return,
+        },
+    };
+
+    mci.edit_original_interaction_response(&ctx.http, |d| {
+        d.content(response).allowed_mentions(|a| a.empty_parse())
+    })
+    .await
+    .unwrap();
+}
@@ -0,0 +1,778 @@
+use super::*;
+use crate::db::Db;
+use serenity::model::{
+    application::interaction::{
+        application_command::{ApplicationCommandInteraction, CommandDataOptionValue},
+        MessageFlags,
+    },
+    channel::Attachment,
+    id::UserId,
+    Timestamp,
+};
+use substr::StringUtils;
+
+// Attachments relayed per message, Discord's upload limit for bots
+const MAX_RELAY_BYTES: u64 = 8 * 1024 * 1024;
+
+pub struct ModmailThread {
+    pub id: i64,
+    pub guild_id: i64,
+    pub user_id: i64,
+    pub thread_id: i64,
+    pub opened_at: i64,
+}
+
+pub struct ModmailMessage {
+    pub author_id: i64,
+    pub author_name: String,
+    pub from_staff: bool,
+    pub anonymous: bool,
+    pub content: String,
+    pub attachments: String,
+    pub sent_at: i64,
+}
+
+pub struct ModmailBlock {
+    pub user_id: i64,
+    pub reason: String,
+    pub blocked_at: i64,
+}
+
+pub struct Snippet {
+    pub name: String,
+    pub content: String,
+}
+
+impl Db {
+    pub async fn get_open_modmail(&self, user_id: UserId) -> Result<Option<ModmailThread>> {
+        let user_id = user_id.0 as i64;
+        let q = sqlx::query_as!(
+            ModmailThread,
+            r#"select id as "id!", guild_id, user_id, thread_id, opened_at from modmail_threads where user_id=? and closed_at is null order by opened_at desc"#,
+            user_id
+        )
+        .fetch_optional(&self.sqlitedb)
+        .await?;
+        Ok(q)
+    }
+
+    pub async fn get_modmail_by_thread(
+        &self,
+        thread_id: ChannelId,
+    ) -> Result<Option<ModmailThread>> {
+        let thread_id = thread_id.0 as i64;
+        let q = sqlx::query_as!(
+            ModmailThread,
+            r#"select id as "id!", guild_id, user_id, thread_id, opened_at from modmail_threads where thread_id=? and closed_at is null"#,
+            thread_id
+        )
+        .fetch_optional(&self.sqlitedb)
+        .await?;
+        Ok(q)
+    }
+
+    pub async fn add_modmail(
+        &self,
+        guild_id: GuildId,
+        user_id: UserId,
+        thread_id: ChannelId,
+    ) -> Result<i64> {
+        let guild_id = guild_id.0 as i64;
+        let user_id = user_id.0 as i64;
+        let thread_id = thread_id.0 as i64;
+        let opened_at = Timestamp::now().unix_timestamp();
+        let q = sqlx::query!(
+            "insert into modmail_threads (guild_id, user_id, thread_id, opened_at) values (?, ?, ?, ?)",
+            guild_id,
+            user_id,
+            thread_id,
+            opened_at
+        )
+        .execute(&self.sqlitedb)
+        .await?;
+        Ok(q.last_insert_rowid())
+    }
+
+    pub async fn close_modmail(&self, id: i64, closed_by: UserId, transcript: &str) -> Result<()> {
+        let closed_by = closed_by.0 as i64;
+        let closed_at = Timestamp::now().unix_timestamp();
+        sqlx::query!(
+            "update modmail_threads set closed_at=?, closed_by=?, transcript=? where id=?",
+            closed_at,
+            closed_by,
+            transcript,
+            id
+        )
+        .execute(&self.sqlitedb)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn count_modmail(&self, guild_id: GuildId, user_id: UserId) -> Result<i64> {
+        let guild_id = guild_id.0 as i64;
+        let user_id = user_id.0 as i64;
+        let q = sqlx::query!(
+            r#"select count(*) as "count!: i64" from modmail_threads where guild_id=? and user_id=?"#,
+            guild_id,
+            user_id
+        )
+        .fetch_one(&self.sqlitedb)
+        .await?;
+        Ok(q.count)
+    }
+
+    pub async fn add_modmail_message(
+        &self,
+        modmail_id: i64,
+        author: &User,
+        from_staff: bool,
+        anonymous: bool,
+        content: &str,
+        attachments: &str,
+    ) -> Result<()> {
+        let author_id = author.id.0 as i64;
+        let author_name = author.tag();
+        let sent_at = Timestamp::now().unix_timestamp();
+        sqlx::query!(
+            "insert into modmail_messages (modmail_id, author_id, author_name, from_staff, anonymous, content, attachments, sent_at) values (?, ?, ?, ?, ?, ?, ?, ?)",
+            modmail_id,
+            author_id,
+            author_name,
+            from_staff,
+            anonymous,
+            content,
+            attachments,
+            sent_at
+        )
+        .execute(&self.sqlitedb)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn get_modmail_messages(&self, modmail_id: i64) -> Result<Vec<ModmailMessage>> {
+        let q = sqlx::query_as!(
+            ModmailMessage,
+            "select author_id, author_name, from_staff, anonymous, content, attachments, sent_at from modmail_messages where modmail_id=? order by id",
+            modmail_id
+        )
+        .fetch_all(&self.sqlitedb)
+        .await?;
+        Ok(q)
+    }
+
+    pub async fn is_modmail_blocked(&self, guild_id: GuildId, user_id: UserId) -> Result<bool> {
+        let guild_id = guild_id.0 as i64;
+        let user_id = user_id.0 as i64;
+        let q = sqlx::query!(
+            "select user_id from modmail_blocks where guild_id=? and user_id=?",
+            guild_id,
+            user_id
+        )
+        .fetch_optional(&self.sqlitedb)
+        .await?;
+        Ok(q.is_some())
+    }
+
+    pub async fn block_modmail(
+        &self,
+        guild_id: GuildId,
+        user_id: UserId,
+        blocked_by: UserId,
+        reason: &str,
+    ) -> Result<()> {
+        let guild_id = guild_id.0 as i64;
+        let user_id = user_id.0 as i64;
+        let blocked_by = blocked_by.0 as i64;
+        let blocked_at = Timestamp::now().unix_timestamp();
+        sqlx::query!(
+            "insert into modmail_blocks (guild_id, user_id, blocked_by, reason, blocked_at) values (?1, ?2, ?3, ?4, ?5) on conflict(guild_id, user_id) do update set blocked_by=?3, reason=?4, blocked_at=?5",
+            guild_id,
+            user_id,
+            blocked_by,
+            reason,
+            blocked_at
+        )
+        .execute(&self.sqlitedb)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn unblock_modmail(&self, guild_id: GuildId, user_id: UserId) -> Result<bool> {
+        let guild_id = guild_id.0 as i64;
+        let user_id = user_id.0 as i64;
+        let q = sqlx::query!(
+            "delete from modmail_blocks where guild_id=? and user_id=?",
+            guild_id,
+            user_id
+        )
+        .execute(&self.sqlitedb)
+        .await?;
+        Ok(q.rows_affected() > 0)
+    }
+
+    pub async fn get_modmail_blocks(&self, guild_id: GuildId) -> Result<Vec<ModmailBlock>> {
+        let guild_id = guild_id.0 as i64;
+        let q = sqlx::query_as!(
+            ModmailBlock,
+            "select user_id, reason, blocked_at from modmail_blocks where guild_id=? order by blocked_at",
+            guild_id
+        )
+        .fetch_all(&self.sqlitedb)
+        .await?;
+        Ok(q)
+    }
+
+    pub async fn set_snippet(
+        &self,
+        guild_id: GuildId,
+        name: &str,
+        content: &str,
+        created_by: UserId,
+    ) -> Result<()> {
+        let guild_id = guild_id.0 as i64;
+        let created_by = created_by.0 as i64;
+        sqlx::query!(
+            "insert into modmail_snippets (guild_id, name, content, created_by) values (?1, ?2, ?3, ?4) on conflict(guild_id, name) do update set content=?3, created_by=?4",
+            guild_id,
+            name,
+            content,
+            created_by
+        )
+        .execute(&self.sqlitedb)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn remove_snippet(&self, guild_id: GuildId, name: &str) -> Result<bool> {
+        let guild_id = guild_id.0 as i64;
+        let q = sqlx::query!(
+            "delete from modmail_snippets where guild_id=? and name=?",
+            guild_id,
+            name
+        )
+        .execute(&self.sqlitedb)
+        .await?;
+        Ok(q.rows_affected() > 0)
+    }
+
+    pub async fn get_snippets(&self, guild_id: GuildId) -> Result<Vec<Snippet>> {
+        let guild_id = guild_id.0 as i64;
+        let q = sqlx::query_as!(
+            Snippet,
+            "select name, content from modmail_snippets where guild_id=? order by name",
+            guild_id
+        )
+        .fetch_all(&self.sqlitedb)
+        .await?;
+        Ok(q)
+    }
+}
+
+/// Copies of the attachments to upload elsewhere, as many as fit.
+async fn download(attachments: &[Attachment]) -> Vec<(Vec<u8>, String)> {
+    let mut files = Vec::new();
+    let mut size = 0;
+    for attachment in attachments {
+        size += attachment.size;
+        if size > MAX_RELAY_BYTES {
+            break;
+        }
+        if let Ok(data) = attachment.download().await {
+            files.push((data, attachment.filename.clone()));
+        }
+    }
+    files
+}
+
+fn attachment_urls(attachments: &[Attachment]) -> String {
+    attachments
+        .iter()
+        .map(|x| x.url.clone())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// The first server with modmail the user is a member of.
+async fn find_guild(ctx: &Context, user_id: UserId) -> Result<Option<(GuildId, ChannelId)>> {
+    let db = ctx.get_db().await;
+    for (guild_id, value) in db.get_guild_configs("modmail_channel").await? {
+        if let Ok(channel_id) = value.parse::<ChannelId>() {
+            if guild_id.member(ctx, user_id).await.is_ok() {
+                return Ok(Some((guild_id, channel_id)));
+            }
+        }
+    }
+    Ok(None)
+}
+
+/// Starts a staff thread for the user, `None` when they can't use modmail.
+async fn open(ctx: &Context, msg: &Message) -> Result<Option<ModmailThread>> {
+    let (guild_id, channel_id) = match find_guild(ctx, msg.author.id).await? {
+        Some(x) => x,
+        None => return Ok(None),
+    };
+    let db = ctx.get_db().await;
+    let locale = i18n::guild_locale(ctx, Some(guild_id)).await;
+    let guild_name = guild_id
+        .name(&ctx.cache)
+        .unwrap_or_else(|| guild_id.to_string());
+    if db.is_modmail_blocked(guild_id, msg.author.id).await? {
+        msg.channel_id
+            .say(
+                &ctx.http,
+                tr_args(locale, "modmail-blocked", &[("guild", guild_name)]),
+            )
+            .await?;
+        return Ok(None);
+    }
+
+    let previous = db.count_modmail(guild_id, msg.author.id).await?;
+    let member = guild_id.member(ctx, msg.author.id).await.ok();
+    let joined = member.as_ref().and_then(|x| x.joined_at).map_or_else(
+        || "-".to_string(),
+        |x| format!("<t:{}:R>", x.unix_timestamp()),
+    );
+    let roles = member
+        .as_ref()
+        .map(|x| {
+            x.roles
+                .iter()
+                .map(|r| r.mention().to_string())
+                .collect::<Vec<_>>()
+                .join(" ")
+        })
+        .filter(|x| !x.is_empty())
+        .unwrap_or_else(|| "-".to_string());
+    let staff_role = support_stats::get_staff_role(ctx, guild_id).await;
+    let opener = channel_id
+        .send_message(&ctx.http, |m| {
+            if let Some(role_id) = staff_role {
+                m.content(role_id.mention());
+            }
+            m.embed(|e| {
+                e.title("New modmail")
+                    .author(|a| a.name(msg.author.tag()).icon_url(msg.author.face()))
+                    .description(format!("{} ({})", msg.author.mention(), msg.author.id))
+                    .field(
+                        "Account created",
+                        format!("<t:{}:R>", msg.author.created_at().unix_timestamp()),
+                        true,
+                    )
+                    .field("Joined", joined, true)
+                    .field("Previous threads", previous, true)
+                    .field("Roles", roles.as_str().substring(0, 1024), false)
+                    .footer(|f| {
+                        f.text("Answer with /reply, or /areply to stay anonymous. Anything else in the thread stays between staff. /close ends it.")
+                    })
+                    .timestamp(Timestamp::now())
+            })
+        })
+        .await?;
+    let thread = channel_id
+        .create_public_thread(&ctx.http, opener.id, |t| {
+            t.name(msg.author.tag().as_str().substring(0, 100))
+        })
+        .await?;
+    db.add_modmail(guild_id, msg.author.id, thread.id).await?;
+    msg.channel_id
+        .say(
+            &ctx.http,
+            tr_args(locale, "modmail-opened", &[("guild", guild_name)]),
+        )
+        .await?;
+    println!("Opened modmail for {} in {}", msg.author.tag(), guild_id);
+    db.get_open_modmail(msg.author.id).await
+}
+
+/// Passes a direct message on to the user's staff thread, opening one if needed.
+pub async fn relay_from_user(ctx: &Context, msg: &Message) -> Result<()> {
+    let db = ctx.get_db().await;
+    let modmail = match db.get_open_modmail(msg.author.id).await? {
+        Some(modmail) => modmail,
+        None => match open(ctx, msg).await? {
+            Some(modmail) => modmail,
+            None => return Ok(()),
+        },
+    };
+
+    let files = download(&msg.attachments).await;
+    let sent = ChannelId(modmail.thread_id as u64)
+        .send_message(&ctx.http, |m| {
+            m.content(
+                format!("📥 **{}**: {}", msg.author.tag(), msg.content)
+                    .as_str()
+                    .substring(0, 2000),
+            )
+            .allowed_mentions(|a| a.empty_parse());
+            for (data, filename) in &files {
+                m.add_file((data.as_slice(), filename.as_str()));
+            }
+            m
+        })
+        .await;
+    match sent {
+        Ok(_) => {
+            db.add_modmail_message(
+                modmail.id,
+                &msg.author,
+                false,
+                false,
+                &msg.content,
+                &attachment_urls(&msg.attachments),
+            )
+            .await?;
+            msg.react(&ctx.http, '✅').await?;
+        }
+        Err(e) => {
+            eprintln!("Failed to relay modmail {}: {}", modmail.id, e);
+            msg.react(&ctx.http, '❌').await?;
+        }
+    }
+    Ok(())
+}
+
+async fn respond_ephemeral(mci: &ApplicationCommandInteraction, ctx: &Context, content: &str) {
+    mci.create_interaction_response(&ctx.http, |r| {
+        r.kind(InteractionResponseType::ChannelMessageWithSource)
+            .interaction_response_data(|d| d.content(content).flags(MessageFlags::EPHEMERAL))
+    })
+    .await
+    .unwrap();
+}
+
+/// `/reply` and `/areply`, sends the staff's answer to the user.
+pub async fn reply(mci: &ApplicationCommandInteraction, ctx: &Context) {
+    let anonymous = mci.data.name == "areply";
+    let guild_id = mci.guild_id.unwrap();
+    let db = ctx.get_db().await;
+    let modmail = match db.get_modmail_by_thread(mci.channel_id).await.unwrap() {
+        Some(modmail) => modmail,
+        None => {
+            respond_ephemeral(mci, ctx, "This isn't an open modmail thread").await;
+            return;
+        }
+    };
+
+    let mut message = None;
+    let mut snippet = None;
+    let mut attachment = None;
+    for option in &mci.data.options {
+        match (option.name.as_str(), &option.resolved) {
+            ("message", Some(CommandDataOptionValue::String(s))) => message = Some(s.clone()),
+            ("snippet", Some(CommandDataOptionValue::String(s))) => snippet = Some(s.clone()),
+            ("attachment", Some(CommandDataOptionValue::Attachment(a))) => attachment = Some(a),
+            _ => {}
+        }
+    }
+    let snippet = match snippet {
+        Some(name) => {
+            let snippets = db.get_snippets(guild_id).await.unwrap();
+            match snippets
+                .into_iter()
+                .find(|x| x.name.eq_ignore_ascii_case(&name))
+            {
+                Some(snippet) => Some(snippet.content),
+                None => {
+                    respond_ephemeral(mci, ctx, &format!("No snippet named `{}`", name)).await;
+                    return;
+                }
+            }
+        }
+        None => None,
+    };
+    let content = [snippet, message]
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>()
+        .join("\n\n");
+    if content.is_empty() && attachment.is_none() {
+        respond_ephemeral(mci, ctx, "Give a message, a snippet or an attachment").await;
+        return;
+    }
+
+    // Attachments can take a while
+    mci.create_interaction_response(&ctx.http, |r| {
+        r.kind(InteractionResponseType::DeferredChannelMessageWithSource)
+    })
+    .await
+    .unwrap();
+    let files = match attachment {
+        Some(attachment) => download(std::slice::from_ref(attachment)).await,
+        None => Vec::new(),
+    };
+    let locale = i18n::guild_locale(ctx, Some(guild_id)).await;
+    let guild_name = guild_id
+        .name(&ctx.cache)
+        .unwrap_or_else(|| guild_id.to_string());
+    let signature = if anonymous {
+        tr_args(locale, "modmail-reply-anonymous", &[("guild", guild_name)])
+    } else {
+        let name = mci
+            .member
+            .as_ref()
+            .and_then(|x| x.nick.clone())
+            .unwrap_or_else(|| mci.user.name.clone());
+        tr_args(
+            locale,
+            "modmail-reply-named",
+            &[("name", name), ("guild", guild_name)],
+        )
+    };
+
+    let sent = match UserId(modmail.user_id as u64)
+        .create_dm_channel(&ctx.http)
+        .await
+    {
+        Ok(channel) => {
+            channel
+                .send_message(&ctx.http, |m| {
+                    m.content(
+                        format!("{}\n{}", signature, content)
+                            .as_str()
+                            .substring(0, 2000),
+                    );
+                    for (data, filename) in &files {
+                        m.add_file((data.as_slice(), filename.as_str()));
+                    }
+                    m
+                })
+                .await
+        }
+        Err(e) => Err(e),
+    };
+    let text = match sent {
+        Ok(sent) => {
+            db.add_modmail_message(
+                modmail.id,
+                &mci.user,
+                true,
+                anonymous,
+                &content,
+                &attachment_urls(&sent.attachments),
+            )
+            .await
+            .unwrap();
+            let mut text = format!(
+                "📤 **{}**{}: {}",
+                mci.user.tag(),
+                if anonymous { " (anonymous)" } else { "" },
+                content
+            );
+            for attachment in &sent.attachments {
+                text.push_str(&format!("\n📎 {}", attachment.url));
+            }
+            text
+        }
+        Err(e) => format!("Couldn't DM them: {}", e),
+    };
+    mci.edit_original_interaction_response(&ctx.http, |d| {
+        d.content(text.as_str().substring(0, 2000))
+            .allowed_mentions(|a| a.empty_parse())
+    })
+    .await
+    .unwrap();
+}
+
+pub async fn is_modmail_thread(ctx: &Context, channel_id: ChannelId) -> bool {
+    let db = ctx.get_db().await;
+    db.get_modmail_by_thread(channel_id)
+        .await
+        .ok()
+        .flatten()
+        .is_some()
+}
+
+fn transcript(modmail: &ModmailThread, messages: &[ModmailMessage]) -> String {
+    let mut lines = vec![format!(
+        "Modmail with user {} opened {}",
+        modmail.user_id,
+        Timestamp::from_unix_timestamp(modmail.opened_at).unwrap_or_else(|_| Timestamp::now())
+    )];
+    for message in messages {
+        let at =
+            Timestamp::from_unix_timestamp(message.sent_at).unwrap_or_else(|_| Timestamp::now());
+        let from = match (message.from_staff, message.anonymous) {
+            (true, true) => format!(
+                "{} ({}, staff, anonymous)",
+                message.author_name, message.author_id
+            ),
+            (true, false) => format!("{} ({}, staff)", message.author_name, message.author_id),
+            _ => format!("{} ({})", message.author_name, message.author_id),
+        };
+        lines.push(format!("[{}] {}: {}", at, from, message.content));
+        for url in message.attachments.lines() {
+            lines.push(format!("    {}", url));
+        }
+    }
+    lines.join("\n")
+}
+
+/// `/close` in a modmail thread, saves the transcript and lets the user know.
+pub async fn close(mci: &ApplicationCommandInteraction, ctx: &Context) {
+    let db = ctx.get_db().await;
+    let modmail = match db.get_modmail_by_thread(mci.channel_id).await.unwrap() {
+        Some(modmail) => modmail,
+        None => return,
+    };
+    let guild_id = GuildId(modmail.guild_id as u64);
+    mci.create_interaction_response(&ctx.http, |r| {
+        r.kind(InteractionResponseType::DeferredChannelMessageWithSource)
+    })
+    .await
+    .unwrap();
+
+    let messages = db.get_modmail_messages(modmail.id).await.unwrap();
+    let transcript = transcript(&modmail, &messages);
+    db.close_modmail(modmail.id, mci.user.id, &transcript)
+        .await
+        .unwrap();
+    let user_id = UserId(modmail.user_id as u64);
+    let locale = i18n::guild_locale(ctx, Some(guild_id)).await;
+    let guild_name = guild_id
+        .name(&ctx.cache)
+        .unwrap_or_else(|| guild_id.to_string());
+    if let Ok(channel) = user_id.create_dm_channel(&ctx.http).await {
+        channel
+            .say(
+                &ctx.http,
+                tr_args(locale, "modmail-closed", &[("guild", guild_name)]),
+            )
+            .await
+            .ok();
+    }
+
+    let filename = format!("modmail-{}-{}.txt", user_id, modmail.id);
+    mci.edit_original_interaction_response(&ctx.http, |d| {
+        d.content(format!(
+            "📪 Closed by {}, {} messages",
+            mci.user.mention(),
+            messages.len()
+        ))
+    })
+    .await
+    .unwrap();
+    // Last in the thread, so thread_update leaves the archived thread alone
+    mci.channel_id
+        .send_message(&ctx.http, |m| {
+            m.content(tr_args(
+                locale,
+                "thread-closed-by",
+                &[("user", mci.user.mention().to_string())],
+            ))
+            .add_file((transcript.as_bytes(), filename.as_str()))
+        })
+        .await
+        .ok();
+    if let Ok(Some(log_channel)) = moderation::mod_log_channel(ctx, guild_id).await {
+        log_channel
+            .send_message(&ctx.http, |m| {
+                m.content(format!(
+                    "📪 Modmail with {} closed by {}, {} messages in {}",
+                    user_id.mention(),
+                    mci.user.mention(),
+                    messages.len(),
+                    mci.channel_id.mention()
+                ))
+                .add_file((transcript.as_bytes(), filename.as_str()))
+                .allowed_mentions(|a| a.empty_parse())
+            })
+            .await
+            .ok();
+    }
+    mci.channel_id
+        .edit_thread(&ctx.http, |t| t.archived(true).locked(true))
+        .await
+        .ok();
+}
+
+/// `/modmail`, blocked users and snippets.
+pub async fn responder(mci: &ApplicationCommandInteraction, ctx: &Context) {
+    let guild_id = mci.guild_id.unwrap();
+    let subcommand = match mci.data.options.first() {
+        Some(x) => x,
+        None => return,
+    };
+    let db = ctx.get_db().await;
+    let mut user = None;
+    let mut strings = Vec::new();
+    for option in &subcommand.options {
+        match &option.resolved {
+            Some(CommandDataOptionValue::User(u, _)) => user = Some(u),
+            Some(CommandDataOptionValue::String(s)) => strings.push((option.name.as_str(), s)),
+            _ => {}
+        }
+    }
+    let string = |name: &str| {
+        strings
+            .iter()
+            .find(|(x, _)| *x == name)
+            .map(|(_, s)| s.trim().to_string())
+    };
+
+    let response = match (subcommand.name.as_str(), user) {
+        ("block", Some(user)) => {
+            let reason = string("reason").unwrap_or_else(|| "No reason given".to_string());
+            db.block_modmail(guild_id, user.id, mci.user.id, &reason)
+                .await
+                .unwrap();
+            format!("{} can't open modmail anymore", user.tag())
+        }
+        ("unblock", Some(user)) => {
+            if db.unblock_modmail(guild_id, user.id).await.unwrap() {
+                format!("{} can use modmail again", user.tag())
+            } else {
+                format!("{} isn't blocked", user.tag())
+            }
+        }
+        ("blocked", _) => {
+            let blocks = db.get_modmail_blocks(guild_id).await.unwrap();
+            if blocks.is_empty() {
+                "Nobody is blocked".to_string()
+            } else {
+                blocks
+                    .iter()
+                    .map(|x| format!("<@{}> <t:{}:d>: {}", x.user_id, x.blocked_at, x.reason))
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            }
+        }
+        ("snippet-set", _) => match (string("name"), string("content")) {
+            (Some(name), Some(content)) if !name.is_empty() => {
+                let name = name.to_lowercase();
+                db.set_snippet(guild_id, &name, &content, mci.user.id)
+                    .await
+                    .unwrap();
+                format!("Saved `{}`, send it with `/reply snippet:{}`", name, name)
+            }
+            _ => "A snippet needs a name and content".to_string(),
+        },
+        ("snippet-remove", _) => {
+            let name = string("name").unwrap_or_default().to_lowercase();
+            if db.remove_snippet(guild_id, &name).await.unwrap() {
+                format!("Removed `{}`", name)
+            } else {
+                format!("No snippet named `{}`", name)
+            }
+        }
+        ("snippets", _) => {
+            let snippets = db.get_snippets(guild_id).await.unwrap();
+            if snippets.is_empty() {
+                "No snippets yet, add one with `/modmail snippet-set`".to_string()
+            } else {
+                snippets
+                    .iter()
+                    .map(|x| {
+                        format!(
+                            "`{}`: {}",
+                            x.name,
+                            x.content.replace('\n', " ").as_str().substring(0, 100)
+                        )
+                    })
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            }
+        }
+        _ => return,
+    };
+    respond_ephemeral(mci, ctx, response.as_str().substring(0, 2000)).await;
+}
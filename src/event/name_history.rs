@@ -0,0 +1,276 @@
+use super::*;
+use crate::db::Db;
+use serenity::builder::CreateComponents;
+use serenity::model::{
+    application::interaction::{
+        application_command::{ApplicationCommandInteraction, CommandDataOptionValue},
+        message_component::MessageComponentInteraction,
+        MessageFlags,
+    },
+    id::UserId,
+    Timestamp,
+};
+use serenity::utils::MessageBuilder;
+
+const PAGE_SIZE: i64 = 10;
+
+/// The names kept, as stored in the `kind` column. Global display names aren't
+/// exposed by our serenity version yet, they'll go in as `global_name`.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum NameKind {
+    Username,
+    /// Per guild
+    Nickname,
+}
+
+impl NameKind {
+    fn name(self) -> &'static str {
+        match self {
+            NameKind::Username => "username",
+            NameKind::Nickname => "nickname",
+        }
+    }
+}
+
+pub struct NameRecord {
+    pub kind: String,
+    pub name: Option<String>,
+    pub recorded_at: i64,
+}
+
+impl Db {
+    /// Adds the name unless it's the last one recorded, returns whether it did.
+    pub async fn record_name(
+        &self,
+        user_id: UserId,
+        guild_id: Option<GuildId>,
+        kind: NameKind,
+        name: Option<&str>,
+    ) -> Result<bool> {
+        let user_id = user_id.0 as i64;
+        let guild_id = guild_id.map(|x| x.0 as i64);
+        let kind = kind.name();
+        let last = sqlx::query!(
+            "select name from name_history where user_id=? and guild_id is ? and kind=? order by id desc limit 1",
+            user_id,
+            guild_id,
+            kind
+        )
+        .fetch_optional(&self.sqlitedb)
+        .await?;
+        let unchanged = match &last {
+            Some(last) => last.name.as_deref() == name,
+            // Nothing to tell about members who never had a nickname
+            None => name.is_none(),
+        };
+        if unchanged {
+            return Ok(false);
+        }
+        let recorded_at = Timestamp::now().unix_timestamp();
+        sqlx::query!(
+            "insert into name_history (user_id, guild_id, kind, name, recorded_at) values (?, ?, ?, ?, ?)",
+            user_id,
+            guild_id,
+            kind,
+            name,
+            recorded_at
+        )
+        .execute(&self.sqlitedb)
+        .await?;
+        Ok(true)
+    }
+
+    /// Usernames and the nicknames in the guild, latest first.
+    pub async fn get_name_history(
+        &self,
+        user_id: UserId,
+        guild_id: GuildId,
+        offset: i64,
+        limit: i64,
+    ) -> Result<Vec<NameRecord>> {
+        let user_id = user_id.0 as i64;
+        let guild_id = guild_id.0 as i64;
+        let q = sqlx::query_as!(
+            NameRecord,
+            "select kind, name, recorded_at from name_history where user_id=? and (guild_id is null or guild_id=?)
+            order by recorded_at desc, id desc limit ? offset ?",
+            user_id,
+            guild_id,
+            limit,
+            offset
+        )
+        .fetch_all(&self.sqlitedb)
+        .await?;
+        Ok(q)
+    }
+
+    /// Who last went by the name, as a nickname, a username or a tag.
+    pub async fn find_name_owner(&self, name: &str) -> Result<Option<UserId>> {
+        let q = sqlx::query!(
+            "select user_id from name_history where lower(name)=lower(?1)
+            or lower(substr(name, 1, length(?1) + 1))=lower(?1 || '#') order by id desc limit 1",
+            name
+        )
+        .fetch_optional(&self.sqlitedb)
+        .await?;
+        Ok(q.map(|x| UserId(x.user_id as u64)))
+    }
+
+    pub async fn count_name_history(&self, user_id: UserId, guild_id: GuildId) -> Result<i64> {
+        let user_id = user_id.0 as i64;
+        let guild_id = guild_id.0 as i64;
+        let q = sqlx::query!(
+            r#"select count(*) as "count!: i64" from name_history where user_id=? and (guild_id is null or guild_id=?)"#,
+            user_id,
+            guild_id
+        )
+        .fetch_one(&self.sqlitedb)
+        .await?;
+        Ok(q.count)
+    }
+}
+
+pub async fn record_member(ctx: &Context, member: &Member) -> Result<()> {
+    let db = ctx.get_db().await;
+    db.record_name(
+        member.user.id,
+        None,
+        NameKind::Username,
+        Some(&member.user.tag()),
+    )
+    .await?;
+    db.record_name(
+        member.user.id,
+        Some(member.guild_id),
+        NameKind::Nickname,
+        member.nick.as_deref(),
+    )
+    .await?;
+    Ok(())
+}
+
+/// Member updates carry username changes too, the event for user updates is only
+/// sent for the bot itself.
+pub async fn member_updated(ctx: &Context, member: &Member) {
+    if let Err(e) = record_member(ctx, member).await {
+        eprintln!(
+            "Failed to record the names of {} in {}: {}",
+            member.user.id, member.guild_id, e
+        );
+    }
+}
+
+/// Records the current names of every cached member, only what changed since the
+/// last run ends up in the table.
+pub async fn backfill(ctx: Context) {
+    let mut recorded = 0;
+    for guild_id in ctx.cache.guilds() {
+        let guild = match guild_id.to_guild_cached(&ctx.cache) {
+            Some(guild) => guild,
+            None => continue,
+        };
+        for member in guild.members.values() {
+            if record_member(&ctx, member).await.is_ok() {
+                recorded += 1;
+            }
+        }
+    }
+    println!("Name history backfilled from {} cached members", recorded);
+}
+
+async fn render_page(
+    ctx: &Context,
+    guild_id: GuildId,
+    user_id: UserId,
+    page: i64,
+) -> Result<(String, CreateComponents)> {
+    let db = ctx.get_db().await;
+    let total = db.count_name_history(user_id, guild_id).await?;
+    let pages = ((total + PAGE_SIZE - 1) / PAGE_SIZE).max(1);
+    let page = page.clamp(0, pages - 1);
+    let records = db
+        .get_name_history(user_id, guild_id, page * PAGE_SIZE, PAGE_SIZE)
+        .await?;
+
+    let mut text = MessageBuilder::new();
+    text.push_line(format!("**Names of** {}", user_id.mention()));
+    if records.is_empty() {
+        text.push_line("Nothing recorded yet");
+    }
+    for record in &records {
+        let name = match &record.name {
+            Some(name) => format!("`{}`", name.replace('`', "'")),
+            None => "*removed*".to_string(),
+        };
+        text.push_line(format!(
+            "<t:{}:d> {}: {}",
+            record.recorded_at, record.kind, name
+        ));
+    }
+    text.push_italic(format!("Page {} of {}", page + 1, pages));
+
+    let mut components = CreateComponents::default();
+    components.create_action_row(|a| {
+        a.create_button(|b| {
+            b.custom_id(format!("names:{}:{}", user_id, page - 1))
+                .label("Previous")
+                .style(ButtonStyle::Secondary)
+                .disabled(page == 0)
+        })
+        .create_button(|b| {
+            b.custom_id(format!("names:{}:{}", user_id, page + 1))
+                .label("Next")
+                .style(ButtonStyle::Secondary)
+                .disabled(page + 1 >= pages)
+        })
+    });
+    Ok((text.build(), components))
+}
+
+/// `/names`
+pub async fn responder(mci: &ApplicationCommandInteraction, ctx: &Context) {
+    let guild_id = mci.guild_id.unwrap();
+    let user_id = mci
+        .data
+        .options
+        .iter()
+        .find_map(|x| match &x.resolved {
+            Some(CommandDataOptionValue::User(user, _)) => Some(user.id),
+            _ => None,
+        })
+        .unwrap_or(mci.user.id);
+    let (content, components) = render_page(ctx, guild_id, user_id, 0).await.unwrap();
+
+    mci.create_interaction_response(&ctx.http, |r| {
+        r.kind(InteractionResponseType::ChannelMessageWithSource)
+            .interaction_response_data(|d| {
+                d.content(content)
+                    .set_components(components)
+                    .allowed_mentions(|m| m.empty_users())
+                    .flags(MessageFlags::EPHEMERAL)
+            })
+    })
+    .await
+    .unwrap();
+}
+
+/// The Previous and Next buttons, `names:{user_id}:{page}`.
+pub async fn handle_page(mci: &MessageComponentInteraction, ctx: &Context) {
+    let guild_id = mci.guild_id.unwrap();
+    let mut parts = mci.data.custom_id.split(':').skip(1);
+    let (user_id, page) = match (
+        parts.next().and_then(|x| x.parse::<u64>().ok()),
+        parts.next().and_then(|x| x.parse::<i64>().ok()),
+    ) {
+        (Some(user_id), Some(page)) => (UserId(user_id), page),
+        _ => return,
+    };
+    let (content, components) = render_page(ctx, guild_id, user_id, page).await.unwrap();
+
+    mci.create_interaction_response(&ctx.http, |r| {
+        r.kind(InteractionResponseType::UpdateMessage)
+            .interaction_response_data(|d| d.content(content).set_components(components))
+    })
+    .await
+    .unwrap();
+}
@@ -0,0 +1,1762 @@
+use super::*;
+use crate::db::{ClientContextExt, Db};
+use serde::{Deserialize, Serialize};
+use serenity::builder::{CreateComponents, CreateInteractionResponse};
+use serenity::model::{
+    application::interaction::{
+        application_command::{ApplicationCommandInteraction, CommandDataOptionValue},
+        message_component::MessageComponentInteraction,
+        modal::ModalSubmitInteraction,
+        MessageFlags,
+    },
+    guild::Role,
+    id::{RoleId, UserId},
+    Permissions, Timestamp,
+};
+use serenity::utils::MessageBuilder;
+use std::{
+    collections::HashSet,
+    str::FromStr,
+    sync::{Mutex, OnceLock},
+};
+use substr::StringUtils;
+
+// Discord limits
+const MAX_OPTIONS: usize = 25;
+const MAX_MODAL_FIELDS: usize = 5;
+const MAX_VALUE_LEN: usize = 75;
+
+/// Sessions not answered for this long have to be started over
+const SESSION_TTL_SECS: i64 = 30 * 60;
+const DEFAULT_INTRO_REMINDER_DAYS: i64 = 3;
+
+/// The onboarding flow behind the "Let's go" button, made of steps answered one after another.
+/// Texts are message ids from the locale catalogues, ids missing from them are shown as they are.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Wizard {
+    pub steps: Vec<Step>,
+    /// Listed in the welcome thread once the new member introduced themselves,
+    /// `text` gets the channel as `{ $channel }`
+    #[serde(default)]
+    pub suggested_channels: Vec<ChannelSuggestion>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ChannelSuggestion {
+    pub channel: ChannelId,
+    pub text: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum StepKind {
+    Select,
+    Buttons,
+    Modal,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Step {
+    pub id: String,
+    #[serde(rename = "type")]
+    pub kind: StepKind,
+    pub prompt: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hint: Option<String>,
+    /// Select menus only, defaults to all the options
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_values: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub placeholder: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub options: Vec<StepOption>,
+    /// Modals only
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub fields: Vec<ModalField>,
+    /// Answers are kept for `/survey results` under the step's id
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub survey: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum OptionStyle {
+    Primary,
+    #[default]
+    Secondary,
+    Success,
+    Danger,
+}
+
+impl From<OptionStyle> for ButtonStyle {
+    fn from(style: OptionStyle) -> Self {
+        match style {
+            OptionStyle::Primary => ButtonStyle::Primary,
+            OptionStyle::Secondary => ButtonStyle::Secondary,
+            OptionStyle::Success => ButtonStyle::Success,
+            OptionStyle::Danger => ButtonStyle::Danger,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct StepOption {
+    pub value: String,
+    pub label: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub emoji: Option<String>,
+    /// Buttons only
+    #[serde(default)]
+    pub style: OptionStyle,
+    /// Names of the roles given to members picking this option
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub roles: Vec<String>,
+    /// Posted in the welcome thread, gets the `channels` as `{ $channel }`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub followup: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub channels: Vec<ChannelId>,
+}
+
+impl StepOption {
+    pub fn reaction(&self) -> Option<ReactionType> {
+        self.emoji
+            .as_deref()
+            .and_then(|x| ReactionType::from_str(x).ok())
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ModalField {
+    pub id: String,
+    pub label: String,
+    #[serde(default)]
+    pub paragraph: bool,
+    #[serde(default)]
+    pub required: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_length: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub placeholder: Option<String>,
+}
+
+impl Wizard {
+    pub fn validate(&self) -> Result<()> {
+        if self.steps.is_empty() {
+            anyhow::bail!("there must be at least one step");
+        }
+        let mut step_ids = HashSet::new();
+        for step in &self.steps {
+            if step.id.is_empty() || !step_ids.insert(step.id.as_str()) {
+                anyhow::bail!("step ids must be unique and not empty: `{}`", step.id);
+            }
+            let mut values = HashSet::new();
+            for option in &step.options {
+                if option.value.is_empty()
+                    || option.value.len() > MAX_VALUE_LEN
+                    || !values.insert(option.value.as_str())
+                {
+                    anyhow::bail!(
+                        "option values in `{}` must be unique and 1 to {} characters long: `{}`",
+                        step.id,
+                        MAX_VALUE_LEN,
+                        option.value
+                    );
+                }
+                if option.label.is_empty() {
+                    anyhow::bail!("option `{}` in `{}` has no label", option.value, step.id);
+                }
+            }
+            match step.kind {
+                StepKind::Select | StepKind::Buttons => {
+                    if step.options.is_empty() || step.options.len() > MAX_OPTIONS {
+                        anyhow::bail!("`{}` must have 1 to {} options", step.id, MAX_OPTIONS);
+                    }
+                    if step
+                        .max_values
+                        .is_some_and(|x| x < 1 || x as usize > step.options.len())
+                    {
+                        anyhow::bail!("`max_values` of `{}` is out of range", step.id);
+                    }
+                }
+                StepKind::Modal => {
+                    if step.fields.is_empty() || step.fields.len() > MAX_MODAL_FIELDS {
+                        anyhow::bail!("`{}` must have 1 to {} fields", step.id, MAX_MODAL_FIELDS);
+                    }
+                    let mut field_ids = HashSet::new();
+                    if step
+                        .fields
+                        .iter()
+                        .any(|x| x.id.is_empty() || x.label.is_empty() || !field_ids.insert(&x.id))
+                    {
+                        anyhow::bail!("fields of `{}` need a label and a unique id", step.id);
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Every role the wizard can give, to clear them when someone goes through it again.
+    pub fn all_roles(&self) -> HashSet<&str> {
+        self.steps
+            .iter()
+            .flat_map(|x| &x.options)
+            .flat_map(|x| &x.roles)
+            .map(|x| x.as_str())
+            .collect()
+    }
+
+    /// The options picked in each step, `answers` holds the values of the steps in order.
+    pub fn chosen_options<'a>(&'a self, answers: &'a [Vec<String>]) -> Vec<&'a StepOption> {
+        self.steps
+            .iter()
+            .zip(answers)
+            .flat_map(|(step, values)| step.options.iter().filter(|x| values.contains(&x.value)))
+            .collect()
+    }
+}
+
+/// Progress of a member through the wizard, `answers` holds one entry per answered step.
+pub struct OnboardingSession {
+    pub answers: Vec<Vec<String>>,
+    pub awaiting_intro: bool,
+    pub updated_at: i64,
+    pub reminded_at: Option<i64>,
+}
+
+/// A member who finished the wizard, or was stuck in `Temp` from before, and has yet to
+/// post in INTRODUCTION_CHANNEL.
+pub struct AwaitingIntroduction {
+    pub guild_id: i64,
+    pub user_id: i64,
+    pub updated_at: i64,
+    pub reminded_at: Option<i64>,
+}
+
+impl Db {
+    pub async fn get_onboarding_wizard(&self, guild_id: GuildId) -> Result<Option<String>> {
+        let guild_id = guild_id.0 as i64;
+        let q = sqlx::query!(
+            "select definition from onboarding_wizards where guild_id=?",
+            guild_id
+        )
+        .fetch_optional(&self.sqlitedb)
+        .await?
+        .map(|x| x.definition);
+        Ok(q)
+    }
+
+    pub async fn set_onboarding_wizard(
+        &self,
+        guild_id: GuildId,
+        definition: &str,
+        updated_at: i64,
+    ) -> Result<()> {
+        let guild_id = guild_id.0 as i64;
+        sqlx::query!(
+            "insert into onboarding_wizards (guild_id, definition, updated_at) values (?1, ?2, ?3) on conflict(guild_id) do update set definition=?2, updated_at=?3",
+            guild_id,
+            definition,
+            updated_at
+        )
+        .execute(&self.sqlitedb)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn remove_onboarding_wizard(&self, guild_id: GuildId) -> Result<()> {
+        let guild_id = guild_id.0 as i64;
+        sqlx::query!("delete from onboarding_wizards where guild_id=?", guild_id)
+            .execute(&self.sqlitedb)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn get_onboarding_session(
+        &self,
+        guild_id: GuildId,
+        user_id: UserId,
+    ) -> Result<Option<OnboardingSession>> {
+        let guild_id = guild_id.0 as i64;
+        let user_id = user_id.0 as i64;
+        let q = sqlx::query!(
+            "select answers, awaiting_intro, updated_at, reminded_at from onboarding_sessions where guild_id=? and user_id=?",
+            guild_id,
+            user_id
+        )
+        .fetch_optional(&self.sqlitedb)
+        .await?;
+        match q {
+            Some(x) => Ok(Some(OnboardingSession {
+                answers: serde_json::from_str(&x.answers)?,
+                awaiting_intro: x.awaiting_intro,
+                updated_at: x.updated_at,
+                reminded_at: x.reminded_at,
+            })),
+            None => Ok(None),
+        }
+    }
+
+    pub async fn save_onboarding_session(
+        &self,
+        guild_id: GuildId,
+        user_id: UserId,
+        session: &OnboardingSession,
+    ) -> Result<()> {
+        let guild_id = guild_id.0 as i64;
+        let user_id = user_id.0 as i64;
+        let answers = serde_json::to_string(&session.answers)?;
+        sqlx::query!(
+            "insert into onboarding_sessions (guild_id, user_id, answers, awaiting_intro, updated_at, reminded_at) values (?1, ?2, ?3, ?4, ?5, ?6)
+            on conflict(guild_id, user_id) do update set answers=?3, awaiting_intro=?4, updated_at=?5, reminded_at=?6",
+            guild_id,
+            user_id,
+            answers,
+            session.awaiting_intro,
+            session.updated_at,
+            session.reminded_at
+        )
+        .execute(&self.sqlitedb)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn remove_onboarding_session(
+        &self,
+        guild_id: GuildId,
+        user_id: UserId,
+    ) -> Result<()> {
+        let guild_id = guild_id.0 as i64;
+        let user_id = user_id.0 as i64;
+        sqlx::query!(
+            "delete from onboarding_sessions where guild_id=? and user_id=?",
+            guild_id,
+            user_id
+        )
+        .execute(&self.sqlitedb)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn set_onboarding_completed(
+        &self,
+        guild_id: GuildId,
+        user_id: UserId,
+        completed_at: i64,
+    ) -> Result<()> {
+        let guild_id = guild_id.0 as i64;
+        let user_id = user_id.0 as i64;
+        sqlx::query!(
+            "insert or ignore into onboarding_completions (guild_id, user_id, completed_at) values (?, ?, ?)",
+            guild_id,
+            user_id,
+            completed_at
+        )
+        .execute(&self.sqlitedb)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn has_completed_onboarding(
+        &self,
+        guild_id: GuildId,
+        user_id: UserId,
+    ) -> Result<bool> {
+        let guild_id = guild_id.0 as i64;
+        let user_id = user_id.0 as i64;
+        let q = sqlx::query!(
+            "select completed_at from onboarding_completions where guild_id=? and user_id=?",
+            guild_id,
+            user_id
+        )
+        .fetch_optional(&self.sqlitedb)
+        .await?;
+        Ok(q.is_some())
+    }
+
+    /// Sessions still answering steps that were last touched before `before`.
+    pub async fn remove_abandoned_onboarding_sessions(&self, before: i64) -> Result<()> {
+        sqlx::query!(
+            "delete from onboarding_sessions where awaiting_intro=0 and updated_at<?",
+            before
+        )
+        .execute(&self.sqlitedb)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn get_awaiting_introductions(&self) -> Result<Vec<AwaitingIntroduction>> {
+        let q = sqlx::query_as!(
+            AwaitingIntroduction,
+            "select guild_id, user_id, updated_at, reminded_at from onboarding_sessions where awaiting_intro=1"
+        )
+        .fetch_all(&self.sqlitedb)
+        .await?;
+        Ok(q)
+    }
+
+    pub async fn set_intro_reminded(
+        &self,
+        guild_id: GuildId,
+        user_id: UserId,
+        reminded_at: i64,
+    ) -> Result<()> {
+        let guild_id = guild_id.0 as i64;
+        let user_id = user_id.0 as i64;
+        sqlx::query!(
+            "update onboarding_sessions set reminded_at=? where guild_id=? and user_id=?",
+            reminded_at,
+            guild_id,
+            user_id
+        )
+        .execute(&self.sqlitedb)
+        .await?;
+        Ok(())
+    }
+
+    /// Keeps the member's first introduction, returns whether this was it.
+    pub async fn add_introduction(
+        &self,
+        guild_id: GuildId,
+        user_id: UserId,
+        message_id: MessageId,
+        introduced_at: i64,
+    ) -> Result<bool> {
+        let guild_id = guild_id.0 as i64;
+        let user_id = user_id.0 as i64;
+        let message_id = message_id.0 as i64;
+        let q = sqlx::query!(
+            "insert or ignore into introductions (guild_id, user_id, message_id, introduced_at) values (?, ?, ?, ?)",
+            guild_id,
+            user_id,
+            message_id,
+            introduced_at
+        )
+        .execute(&self.sqlitedb)
+        .await?;
+        Ok(q.rows_affected() > 0)
+    }
+
+    pub async fn has_introduced(&self, guild_id: GuildId, user_id: UserId) -> Result<bool> {
+        let guild_id = guild_id.0 as i64;
+        let user_id = user_id.0 as i64;
+        let q = sqlx::query!(
+            "select introduced_at from introductions where guild_id=? and user_id=?",
+            guild_id,
+            user_id
+        )
+        .fetch_optional(&self.sqlitedb)
+        .await?;
+        Ok(q.is_some())
+    }
+}
+
+/// The original four steps, used until a guild imports its own definition.
+pub async fn default_wizard(db: &Db) -> Wizard {
+    let general_channel = if cfg!(debug_assertions) {
+        ChannelId(947769443516284943)
+    } else {
+        ChannelId(839379835662368768)
+    };
+    let offtopic_channel = if cfg!(debug_assertions) {
+        ChannelId(947769443793141769)
+    } else {
+        ChannelId(972510491933032508)
+    };
+    let selfhosted_questions_channel = if cfg!(debug_assertions) {
+        ChannelId(947769443793141761)
+    } else {
+        ChannelId(879915120510267412)
+    };
+    let questions_channel = db
+        .get_question_channels()
+        .await
+        .ok()
+        .and_then(|x| x.into_iter().next())
+        .map(|x| x.id);
+
+    let mut channel_options = Vec::from([
+        StepOption {
+            value: "JetBrainsIDEs".to_string(),
+            label: "onboarding-role-jetbrains".to_string(),
+            description: Some("onboarding-role-jetbrains-description".to_string()),
+            emoji: Some("🧠".to_string()),
+            roles: vec!["JetBrainsIDEs".to_string()],
+            ..Default::default()
+        },
+        StepOption {
+            value: "DevX".to_string(),
+            label: "onboarding-role-devx".to_string(),
+            description: Some("onboarding-role-devx-description".to_string()),
+            emoji: Some("✨".to_string()),
+            roles: vec!["DevX".to_string()],
+            ..Default::default()
+        },
+        StepOption {
+            value: "SelfHosted".to_string(),
+            label: "onboarding-role-selfhosted".to_string(),
+            description: Some("onboarding-role-selfhosted-description".to_string()),
+            emoji: Some("🏡".to_string()),
+            roles: vec!["SelfHosted".to_string()],
+            ..Default::default()
+        },
+        StepOption {
+            value: "OnMobile".to_string(),
+            label: "onboarding-role-mobile".to_string(),
+            description: Some("onboarding-role-mobile-description".to_string()),
+            emoji: Some("📱".to_string()),
+            roles: vec!["OnMobile".to_string()],
+            ..Default::default()
+        },
+    ]);
+    for prog_role in [
+        "Bash", "C", "CPP", "CSharp", "Docker", "Go", "Haskell", "Java", "Js", "Kotlin", "Lua",
+        "Nim", "Nix", "Node", "Perl", "Php", "Python", "Ruby", "Rust",
+    ] {
+        channel_options.push(StepOption {
+            value: prog_role.to_string(),
+            label: prog_role.to_string(),
+            description: Some("onboarding-role-language-description".to_string()),
+            emoji: Some("📜".to_string()),
+            roles: vec![prog_role.to_string()],
+            ..Default::default()
+        });
+    }
+    channel_options.push(StepOption {
+        value: "none".to_string(),
+        label: "onboarding-channels-skip".to_string(),
+        description: Some("onboarding-channels-skip-description".to_string()),
+        emoji: Some("⏭".to_string()),
+        ..Default::default()
+    });
+
+    let mut found_options = Vec::new();
+    for (value, key, emoji) in [
+        ("FromFriend", "friend", "🫂"),
+        ("FromGoogle", "google", "🔎"),
+        ("FromYouTube", "youtube", "📺"),
+        ("FromTwitter", "twitter", "🐦"),
+        ("FromGitRepo", "gitrepo", "✨"),
+    ] {
+        found_options.push(StepOption {
+            value: value.to_string(),
+            label: format!("onboarding-found-{}", key),
+            description: Some(format!("onboarding-found-{}-description", key)),
+            emoji: Some(emoji.to_string()),
+            ..Default::default()
+        });
+    }
+    found_options.push(StepOption {
+        value: "none".to_string(),
+        label: "onboarding-found-skip".to_string(),
+        emoji: Some("⏭".to_string()),
+        ..Default::default()
+    });
+
+    let mut suggested_channels = vec![
+        ChannelSuggestion {
+            channel: general_channel,
+            text: "intro-channel-general".to_string(),
+        },
+        ChannelSuggestion {
+            channel: offtopic_channel,
+            text: "intro-channel-offtopic".to_string(),
+        },
+    ];
+    if let Some(channel) = questions_channel {
+        suggested_channels.push(ChannelSuggestion {
+            channel,
+            text: "intro-channel-questions".to_string(),
+        });
+    }
+
+    Wizard {
+        steps: vec![
+            Step {
+                id: "channels".to_string(),
+                kind: StepKind::Select,
+                prompt: "onboarding-step-channels".to_string(),
+                hint: None,
+                max_values: None,
+                placeholder: Some("onboarding-channels-placeholder".to_string()),
+                options: channel_options,
+                title: None,
+                fields: vec![],
+                survey: false,
+            },
+            Step {
+                id: "subscribe".to_string(),
+                kind: StepKind::Buttons,
+                prompt: "onboarding-step-subscribe".to_string(),
+                hint: None,
+                max_values: None,
+                placeholder: None,
+                options: vec![
+                    StepOption {
+                        value: "subscribed".to_string(),
+                        label: "onboarding-subscribe-yes".to_string(),
+                        style: OptionStyle::Success,
+                        roles: vec!["Subscriber".to_string()],
+                        ..Default::default()
+                    },
+                    StepOption {
+                        value: "not_subscribed".to_string(),
+                        label: "onboarding-subscribe-no".to_string(),
+                        style: OptionStyle::Danger,
+                        ..Default::default()
+                    },
+                ],
+                title: None,
+                fields: vec![],
+                survey: false,
+            },
+            Step {
+                id: "reason".to_string(),
+                kind: StepKind::Buttons,
+                prompt: "onboarding-step-reason".to_string(),
+                hint: Some("onboarding-step-reason-hint".to_string()),
+                max_values: None,
+                placeholder: None,
+                options: vec![
+                    StepOption {
+                        value: "hangout".to_string(),
+                        label: "onboarding-reason-hangout".to_string(),
+                        emoji: Some("🏄".to_string()),
+                        ..Default::default()
+                    },
+                    StepOption {
+                        value: "gitpodio_help".to_string(),
+                        label: "onboarding-reason-gitpodio-help".to_string(),
+                        emoji: Some("✌️".to_string()),
+                        followup: questions_channel.map(|_| "intro-gitpodio-help".to_string()),
+                        channels: questions_channel.into_iter().collect(),
+                        ..Default::default()
+                    },
+                    StepOption {
+                        value: "selfhosted_help".to_string(),
+                        label: "onboarding-reason-selfhosted-help".to_string(),
+                        emoji: Some("🏡".to_string()),
+                        roles: vec!["SelfHosted".to_string()],
+                        followup: Some("intro-selfhosted-help".to_string()),
+                        channels: vec![selfhosted_questions_channel],
+                        ..Default::default()
+                    },
+                ],
+                title: None,
+                fields: vec![],
+                survey: false,
+            },
+            Step {
+                id: "found".to_string(),
+                kind: StepKind::Select,
+                prompt: "onboarding-step-found".to_string(),
+                hint: None,
+                max_values: Some(5),
+                placeholder: Some("onboarding-found-placeholder".to_string()),
+                options: found_options,
+                title: None,
+                fields: vec![],
+                survey: true,
+            },
+        ],
+        suggested_channels,
+    }
+}
+
+/// The guild's wizard, or the default one when it has none or it can't be parsed.
+pub async fn get_wizard(ctx: &Context, guild_id: GuildId) -> Wizard {
+    let db = ctx.get_db().await;
+    if let Ok(Some(definition)) = db.get_onboarding_wizard(guild_id).await {
+        match serde_yaml::from_str::<Wizard>(&definition) {
+            Ok(wizard) => return wizard,
+            Err(e) => eprintln!("Invalid onboarding wizard in {}: {}", guild_id, e),
+        }
+    }
+    default_wizard(&db).await
+}
+
+fn step_content(wizard: &Wizard, index: usize, locale: &str) -> String {
+    let step = &wizard.steps[index];
+    let mut content = format!(
+        "**[{}/{}]:** {}",
+        index + 1,
+        wizard.steps.len(),
+        tr(locale, &step.prompt)
+    );
+    if let Some(hint) = &step.hint {
+        content.push('\n');
+        content.push_str(&tr(locale, hint));
+    }
+    content
+}
+
+fn step_components(step: &Step, index: usize, locale: &str, disabled: bool) -> CreateComponents {
+    let mut components = CreateComponents::default();
+    match step.kind {
+        StepKind::Select => {
+            components.create_action_row(|a| {
+                a.create_select_menu(|s| {
+                    if let Some(placeholder) = &step.placeholder {
+                        s.placeholder(tr(locale, placeholder));
+                    }
+                    s.options(|o| {
+                        for option in &step.options {
+                            o.create_option(|opt| {
+                                opt.label(tr(locale, &option.label).substring(0, 100))
+                                    .value(&option.value);
+                                if let Some(description) = &option.description {
+                                    opt.description(tr(locale, description).substring(0, 100));
+                                }
+                                if let Some(emoji) = option.reaction() {
+                                    opt.emoji(emoji);
+                                }
+                                opt
+                            });
+                        }
+                        o
+                    });
+                    s.custom_id(format!("onboarding_select:{}", index))
+                        .max_values(step.max_values.unwrap_or(step.options.len() as u64))
+                        .disabled(disabled)
+                })
+            });
+        }
+        StepKind::Buttons => {
+            for row in step.options.chunks(5) {
+                components.create_action_row(|a| {
+                    for option in row {
+                        a.create_button(|b| {
+                            b.label(tr(locale, &option.label).substring(0, 80))
+                                .custom_id(format!("onboarding_button:{}:{}", index, option.value))
+                                .style(option.style.into())
+                                .disabled(disabled);
+                            if let Some(emoji) = option.reaction() {
+                                b.emoji(emoji);
+                            }
+                            b
+                        });
+                    }
+                    a
+                });
+            }
+        }
+        StepKind::Modal => {
+            components.create_action_row(|a| {
+                a.create_button(|b| {
+                    b.label(tr(locale, "onboarding-open-form"))
+                        .custom_id(format!("onboarding_form:{}", index))
+                        .style(ButtonStyle::Primary)
+                        .emoji(ReactionType::Unicode("📝".to_string()))
+                        .disabled(disabled)
+                })
+            });
+        }
+    }
+    components
+}
+
+fn update_step<'a, 'b>(
+    r: &'b mut CreateInteractionResponse<'a>,
+    content: String,
+    components: CreateComponents,
+) -> &'b mut CreateInteractionResponse<'a> {
+    r.kind(InteractionResponseType::UpdateMessage)
+        .interaction_response_data(|d| d.content(content).set_components(components))
+}
+
+fn show_form<'a, 'b>(
+    r: &'b mut CreateInteractionResponse<'a>,
+    step: &Step,
+    index: usize,
+    locale: &str,
+) -> &'b mut CreateInteractionResponse<'a> {
+    let title = tr(
+        locale,
+        step.title.as_deref().unwrap_or("onboarding-form-title"),
+    );
+    r.kind(InteractionResponseType::Modal)
+        .interaction_response_data(|d| {
+            d.custom_id(format!("onboarding_form_submit:{}", index))
+                .title(title.substring(0, 45))
+                .components(|c| {
+                    for field in &step.fields {
+                        c.create_action_row(|a| {
+                            a.create_input_text(|it| {
+                                it.custom_id(&field.id)
+                                    .label(tr(locale, &field.label).substring(0, 45))
+                                    .style(if field.paragraph {
+                                        InputTextStyle::Paragraph
+                                    } else {
+                                        InputTextStyle::Short
+                                    })
+                                    .required(field.required)
+                                    .max_length(field.max_length.unwrap_or(1000));
+                                if let Some(placeholder) = &field.placeholder {
+                                    it.placeholder(tr(locale, placeholder).substring(0, 100));
+                                }
+                                it
+                            })
+                        });
+                    }
+                    c
+                })
+        })
+}
+
+/// Component and modal interactions answering a step, both are answered by updating the
+/// wizard's message.
+enum Reply<'a> {
+    Component(&'a MessageComponentInteraction),
+    Form(&'a ModalSubmitInteraction),
+}
+
+impl Reply<'_> {
+    fn guild_id(&self) -> GuildId {
+        match self {
+            Reply::Component(x) => x.guild_id.unwrap(),
+            Reply::Form(x) => x.guild_id.unwrap(),
+        }
+    }
+
+    fn user_id(&self) -> UserId {
+        match self {
+            Reply::Component(x) => x.user.id,
+            Reply::Form(x) => x.user.id,
+        }
+    }
+
+    async fn locale(&self, ctx: &Context) -> &'static str {
+        let locale = match self {
+            Reply::Component(x) => &x.locale,
+            Reply::Form(x) => &x.locale,
+        };
+        i18n::user_locale(ctx, Some(self.guild_id()), locale).await
+    }
+
+    async fn update(&self, ctx: &Context, content: String, components: CreateComponents) {
+        match self {
+            Reply::Component(x) => {
+                x.create_interaction_response(&ctx.http, |r| update_step(r, content, components))
+                    .await
+            }
+            Reply::Form(x) => {
+                x.create_interaction_response(&ctx.http, |r| update_step(r, content, components))
+                    .await
+            }
+        }
+        .unwrap();
+    }
+}
+
+/// Keeps a member from answering two steps at once, e.g. by double clicking.
+struct UserLock(UserId);
+
+static LOCKED_USERS: OnceLock<Mutex<HashSet<UserId>>> = OnceLock::new();
+
+impl UserLock {
+    fn acquire(user_id: UserId) -> Option<Self> {
+        let mut locked = LOCKED_USERS.get_or_init(Default::default).lock().unwrap();
+        locked.insert(user_id).then_some(UserLock(user_id))
+    }
+}
+
+impl Drop for UserLock {
+    fn drop(&mut self) {
+        if let Some(locked) = LOCKED_USERS.get() {
+            locked.lock().unwrap().remove(&self.0);
+        }
+    }
+}
+
+async fn get_role(ctx: &Context, guild_id: GuildId, name: &str) -> Role {
+    let role = {
+        if let Some(result) = guild_id
+            .to_guild_cached(&ctx.cache)
+            .unwrap()
+            .role_by_name(name)
+        {
+            result.clone()
+        } else {
+            let r = guild_id
+                .create_role(&ctx.http, |r| {
+                    r.name(name);
+                    r.mentionable(false);
+                    r.hoist(false);
+                    r
+                })
+                .await
+                .unwrap();
+            r.clone()
+        }
+    };
+    if role.name != "Member" && role.name != "Gitpodders" && !role.permissions.is_empty() {
+        role.edit(&ctx.http, |r| r.permissions(Permissions::empty()))
+            .await
+            .unwrap();
+    }
+    role
+}
+
+async fn assign_roles(
+    ctx: &Context,
+    guild_id: GuildId,
+    role_names: Vec<String>,
+    member: &mut Member,
+    temp_role: &Role,
+    member_role: &Role,
+) {
+    let mut role_ids: Vec<RoleId> = Vec::new();
+    for role_name in role_names {
+        let role = get_role(ctx, guild_id, role_name.as_str()).await;
+        role_ids.push(role.id);
+    }
+    if !role_ids.is_empty() {
+        member.add_roles(&ctx.http, &role_ids).await.unwrap();
+    }
+    // Saved even when empty, these are given back if the member rejoins
+    let db = &ctx.get_db().await;
+    db.set_user_roles(member.user.id, role_ids).await.unwrap();
+
+    // Remove the temp role from user
+    if member.roles.iter().any(|x| x == &temp_role.id) {
+        member.remove_role(&ctx.http, temp_role.id).await.unwrap();
+    }
+    // Add member role if missing
+    if !member.roles.iter().any(|x| x == &member_role.id) {
+        member.add_role(&ctx.http, member_role.id).await.unwrap();
+    }
+}
+
+async fn never_introduced(ctx: &Context, guild_id: GuildId, member: &Member) -> bool {
+    let db = ctx.get_db().await;
+    if db
+        .has_introduced(guild_id, member.user.id)
+        .await
+        .unwrap_or(false)
+    {
+        return false;
+    }
+    let member_role = get_role(ctx, guild_id, "Member").await;
+    let mut status = true;
+    if let Some(roles) = member.roles(&ctx.cache) {
+        let gitpodder_role = get_role(ctx, guild_id, "Gitpodders").await;
+        status = !roles
+            .into_iter()
+            .any(|x| x == member_role || x == gitpodder_role);
+    }
+    if status {
+        let mut count = 0;
+        if let Ok(intro_msgs) = &ctx
+            .http
+            .get_messages(*INTRODUCTION_CHANNEL.as_u64(), "")
+            .await
+        {
+            intro_msgs.iter().for_each(|x| {
+                if x.author.id == member.user.id {
+                    count += 1;
+                }
+            });
+        }
+
+        status = count < 1;
+    }
+    status
+}
+
+/// Swaps the roles from a previous run of the wizard for the ones picked now,
+/// and lets the member in.
+async fn finish(ctx: &Context, guild_id: GuildId, user_id: UserId, answers: &[Vec<String>]) {
+    let wizard = get_wizard(ctx, guild_id).await;
+    let mut member = match guild_id.member(ctx, user_id).await {
+        Ok(member) => member,
+        // They left in the meantime
+        Err(_) => return,
+    };
+    let temp_role = get_role(ctx, guild_id, "Temp").await;
+    let member_role = get_role(ctx, guild_id, "Member").await;
+    let db = ctx.get_db().await;
+    let now = Timestamp::now().unix_timestamp();
+
+    // Members stuck in Temp from before sessions were kept, we don't know what they picked
+    if answers.is_empty() {
+        if member.roles.contains(&temp_role.id) {
+            member.remove_role(&ctx.http, temp_role.id).await.unwrap();
+        }
+        if !member.roles.contains(&member_role.id) {
+            member.add_role(&ctx.http, member_role.id).await.unwrap();
+        }
+        db.set_onboarding_completed(guild_id, user_id, now)
+            .await
+            .unwrap();
+        return;
+    }
+
+    // Remove old roles
+    if let Some(roles) = member.roles(&ctx.cache) {
+        let all_roles = wizard.all_roles();
+        let removeable_roles: Vec<RoleId> = roles
+            .into_iter()
+            .filter(|x| all_roles.contains(x.name.as_str()))
+            .map(|x| x.id)
+            .collect();
+        if !removeable_roles.is_empty() {
+            member
+                .remove_roles(&ctx.http, &removeable_roles)
+                .await
+                .unwrap();
+        }
+    }
+
+    let mut role_names: Vec<String> = Vec::new();
+    for role in wizard.chosen_options(answers).iter().flat_map(|x| &x.roles) {
+        if !role_names.contains(role) {
+            role_names.push(role.clone());
+        }
+    }
+    assign_roles(
+        ctx,
+        guild_id,
+        role_names,
+        &mut member,
+        &temp_role,
+        &member_role,
+    )
+    .await;
+
+    db.set_onboarding_completed(guild_id, user_id, now)
+        .await
+        .unwrap();
+}
+
+/// "Let's go": starts a new session, or shows the current step again when one is in progress.
+pub async fn start(mci: &MessageComponentInteraction, ctx: &Context) {
+    let locale = i18n::user_locale(ctx, mci.guild_id, &mci.locale).await;
+    if raid::is_onboarding_paused(ctx, mci.guild_id.unwrap()).await {
+        mci.create_interaction_response(&ctx.http, |r| {
+            r.kind(InteractionResponseType::ChannelMessageWithSource)
+                .interaction_response_data(|d| {
+                    d.content(tr(locale, "onboarding-paused"))
+                        .flags(MessageFlags::EPHEMERAL)
+                })
+        })
+        .await
+        .unwrap();
+        return;
+    }
+    let _lock = match UserLock::acquire(mci.user.id) {
+        Some(lock) => lock,
+        None => {
+            mci.create_interaction_response(&ctx.http, |r| {
+                r.kind(InteractionResponseType::ChannelMessageWithSource)
+                    .interaction_response_data(|d| {
+                        d.content(tr(locale, "onboarding-busy"))
+                            .flags(MessageFlags::EPHEMERAL)
+                    })
+            })
+            .await
+            .unwrap();
+            return;
+        }
+    };
+    let guild_id = mci.guild_id.unwrap();
+    let db = ctx.get_db().await;
+    let wizard = get_wizard(ctx, guild_id).await;
+    let now = Timestamp::now().unix_timestamp();
+
+    let session = match db
+        .get_onboarding_session(guild_id, mci.user.id)
+        .await
+        .unwrap()
+        .filter(|x| {
+            !x.awaiting_intro
+                && x.updated_at > now - SESSION_TTL_SECS
+                && x.answers.len() < wizard.steps.len()
+        }) {
+        Some(session) => session,
+        None => {
+            let session = OnboardingSession {
+                answers: vec![],
+                awaiting_intro: false,
+                updated_at: now,
+                reminded_at: None,
+            };
+            db.save_onboarding_session(guild_id, mci.user.id, &session)
+                .await
+                .unwrap();
+            session
+        }
+    };
+
+    let index = session.answers.len();
+    mci.create_interaction_response(&ctx.http, |r| {
+        r.kind(InteractionResponseType::ChannelMessageWithSource)
+            .interaction_response_data(|d| {
+                d.content(step_content(&wizard, index, locale))
+                    .set_components(step_components(&wizard.steps[index], index, locale, false))
+                    .flags(MessageFlags::EPHEMERAL)
+            })
+    })
+    .await
+    .unwrap();
+}
+
+/// Loads the session a step was answered in. Expired sessions, and steps answered on an old
+/// copy of the wizard, are answered here and give `None`.
+async fn resume(
+    reply: &Reply<'_>,
+    ctx: &Context,
+    wizard: &Wizard,
+    index: usize,
+) -> Option<OnboardingSession> {
+    let db = ctx.get_db().await;
+    let locale = reply.locale(ctx).await;
+    let now = Timestamp::now().unix_timestamp();
+    let session = db
+        .get_onboarding_session(reply.guild_id(), reply.user_id())
+        .await
+        .unwrap();
+
+    match session {
+        Some(session)
+            if !session.awaiting_intro
+                && session.updated_at > now - SESSION_TTL_SECS
+                && session.answers.len() < wizard.steps.len() =>
+        {
+            let current = session.answers.len();
+            if current == index {
+                return Some(session);
+            }
+            reply
+                .update(
+                    ctx,
+                    step_content(wizard, current, locale),
+                    step_components(&wizard.steps[current], current, locale, false),
+                )
+                .await;
+        }
+        _ => {
+            reply
+                .update(
+                    ctx,
+                    tr_args(
+                        locale,
+                        "onboarding-expired",
+                        &[("channel", GETTING_STARTED_CHANNEL.mention().to_string())],
+                    ),
+                    CreateComponents::default(),
+                )
+                .await;
+        }
+    }
+    None
+}
+
+/// Saves the answer to the current step, then shows the next one or wraps up.
+async fn advance(
+    reply: &Reply<'_>,
+    ctx: &Context,
+    wizard: &Wizard,
+    mut session: OnboardingSession,
+    values: Vec<String>,
+) {
+    let db = ctx.get_db().await;
+    let locale = reply.locale(ctx).await;
+    let guild_id = reply.guild_id();
+    let user_id = reply.user_id();
+    session.answers.push(values);
+    session.updated_at = Timestamp::now().unix_timestamp();
+
+    let index = session.answers.len();
+    if index < wizard.steps.len() {
+        db.save_onboarding_session(guild_id, user_id, &session)
+            .await
+            .unwrap();
+        reply
+            .update(
+                ctx,
+                step_content(wizard, index, locale),
+                step_components(&wizard.steps[index], index, locale, false),
+            )
+            .await;
+        return;
+    }
+
+    for (step, values) in wizard.steps.iter().zip(&session.answers) {
+        if step.survey {
+            db.set_survey_answers(guild_id, user_id, &step.id, values, session.updated_at)
+                .await
+                .unwrap();
+        }
+    }
+
+    let member = guild_id.member(ctx, user_id).await.unwrap();
+    if never_introduced(ctx, guild_id, &member).await {
+        // Wrapped up once they post in INTRODUCTION_CHANNEL
+        let temp_role = get_role(ctx, guild_id, "Temp").await;
+        member
+            .clone()
+            .add_role(&ctx.http, temp_role.id)
+            .await
+            .unwrap();
+        session.awaiting_intro = true;
+        db.save_onboarding_session(guild_id, user_id, &session)
+            .await
+            .unwrap();
+        let final_msg = MessageBuilder::new()
+            .push_line(tr_args(
+                locale,
+                "onboarding-intro-unlock",
+                &[
+                    ("user", user_id.mention().to_string()),
+                    ("channel", INTRODUCTION_CHANNEL.mention().to_string()),
+                ],
+            ))
+            .push_line("")
+            .push_line(tr(locale, "onboarding-intro-ask"))
+            .push_quote_line(tr(locale, "onboarding-intro-working-on"))
+            .push_quote_line(tr(locale, "onboarding-intro-blockers"))
+            .push_quote_line(tr(locale, "onboarding-intro-feature"))
+            .push_quote_line(tr(locale, "onboarding-intro-emoji"))
+            .build();
+        reply
+            .update(ctx, final_msg, CreateComponents::default())
+            .await;
+    } else {
+        db.remove_onboarding_session(guild_id, user_id)
+            .await
+            .unwrap();
+        reply
+            .update(
+                ctx,
+                tr(locale, "onboarding-profile-updated"),
+                CreateComponents::default(),
+            )
+            .await;
+        finish(ctx, guild_id, user_id, &session.answers).await;
+    }
+}
+
+/// Buttons and select menus of the wizard, their ids carry the step they belong to.
+pub async fn handle_component(mci: &MessageComponentInteraction, ctx: &Context) {
+    let reply = Reply::Component(mci);
+    let locale = reply.locale(ctx).await;
+    let mut parts = mci.data.custom_id.splitn(3, ':');
+    let (kind, index) = match (parts.next(), parts.next().and_then(|x| x.parse().ok())) {
+        (Some(kind), Some(index)) => (kind, index),
+        _ => return,
+    };
+    let _lock = match UserLock::acquire(mci.user.id) {
+        Some(lock) => lock,
+        None => {
+            mci.create_interaction_response(&ctx.http, |r| {
+                r.kind(InteractionResponseType::DeferredUpdateMessage)
+            })
+            .await
+            .ok();
+            return;
+        }
+    };
+    let wizard = get_wizard(ctx, mci.guild_id.unwrap()).await;
+    let session = match resume(&reply, ctx, &wizard, index).await {
+        Some(session) => session,
+        None => return,
+    };
+    let step = &wizard.steps[index];
+
+    let values = match (step.kind, kind) {
+        (StepKind::Select, "onboarding_select") => mci.data.values.clone(),
+        (StepKind::Buttons, "onboarding_button") => match parts.next() {
+            Some(value) => vec![value.to_string()],
+            None => return,
+        },
+        (StepKind::Modal, "onboarding_form") => {
+            mci.create_interaction_response(&ctx.http, |r| show_form(r, step, index, locale))
+                .await
+                .unwrap();
+            return;
+        }
+        _ => return,
+    };
+    advance(&reply, ctx, &wizard, session, values).await;
+}
+
+/// Submitted modal steps.
+pub async fn handle_form(mci: &ModalSubmitInteraction, ctx: &Context) {
+    let reply = Reply::Form(mci);
+    let index = match mci
+        .data
+        .custom_id
+        .trim_start_matches("onboarding_form_submit:")
+        .parse()
+    {
+        Ok(index) => index,
+        Err(_) => return,
+    };
+    let _lock = match UserLock::acquire(mci.user.id) {
+        Some(lock) => lock,
+        None => {
+            mci.create_interaction_response(&ctx.http, |r| {
+                r.kind(InteractionResponseType::DeferredUpdateMessage)
+            })
+            .await
+            .ok();
+            return;
+        }
+    };
+    let wizard = get_wizard(ctx, mci.guild_id.unwrap()).await;
+    let session = match resume(&reply, ctx, &wizard, index).await {
+        Some(session) => session,
+        None => return,
+    };
+    // The wizard was changed while the form was open, show the step as it is now
+    if wizard.steps[index].kind != StepKind::Modal {
+        let locale = reply.locale(ctx).await;
+        reply
+            .update(
+                ctx,
+                step_content(&wizard, index, locale),
+                step_components(&wizard.steps[index], index, locale, false),
+            )
+            .await;
+        return;
+    }
+    let values = mci
+        .data
+        .components
+        .iter()
+        .flat_map(|x| &x.components)
+        .filter_map(|x| match x {
+            ActionRowComponent::InputText(it) => Some(it.value.clone()),
+            _ => None,
+        })
+        .collect();
+    advance(&reply, ctx, &wizard, session, values).await;
+}
+
+fn cached_role_id(ctx: &Context, guild_id: GuildId, name: &str) -> Option<RoleId> {
+    guild_id
+        .to_guild_cached(&ctx.cache)
+        .and_then(|x| x.role_by_name(name).map(|r| r.id))
+}
+
+/// Called for messages in INTRODUCTION_CHANNEL. Keeps track of who introduced themselves,
+/// and opens the welcome thread and lets the member in when they were waiting on it.
+pub async fn welcome_introduced(ctx: &Context, msg: &Message) -> Result<()> {
+    let guild_id = match msg.guild_id {
+        Some(id) => id,
+        None => return Ok(()),
+    };
+    let db = ctx.get_db().await;
+    if !db
+        .add_introduction(
+            guild_id,
+            msg.author.id,
+            msg.id,
+            msg.timestamp.unix_timestamp(),
+        )
+        .await?
+    {
+        return Ok(());
+    }
+    let answers = match db.get_onboarding_session(guild_id, msg.author.id).await? {
+        Some(session) if session.awaiting_intro => session.answers,
+        _ => {
+            let temp_role = cached_role_id(ctx, guild_id, "Temp");
+            let in_temp = msg
+                .member
+                .as_ref()
+                .zip(temp_role)
+                .is_some_and(|(member, temp_role)| member.roles.contains(&temp_role));
+            if !in_temp {
+                return Ok(());
+            }
+            vec![]
+        }
+    };
+    db.remove_onboarding_session(guild_id, msg.author.id)
+        .await?;
+
+    let wizard = get_wizard(ctx, guild_id).await;
+    // The welcome thread is public, use the guild's locale
+    let locale = i18n::guild_locale(ctx, Some(guild_id)).await;
+    let thread = msg
+        .channel_id
+        .create_public_thread(&ctx.http, &msg.id, |t| {
+            t.auto_archive_duration(1440).name(tr_args(
+                locale,
+                "intro-thread-name",
+                &[("name", msg.author.name.clone())],
+            ))
+        })
+        .await?;
+
+    if words_count::count(&msg.content).words > 5 {
+        msg.react(&ctx.http, ReactionType::Unicode("🔥".to_string()))
+            .await?;
+    }
+    msg.react(&ctx.http, ReactionType::Unicode("👋".to_string()))
+        .await?;
+
+    let mut prepared_msg = MessageBuilder::new();
+    prepared_msg
+        .push_line(tr_args(
+            locale,
+            "intro-welcome",
+            &[("user", msg.author.mention().to_string())],
+        ))
+        .push_line("");
+    for option in wizard.chosen_options(&answers) {
+        if let Some(followup) = &option.followup {
+            let channels = option
+                .channels
+                .iter()
+                .map(|x| x.mention().to_string())
+                .collect::<Vec<_>>()
+                .join(", ");
+            prepared_msg
+                .push_line(tr_args(locale, followup, &[("channel", channels)]))
+                .push_line("");
+        }
+    }
+    if !wizard.suggested_channels.is_empty() {
+        prepared_msg.push_bold_line(tr(locale, "intro-channels-heading"));
+        for suggestion in &wizard.suggested_channels {
+            prepared_msg.push_quote_line(tr_args(
+                locale,
+                &suggestion.text,
+                &[("channel", suggestion.channel.mention().to_string())],
+            ));
+        }
+        prepared_msg.push_line("");
+    }
+    prepared_msg
+        .push_line(format!("{}\n", tr(locale, "intro-explore")))
+        .push_bold_line(tr(locale, "intro-learn-more"))
+        .push_quote_line("• https://www.gitpod.io/community")
+        .push_quote_line("• https://www.gitpod.io/about");
+    let mut thread_msg = thread
+        .send_message(&ctx.http, |t| t.content(prepared_msg))
+        .await?;
+    thread_msg.suppress_embeds(&ctx.http).await?;
+
+    finish(ctx, guild_id, msg.author.id, &answers).await;
+    Ok(())
+}
+
+/// Starts tracking members left in `Temp` before introductions were kept, so that they
+/// get reminded too.
+async fn track_stuck_members(ctx: &Context) {
+    let db = ctx.get_db().await;
+    let now = Timestamp::now().unix_timestamp();
+    for guild_id in ctx.cache.guilds() {
+        let guild = match guild_id.to_guild_cached(&ctx.cache) {
+            Some(guild) => guild,
+            None => continue,
+        };
+        let temp_role = match guild.role_by_name("Temp") {
+            Some(role) => role.id,
+            None => continue,
+        };
+        let member_role = guild.role_by_name("Member").map(|x| x.id);
+        for member in guild.members.values().filter(|x| {
+            x.roles.contains(&temp_role) && !member_role.is_some_and(|r| x.roles.contains(&r))
+        }) {
+            if let Ok(None) = db.get_onboarding_session(guild_id, member.user.id).await {
+                let session = OnboardingSession {
+                    answers: vec![],
+                    awaiting_intro: true,
+                    updated_at: now,
+                    reminded_at: None,
+                };
+                db.save_onboarding_session(guild_id, member.user.id, &session)
+                    .await
+                    .ok();
+            }
+        }
+    }
+}
+
+/// Reminds the member after `intro_reminder_days`, and removes their Temp role or kicks them
+/// after `intro_timeout_days` when an `intro_timeout_action` is set.
+async fn check_introduction(ctx: &Context, intro: &AwaitingIntroduction, now: i64) -> Result<()> {
+    let db = ctx.get_db().await;
+    let guild_id = GuildId(intro.guild_id as u64);
+    let user_id = UserId(intro.user_id as u64);
+    let waited_days = (now - intro.updated_at) / 86400;
+
+    let timeout_days = db
+        .get_guild_config_as::<i64>(guild_id, "intro_timeout_days")
+        .await?
+        .unwrap_or(0);
+    let action = db
+        .get_guild_config(guild_id, "intro_timeout_action")
+        .await?;
+    if let (true, Some(action)) = (timeout_days > 0 && waited_days >= timeout_days, action) {
+        db.remove_onboarding_session(guild_id, user_id).await?;
+        let mut member = match guild_id.member(ctx, user_id).await {
+            Ok(member) => member,
+            Err(_) => return Ok(()),
+        };
+        let done = match action.as_str() {
+            "kick" => {
+                member
+                    .kick_with_reason(
+                        &ctx.http,
+                        &format!("Did not introduce themselves within {} days", timeout_days),
+                    )
+                    .await?;
+                "kicked"
+            }
+            "remove_temp" => {
+                if let Some(temp_role) = cached_role_id(ctx, guild_id, "Temp") {
+                    member.remove_role(&ctx.http, temp_role).await?;
+                }
+                "removed from Temp"
+            }
+            _ => return Ok(()),
+        };
+        post_log(
+            ctx,
+            guild_id,
+            format!(
+                "> :hourglass: {} (**{}**) was {}, no introduction after {} days",
+                user_id.mention(),
+                member.user.tag(),
+                done,
+                timeout_days
+            ),
+        )
+        .await?;
+        return Ok(());
+    }
+
+    let reminder_days = db
+        .get_guild_config_as::<i64>(guild_id, "intro_reminder_days")
+        .await?
+        .unwrap_or(DEFAULT_INTRO_REMINDER_DAYS);
+    if reminder_days > 0 && intro.reminded_at.is_none() && waited_days >= reminder_days {
+        // Marked first, so that a failing DM isn't retried every minute
+        db.set_intro_reminded(guild_id, user_id, now).await?;
+        let locale = i18n::guild_locale(ctx, Some(guild_id)).await;
+        let guild_name = guild_id.name(&ctx.cache).unwrap_or_default();
+        if let Ok(dm) = user_id.create_dm_channel(&ctx.http).await {
+            dm.say(
+                &ctx.http,
+                tr_args(
+                    locale,
+                    "intro-reminder",
+                    &[
+                        ("user", user_id.mention().to_string()),
+                        ("guild", guild_name),
+                        ("channel", INTRODUCTION_CHANNEL.mention().to_string()),
+                    ],
+                ),
+            )
+            .await
+            .ok();
+        }
+    }
+    Ok(())
+}
+
+/// Follows up on members who didn't introduce themselves yet and drops abandoned sessions,
+/// checked every minute.
+pub async fn run_expiry(ctx: Context) {
+    track_stuck_members(&ctx).await;
+    loop {
+        let db = ctx.get_db().await;
+        let now = Timestamp::now().unix_timestamp();
+        match db.get_awaiting_introductions().await {
+            Ok(awaiting) => {
+                for intro in &awaiting {
+                    if let Err(e) = check_introduction(&ctx, intro, now).await {
+                        eprintln!(
+                            "Failed to follow up on the introduction of {} in {}: {}",
+                            intro.user_id, intro.guild_id, e
+                        );
+                    }
+                }
+            }
+            Err(e) => eprintln!("Failed to get pending introductions: {}", e),
+        }
+        if let Err(e) = db
+            .remove_abandoned_onboarding_sessions(now - SESSION_TTL_SECS)
+            .await
+        {
+            eprintln!("Failed to clean up onboarding sessions: {}", e);
+        }
+        tokio::time::sleep(Duration::from_secs(60)).await;
+    }
+}
+
+fn summary(wizard: &Wizard, locale: &str) -> String {
+    let mut text = MessageBuilder::new();
+    for (index, step) in wizard.steps.iter().enumerate() {
+        let kind = match step.kind {
+            StepKind::Select => "select",
+            StepKind::Buttons => "buttons",
+            StepKind::Modal => "modal",
+        };
+        text.push_line(format!(
+            "**{}.** `{}` ({}{}) — {}",
+            index + 1,
+            step.id,
+            kind,
+            if step.survey { ", survey" } else { "" },
+            tr(locale, &step.prompt)
+        ));
+        for option in &step.options {
+            let mut line = format!("`{}`", option.value);
+            if !option.roles.is_empty() {
+                line.push_str(&format!(" → {}", option.roles.join(", ")));
+            }
+            if option.followup.is_some() {
+                line.push_str(" · follow-up");
+            }
+            text.push_quote_line(line);
+        }
+        for field in &step.fields {
+            text.push_quote_line(format!("`{}`: {}", field.id, tr(locale, &field.label)));
+        }
+    }
+    if !wizard.suggested_channels.is_empty() {
+        text.push_line(format!(
+            "Suggested channels: {}",
+            wizard
+                .suggested_channels
+                .iter()
+                .map(|x| x.channel.mention().to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        ));
+    }
+    text.build()
+}
+
+pub async fn responder(mci: &ApplicationCommandInteraction, ctx: &Context) {
+    let guild_id = mci.guild_id.unwrap();
+    let subcommand = match mci.data.options.first() {
+        Some(x) => x,
+        None => return,
+    };
+    let db = ctx.get_db().await;
+    let locale = i18n::user_locale(ctx, mci.guild_id, &mci.locale).await;
+
+    match subcommand.name.as_str() {
+        "preview" => {
+            let wizard = get_wizard(ctx, guild_id).await;
+            let index = subcommand
+                .options
+                .iter()
+                .find_map(|x| match x.resolved {
+                    Some(CommandDataOptionValue::Integer(n)) => Some(n as usize),
+                    _ => None,
+                })
+                .unwrap_or(1)
+                .clamp(1, wizard.steps.len())
+                - 1;
+            let step = &wizard.steps[index];
+            let mut content = step_content(&wizard, index, locale);
+            for field in &step.fields {
+                content.push_str(&format!("\n> {}", tr(locale, &field.label)));
+            }
+            mci.create_interaction_response(&ctx.http, |r| {
+                r.kind(InteractionResponseType::ChannelMessageWithSource)
+                    .interaction_response_data(|d| {
+                        d.content(content)
+                            .set_components(step_components(step, index, locale, true))
+                            .flags(MessageFlags::EPHEMERAL)
+                    })
+            })
+            .await
+            .unwrap();
+            return;
+        }
+        "export" => {
+            let wizard = get_wizard(ctx, guild_id).await;
+            let yaml = serde_yaml::to_string(&wizard).unwrap();
+            mci.create_interaction_response(&ctx.http, |r| {
+                r.kind(InteractionResponseType::ChannelMessageWithSource)
+                    .interaction_response_data(|d| {
+                        d.add_file((yaml.as_bytes(), "onboarding.yaml"))
+                            .flags(MessageFlags::EPHEMERAL)
+                    })
+            })
+            .await
+            .unwrap();
+            return;
+        }
+        _ => {}
+    }
+
+    let response = match subcommand.name.as_str() {
+        "show" => {
+            let custom = db.get_onboarding_wizard(guild_id).await.unwrap().is_some();
+            let wizard = get_wizard(ctx, guild_id).await;
+            format!(
+                "{}\n{}",
+                if custom {
+                    "**Onboarding steps**"
+                } else {
+                    "**Onboarding steps** (default)"
+                },
+                summary(&wizard, locale)
+            )
+        }
+        "import" => {
+            let attachment = subcommand.options.iter().find_map(|x| match &x.resolved {
+                Some(CommandDataOptionValue::Attachment(a)) => Some(a),
+                _ => None,
+            });
+            let definition = match attachment {
+                Some(attachment) => attachment
+                    .download()
+                    .await
+                    .ok()
+                    .and_then(|x| String::from_utf8(x).ok()),
+                None => None,
+            };
+            match definition.map(|x| {
+                serde_yaml::from_str::<Wizard>(&x)
+                    .map_err(anyhow::Error::from)
+                    .and_then(|wizard| wizard.validate().map(|_| (x, wizard)))
+            }) {
+                Some(Ok((definition, wizard))) => {
+                    db.set_onboarding_wizard(
+                        guild_id,
+                        &definition,
+                        Timestamp::now().unix_timestamp(),
+                    )
+                    .await
+                    .unwrap();
+                    format!(
+                        "Saved, new members will now go through these steps. Use `/onboarding preview` to see them as members do.\n{}",
+                        summary(&wizard, locale)
+                    )
+                }
+                Some(Err(e)) => format!("Invalid onboarding definition: {}", e),
+                None => "Please attach the definition as a UTF-8 YAML file".to_string(),
+            }
+        }
+        "reset" => {
+            db.remove_onboarding_wizard(guild_id).await.unwrap();
+            "Onboarding is back to the default steps".to_string()
+        }
+        _ => return,
+    };
+
+    mci.create_interaction_response(&ctx.http, |r| {
+        r.kind(InteractionResponseType::ChannelMessageWithSource)
+            .interaction_response_data(|d| {
+                d.content(response.as_str().substring(0, 2000))
+                    .flags(MessageFlags::EPHEMERAL)
+            })
+    })
+    .await
+    .unwrap();
+}
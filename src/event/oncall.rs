@@ -0,0 +1,360 @@
+use super::*;
+use crate::db::{ClientContextExt, Db};
+use crate::utils::misc::{format_duration, week_start, WEEK_SECS};
+use serenity::model::{
+    application::interaction::{
+        application_command::{ApplicationCommandInteraction, CommandDataOptionValue},
+        MessageFlags,
+    },
+    id::{RoleId, UserId},
+    Timestamp,
+};
+use serenity::utils::MessageBuilder;
+
+const DEFAULT_ESCALATION_MINUTES: i64 = 24 * 60;
+const SHOW_WEEKS: i64 = 4;
+pub const ROTATION_MAX_USERS: usize = 6;
+
+pub struct PendingEscalation {
+    pub thread_id: i64,
+    pub channel_id: i64,
+    pub created_at: i64,
+}
+
+impl Db {
+    pub async fn set_oncall_rotation(&self, guild_id: GuildId, users: &[UserId]) -> Result<()> {
+        let guild_id = guild_id.0 as i64;
+        let mut tx = self.sqlitedb.begin().await?;
+        sqlx::query!("delete from oncall_rotation where guild_id=?", guild_id)
+            .execute(&mut tx)
+            .await?;
+        sqlx::query!("delete from oncall_overrides where guild_id=?", guild_id)
+            .execute(&mut tx)
+            .await?;
+        for (position, user_id) in users.iter().enumerate() {
+            let position = position as i64;
+            let user_id = user_id.0 as i64;
+            sqlx::query!(
+                "insert into oncall_rotation (guild_id, position, user_id) values (?, ?, ?)",
+                guild_id,
+                position,
+                user_id
+            )
+            .execute(&mut tx)
+            .await?;
+        }
+        tx.commit().await?;
+        Ok(())
+    }
+
+    pub async fn get_oncall_rotation(&self, guild_id: GuildId) -> Result<Vec<UserId>> {
+        let guild_id = guild_id.0 as i64;
+        let q = sqlx::query!(
+            "select user_id from oncall_rotation where guild_id=? order by position",
+            guild_id
+        )
+        .fetch_all(&self.sqlitedb)
+        .await?
+        .into_iter()
+        .map(|x| UserId(x.user_id as u64))
+        .collect();
+        Ok(q)
+    }
+
+    pub async fn set_oncall_override(
+        &self,
+        guild_id: GuildId,
+        week_start: i64,
+        user_id: UserId,
+    ) -> Result<()> {
+        let guild_id = guild_id.0 as i64;
+        let user_id = user_id.0 as i64;
+        sqlx::query!(
+            "insert into oncall_overrides (guild_id, week_start, user_id) values (?1, ?2, ?3) on conflict(guild_id, week_start) do update set user_id=?3",
+            guild_id,
+            week_start,
+            user_id
+        )
+        .execute(&self.sqlitedb)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn get_oncall_override(
+        &self,
+        guild_id: GuildId,
+        week_start: i64,
+    ) -> Result<Option<UserId>> {
+        let guild_id = guild_id.0 as i64;
+        let q = sqlx::query!(
+            "select user_id from oncall_overrides where guild_id=? and week_start=?",
+            guild_id,
+            week_start
+        )
+        .fetch_optional(&self.sqlitedb)
+        .await?
+        .map(|x| UserId(x.user_id as u64));
+        Ok(q)
+    }
+
+    /// Open question threads created before `before` that got no reply and weren't escalated yet.
+    pub async fn get_pending_escalations(
+        &self,
+        guild_id: GuildId,
+        before: i64,
+    ) -> Result<Vec<PendingEscalation>> {
+        let guild_id = guild_id.0 as i64;
+        let q = sqlx::query_as!(
+            PendingEscalation,
+            r#"select thread_id as "thread_id!", channel_id as "channel_id!", created_at as "created_at!" from question_threads
+            where guild_id=? and created_at<=? and first_response_at is null and closed_at is null
+            and thread_id not in (select thread_id from escalations)"#,
+            guild_id,
+            before
+        )
+        .fetch_all(&self.sqlitedb)
+        .await?;
+        Ok(q)
+    }
+
+    pub async fn add_escalation(
+        &self,
+        thread_id: ChannelId,
+        guild_id: GuildId,
+        channel_id: ChannelId,
+        escalated_at: i64,
+        oncall_user_id: Option<UserId>,
+    ) -> Result<()> {
+        let thread_id = thread_id.0 as i64;
+        let guild_id = guild_id.0 as i64;
+        let channel_id = channel_id.0 as i64;
+        let oncall_user_id = oncall_user_id.map(|x| x.0 as i64);
+        sqlx::query!(
+            "insert into escalations (thread_id, guild_id, channel_id, escalated_at, oncall_user_id) values (?, ?, ?, ?, ?)",
+            thread_id,
+            guild_id,
+            channel_id,
+            escalated_at,
+            oncall_user_id
+        )
+        .execute(&self.sqlitedb)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn count_escalations_since(&self, guild_id: GuildId, since: i64) -> Result<i64> {
+        let guild_id = guild_id.0 as i64;
+        let q = sqlx::query!(
+            r#"select count(*) as "count!: i64" from escalations where guild_id=? and escalated_at>=?"#,
+            guild_id,
+            since
+        )
+        .fetch_one(&self.sqlitedb)
+        .await?
+        .count;
+        Ok(q)
+    }
+}
+
+/// Who is on call during the week starting at `week`. Overrides from `/oncall swap` take
+/// precedence, otherwise the rotation cycles weekly from when it was set.
+pub async fn get_oncall_user(db: &Db, guild_id: GuildId, week: i64) -> Result<Option<UserId>> {
+    if let Some(user_id) = db.get_oncall_override(guild_id, week).await? {
+        return Ok(Some(user_id));
+    }
+    let rotation = db.get_oncall_rotation(guild_id).await?;
+    if rotation.is_empty() {
+        return Ok(None);
+    }
+    let start = db
+        .get_guild_config_as::<i64>(guild_id, "oncall_start_week")
+        .await?
+        .unwrap_or(week);
+    let index = ((week - start) / WEEK_SECS).rem_euclid(rotation.len() as i64);
+    Ok(Some(rotation[index as usize]))
+}
+
+async fn escalate(ctx: &Context, guild_id: GuildId, helper_role: RoleId) -> Result<()> {
+    let db = ctx.get_db().await;
+    let now = Timestamp::now().unix_timestamp();
+    let after_minutes = db
+        .get_guild_config_as::<i64>(guild_id, "escalation_minutes")
+        .await?
+        .unwrap_or(DEFAULT_ESCALATION_MINUTES);
+    let dm_oncall = db
+        .get_guild_config_as::<bool>(guild_id, "escalation_dm")
+        .await?
+        .unwrap_or(false);
+
+    for thread in db
+        .get_pending_escalations(guild_id, now - after_minutes * 60)
+        .await?
+    {
+        let thread_id = ChannelId(thread.thread_id as u64);
+        let oncall = get_oncall_user(&db, guild_id, week_start(now)).await?;
+        // Log it first, so that a failing message never pings the same thread twice
+        db.add_escalation(
+            thread_id,
+            guild_id,
+            ChannelId(thread.channel_id as u64),
+            now,
+            oncall,
+        )
+        .await?;
+        println!("Escalating question thread {} in {}", thread_id, guild_id);
+
+        let waiting_for = format_duration(now - thread.created_at);
+        thread_id
+            .say(
+                &ctx.http,
+                format!(
+                    "{} this question has been waiting for {} without a reply, could someone take a look? 🙏",
+                    helper_role.mention(),
+                    waiting_for
+                ),
+            )
+            .await?;
+
+        if let (true, Some(user_id)) = (dm_oncall, oncall) {
+            if let Ok(dm) = user_id.create_dm_channel(&ctx.http).await {
+                dm.say(
+                    &ctx.http,
+                    format!(
+                        "Hey, you are on call this week! {} has been waiting for {} without a reply: https://discord.com/channels/{}/{}",
+                        thread_id.mention(),
+                        waiting_for,
+                        guild_id,
+                        thread_id
+                    ),
+                )
+                .await
+                .ok();
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Checks every minute for unanswered questions that need the helpers' attention.
+pub async fn run_escalations(ctx: Context) {
+    loop {
+        let db = ctx.get_db().await;
+        if let Ok(helper_roles) = db.get_guild_configs("helper_role").await {
+            for (guild_id, role_id) in helper_roles {
+                if let Ok(role_id) = role_id.parse::<RoleId>() {
+                    if let Err(e) = escalate(&ctx, guild_id, role_id).await {
+                        eprintln!("Failed to escalate questions in {}: {}", guild_id, e);
+                    }
+                }
+            }
+        }
+        tokio::time::sleep(Duration::from_secs(60)).await;
+    }
+}
+
+pub async fn responder(mci: &ApplicationCommandInteraction, ctx: &Context) {
+    let guild_id = mci.guild_id.unwrap();
+    let subcommand = match mci.data.options.first() {
+        Some(x) => x,
+        None => return,
+    };
+    let db = ctx.get_db().await;
+    let this_week = week_start(Timestamp::now().unix_timestamp());
+
+    let response = match subcommand.name.as_str() {
+        "set" => {
+            let mut options: Vec<_> = subcommand.options.iter().collect();
+            options.sort_by(|a, b| a.name.cmp(&b.name));
+            let users: Vec<UserId> = options
+                .into_iter()
+                .filter_map(|x| match &x.resolved {
+                    Some(CommandDataOptionValue::User(user, _)) => Some(user.id),
+                    _ => None,
+                })
+                .collect();
+
+            db.set_oncall_rotation(guild_id, &users).await.unwrap();
+            db.set_guild_config(guild_id, "oncall_start_week", &this_week.to_string())
+                .await
+                .unwrap();
+
+            let mut text = MessageBuilder::new();
+            text.push_line("The weekly on-call rotation starting this week is now:");
+            for user_id in &users {
+                text.push_line(format!("• {}", user_id.mention()));
+            }
+            text.build()
+        }
+        "show" => {
+            let mut text = MessageBuilder::new();
+            text.push_bold_line("On-call rotation");
+            for week in 0..SHOW_WEEKS {
+                let week = this_week + week * WEEK_SECS;
+                let user = match get_oncall_user(&db, guild_id, week).await.unwrap() {
+                    Some(user_id) => user_id.mention().to_string(),
+                    None => "nobody".to_string(),
+                };
+                text.push_line(format!("Week of <t:{}:d>: {}", week, user));
+            }
+
+            let helper_role = db
+                .get_guild_config_as::<RoleId>(guild_id, "helper_role")
+                .await
+                .unwrap();
+            let escalations = db
+                .count_escalations_since(guild_id, this_week - (SHOW_WEEKS - 1) * WEEK_SECS)
+                .await
+                .unwrap();
+            text.push_line("")
+                .push_line(match helper_role {
+                    Some(role_id) => format!("Unanswered questions are escalated to {}", role_id.mention()),
+                    None => "Escalations are disabled, set a `helper_role` with `gp config` to enable them".to_string(),
+                })
+                .push_line(format!(
+                    "Questions escalated in the last {} weeks: {}",
+                    SHOW_WEEKS, escalations
+                ));
+            text.build()
+        }
+        "swap" => {
+            let mut weeks = [0i64; 2];
+            for option in &subcommand.options {
+                match (option.name.as_str(), &option.resolved) {
+                    ("first", Some(CommandDataOptionValue::Integer(n))) => weeks[0] = *n,
+                    ("second", Some(CommandDataOptionValue::Integer(n))) => weeks[1] = *n,
+                    _ => {}
+                }
+            }
+            let [first, second] = weeks.map(|x| this_week + x * WEEK_SECS);
+            let first_user = get_oncall_user(&db, guild_id, first).await.unwrap();
+            let second_user = get_oncall_user(&db, guild_id, second).await.unwrap();
+
+            match (first_user, second_user) {
+                (Some(first_user), Some(second_user)) => {
+                    db.set_oncall_override(guild_id, first, second_user)
+                        .await
+                        .unwrap();
+                    db.set_oncall_override(guild_id, second, first_user)
+                        .await
+                        .unwrap();
+                    format!(
+                        "Swapped: {} is on call the week of <t:{}:d> and {} the week of <t:{}:d>",
+                        second_user.mention(),
+                        first,
+                        first_user.mention(),
+                        second
+                    )
+                }
+                _ => "There is no on-call rotation yet, create one with `/oncall set`".to_string(),
+            }
+        }
+        _ => return,
+    };
+
+    mci.create_interaction_response(&ctx.http, |r| {
+        r.kind(InteractionResponseType::ChannelMessageWithSource)
+            .interaction_response_data(|d| d.content(response).flags(MessageFlags::EPHEMERAL))
+    })
+    .await
+    .unwrap();
+}
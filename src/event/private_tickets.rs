@@ -0,0 +1,94 @@
+use super::*;
+use crate::db::Db;
+use serenity::model::{
+    application::interaction::modal::ModalSubmitInteraction,
+    id::{RoleId, UserId},
+    Timestamp,
+};
+use substr::StringUtils;
+
+impl Db {
+    pub async fn add_private_ticket(
+        &self,
+        thread_id: ChannelId,
+        guild_id: GuildId,
+        channel_id: ChannelId,
+        user_id: UserId,
+        created_at: i64,
+    ) -> Result<()> {
+        let thread_id = thread_id.0 as i64;
+        let guild_id = guild_id.0 as i64;
+        let channel_id = channel_id.0 as i64;
+        let user_id = user_id.0 as i64;
+        sqlx::query!(
+            "insert into private_tickets (thread_id, guild_id, channel_id, user_id, created_at) values (?, ?, ?, ?, ?)",
+            thread_id,
+            guild_id,
+            channel_id,
+            user_id,
+            created_at
+        )
+        .execute(&self.sqlitedb)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn close_private_ticket(&self, thread_id: ChannelId, at: i64) -> Result<()> {
+        let thread_id = thread_id.0 as i64;
+        sqlx::query!(
+            "update private_tickets set closed_at=? where thread_id=? and closed_at is null",
+            at,
+            thread_id
+        )
+        .execute(&self.sqlitedb)
+        .await?;
+        Ok(())
+    }
+}
+
+/// The role that can see private tickets, the staff role when no `support_role` is configured.
+pub async fn get_support_role(ctx: &Context, guild_id: GuildId) -> Option<RoleId> {
+    let db = ctx.get_db().await;
+    if let Ok(Some(role_id)) = db
+        .get_guild_config_as::<RoleId>(guild_id, "support_role")
+        .await
+    {
+        return Some(role_id);
+    }
+    support_stats::get_staff_role(ctx, guild_id).await
+}
+
+/// Opens a private thread under the question channel with only the asker in it.
+///
+/// The support role joins once it is mentioned in the thread.
+pub async fn open(
+    ctx: &Context,
+    mci: &ModalSubmitInteraction,
+    title: &str,
+) -> Result<GuildChannel> {
+    let thread_auto_archive_dur = if cfg!(debug_assertions) {
+        1440 // 1 day
+    } else {
+        4320 // 3 days
+    };
+    // Same prefix as public questions, so closing them works the same way
+    let thread = mci
+        .channel_id
+        .create_private_thread(&ctx.http, |t| {
+            t.name(format!("❓ {}", title).as_str().substring(0, 100))
+                .auto_archive_duration(thread_auto_archive_dur)
+        })
+        .await?;
+    thread.id.add_thread_member(&ctx.http, mci.user.id).await?;
+
+    let db = ctx.get_db().await;
+    db.add_private_ticket(
+        thread.id,
+        mci.guild_id.unwrap(),
+        mci.channel_id,
+        mci.user.id,
+        Timestamp::now().unix_timestamp(),
+    )
+    .await?;
+    Ok(thread)
+}
@@ -0,0 +1,350 @@
+use super::*;
+use serenity::model::{
+    application::interaction::{
+        application_command::{ApplicationCommandInteraction, CommandDataOptionValue},
+        MessageFlags,
+    },
+    id::UserId,
+    Timestamp,
+};
+use std::{
+    collections::HashMap,
+    sync::{Mutex, OnceLock},
+};
+use substr::StringUtils;
+
+/// Discord only bulk deletes messages younger than 14 days, this leaves a margin.
+const BULK_DELETE_MAX_AGE_SECS: i64 = 14 * 86400 - 3600;
+const MAX_COUNT: i64 = 1000;
+/// Older messages go one request at a time, this keeps a purge within the interaction's 15 minutes.
+const MAX_OLD_DELETES: usize = 100;
+// How far back to look for matching messages
+const MAX_SCANNED: usize = 5000;
+
+// Messages deleted by a purge, so the deleted-message relay skips them
+static PURGED: OnceLock<Mutex<HashMap<MessageId, i64>>> = OnceLock::new();
+
+fn mark_purged(ids: &[MessageId]) {
+    let now = Timestamp::now().unix_timestamp();
+    let mut purged = PURGED.get_or_init(Default::default).lock().unwrap();
+    purged.retain(|_, at| *at > now - 600);
+    purged.extend(ids.iter().map(|id| (*id, now)));
+}
+
+/// Whether the message was removed by `/purge`.
+pub fn is_purged(id: MessageId) -> bool {
+    PURGED
+        .get_or_init(Default::default)
+        .lock()
+        .unwrap()
+        .remove(&id)
+        .is_some()
+}
+
+#[derive(Default)]
+struct PurgeFilter {
+    author: Option<UserId>,
+    bots: bool,
+    contains: Option<String>,
+    regex: Option<Regex>,
+    attachments: bool,
+    links: bool,
+    before: Option<MessageId>,
+    after: Option<MessageId>,
+}
+
+impl PurgeFilter {
+    fn matches(&self, msg: &Message) -> bool {
+        !msg.pinned
+            && self.author.is_none_or(|x| msg.author.id == x)
+            && (!self.bots || msg.author.bot)
+            && self
+                .contains
+                .as_ref()
+                .is_none_or(|x| msg.content.to_lowercase().contains(&x.to_lowercase()))
+            && self.regex.as_ref().is_none_or(|x| x.is_match(&msg.content))
+            && (!self.attachments || !msg.attachments.is_empty())
+            && (!self.links || msg.content.contains("http://") || msg.content.contains("https://"))
+            && self.after.is_none_or(|x| msg.id > x)
+    }
+
+    fn describe(&self) -> String {
+        let mut parts = Vec::new();
+        if let Some(author) = self.author {
+            parts.push(format!("from {}", author.mention()));
+        }
+        if self.bots {
+            parts.push("bots".to_string());
+        }
+        if let Some(text) = &self.contains {
+            parts.push(format!("containing `{}`", text.replace('`', "'")));
+        }
+        if let Some(regex) = &self.regex {
+            parts.push(format!("matching `{}`", regex.as_str().replace('`', "'")));
+        }
+        if self.attachments {
+            parts.push("with attachments".to_string());
+        }
+        if self.links {
+            parts.push("with links".to_string());
+        }
+        if let Some(before) = self.before {
+            parts.push(format!("before {}", before));
+        }
+        if let Some(after) = self.after {
+            parts.push(format!("after {}", after));
+        }
+        if parts.is_empty() {
+            "any".to_string()
+        } else {
+            parts.join(", ")
+        }
+    }
+}
+
+/// The newest `count` messages matching the filter, newest first.
+async fn collect(
+    ctx: &Context,
+    channel_id: ChannelId,
+    filter: &PurgeFilter,
+    count: usize,
+) -> Result<Vec<Message>> {
+    let mut found = Vec::new();
+    let mut cursor = filter.before;
+    let mut scanned = 0;
+    'pages: while scanned < MAX_SCANNED {
+        let page = channel_id
+            .messages(&ctx.http, |b| match cursor {
+                Some(id) => b.before(id).limit(100),
+                None => b.limit(100),
+            })
+            .await?;
+        if page.is_empty() {
+            break;
+        }
+        scanned += page.len();
+        cursor = page.last().map(|x| x.id);
+        for msg in page {
+            if filter.after.is_some_and(|x| msg.id <= x) {
+                break 'pages;
+            }
+            if filter.matches(&msg) {
+                found.push(msg);
+                if found.len() == count {
+                    break 'pages;
+                }
+            }
+        }
+    }
+    Ok(found)
+}
+
+/// Keeps a copy in the message cache, in the format of the deleted-message relay.
+async fn archive(messages: &[Message]) {
+    let dbnode = Database::from("msgcache".to_string()).await;
+    for msg in messages {
+        let attachments: String = msg
+            .attachments
+            .iter()
+            .map(|x| format!("\n{}", x.url))
+            .collect();
+        dbnode
+            .save_msg(
+                &msg.id,
+                format!(
+                    "{}{}\n> ---MSG_TYPE--- {} `||` At: <t:{}:T>",
+                    &msg.content,
+                    &attachments,
+                    &msg.author,
+                    msg.timestamp.unix_timestamp()
+                ),
+            )
+            .await;
+    }
+}
+
+fn transcript(messages: &[Message]) -> String {
+    messages
+        .iter()
+        .rev()
+        .map(|msg| {
+            let mut line = format!(
+                "[{}] {} ({}): {}",
+                msg.timestamp,
+                msg.author.tag(),
+                msg.author.id,
+                msg.content
+            );
+            for attachment in &msg.attachments {
+                line.push_str(&format!("\n    {}", attachment.url));
+            }
+            line
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+struct Deleted {
+    total: usize,
+    old: usize,
+    /// Old messages left alone, past `MAX_OLD_DELETES`.
+    skipped: usize,
+}
+
+/// Deletes the messages, in bulk where Discord allows it.
+async fn delete(ctx: &Context, channel_id: ChannelId, messages: &[Message]) -> Deleted {
+    let cutoff = Timestamp::now().unix_timestamp() - BULK_DELETE_MAX_AGE_SECS;
+    let (recent, mut old): (Vec<&Message>, Vec<&Message>) = messages
+        .iter()
+        .partition(|x| x.timestamp.unix_timestamp() > cutoff);
+    let skipped = old.len().saturating_sub(MAX_OLD_DELETES);
+    old.truncate(MAX_OLD_DELETES);
+    mark_purged(
+        &recent
+            .iter()
+            .chain(old.iter())
+            .map(|x| x.id)
+            .collect::<Vec<_>>(),
+    );
+
+    let mut deleted = 0;
+    for chunk in recent.chunks(100) {
+        let ids: Vec<MessageId> = chunk.iter().map(|x| x.id).collect();
+        match channel_id.delete_messages(&ctx.http, &ids).await {
+            Ok(_) => deleted += ids.len(),
+            Err(e) => eprintln!("Failed to bulk delete in {}: {}", channel_id, e),
+        }
+    }
+    let mut deleted_old = 0;
+    for msg in old {
+        if channel_id.delete_message(&ctx.http, msg.id).await.is_ok() {
+            deleted_old += 1;
+        }
+    }
+    Deleted {
+        total: deleted + deleted_old,
+        old: deleted_old,
+        skipped,
+    }
+}
+
+async fn purge(mci: &ApplicationCommandInteraction, ctx: &Context) -> String {
+    let guild_id = mci.guild_id.unwrap();
+    let channel_id = mci.channel_id;
+    let mut count = 0;
+    let mut filter = PurgeFilter::default();
+    for option in &mci.data.options {
+        match (option.name.as_str(), &option.resolved) {
+            ("count", Some(CommandDataOptionValue::Integer(i))) => count = *i,
+            ("user", Some(CommandDataOptionValue::User(u, _))) => filter.author = Some(u.id),
+            ("bots", Some(CommandDataOptionValue::Boolean(b))) => filter.bots = *b,
+            ("contains", Some(CommandDataOptionValue::String(s))) => {
+                filter.contains = Some(s.clone())
+            }
+            ("regex", Some(CommandDataOptionValue::String(s))) => match Regex::new(s) {
+                Ok(regex) => filter.regex = Some(regex),
+                Err(e) => return format!("Invalid regex: {}", e),
+            },
+            ("attachments", Some(CommandDataOptionValue::Boolean(b))) => filter.attachments = *b,
+            ("links", Some(CommandDataOptionValue::Boolean(b))) => filter.links = *b,
+            ("before" | "after", Some(CommandDataOptionValue::String(s))) => {
+                // A message id or the end of a message link
+                let id = match s
+                    .trim()
+                    .rsplit('/')
+                    .next()
+                    .and_then(|x| x.parse::<u64>().ok())
+                {
+                    Some(id) => MessageId(id),
+                    None => return format!("`{}` isn't a message id or link", s),
+                };
+                if option.name == "before" {
+                    filter.before = Some(id);
+                } else {
+                    filter.after = Some(id);
+                }
+            }
+            _ => {}
+        }
+    }
+    if !(1..=MAX_COUNT).contains(&count) {
+        return format!("The count has to be between 1 and {}", MAX_COUNT);
+    }
+
+    let messages = match collect(ctx, channel_id, &filter, count as usize).await {
+        Ok(messages) => messages,
+        Err(e) => return format!("Failed to fetch the messages: {}", e),
+    };
+    if messages.is_empty() {
+        return "No messages matched".to_string();
+    }
+    archive(&messages).await;
+    let deleted = delete(ctx, channel_id, &messages).await;
+
+    let mut authors: HashMap<UserId, usize> = HashMap::new();
+    for msg in &messages {
+        *authors.entry(msg.author.id).or_default() += 1;
+    }
+    let mut authors: Vec<(UserId, usize)> = authors.into_iter().collect();
+    authors.sort_by_key(|(_, n)| std::cmp::Reverse(*n));
+    if let Ok(Some(log_channel)) = moderation::mod_log_channel(ctx, guild_id).await {
+        let summary = format!(
+            "🧹 {} purged {} messages in {} ({})\n{}",
+            mci.user.mention(),
+            deleted.total,
+            channel_id.mention(),
+            filter.describe(),
+            authors
+                .iter()
+                .take(10)
+                .map(|(user, n)| format!("{}: {}", user.mention(), n))
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+        let transcript = transcript(&messages);
+        log_channel
+            .send_message(&ctx.http, |m| {
+                m.content(summary.as_str().substring(0, 2000))
+                    .add_file((
+                        transcript.as_bytes(),
+                        format!("purge-{}.txt", channel_id).as_str(),
+                    ))
+                    .allowed_mentions(|a| a.empty_parse())
+            })
+            .await
+            .ok();
+    }
+
+    let mut text = format!(
+        "Deleted {} of {} matching messages",
+        deleted.total,
+        messages.len()
+    );
+    if deleted.old > 0 {
+        text.push_str(&format!(", {} older than 14 days one by one", deleted.old));
+    }
+    if deleted.skipped > 0 {
+        text.push_str(&format!(
+            ". {} older ones were left, those take one request each so run the purge again for them",
+            deleted.skipped
+        ));
+    }
+    text
+}
+
+/// `/purge`
+pub async fn responder(mci: &ApplicationCommandInteraction, ctx: &Context) {
+    mci.create_interaction_response(&ctx.http, |r| {
+        r.kind(InteractionResponseType::DeferredChannelMessageWithSource)
+            .interaction_response_data(|d| d.flags(MessageFlags::EPHEMERAL))
+    })
+    .await
+    .unwrap();
+    let response = purge(mci, ctx).await;
+    if let Err(e) = mci
+        .edit_original_interaction_response(&ctx.http, |d| d.content(&response))
+        .await
+    {
+        eprintln!("Failed to report the purge in {}: {}", mci.channel_id, e);
+    }
+}
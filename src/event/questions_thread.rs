@@ -1,6 +1,54 @@
 use super::*;
 use crate::db::ClientContextExt;
-use serenity::{futures::StreamExt, utils::MessageBuilder};
+use serenity::{builder::CreateComponents, futures::StreamExt, utils::MessageBuilder};
+
+fn has_button(msg: &Message, custom_id: &str) -> bool {
+    msg.components.iter().flat_map(|x| &x.components).any(|x| {
+        matches!(x, ActionRowComponent::Button(b) if b.custom_id.as_deref() == Some(custom_id))
+    })
+}
+
+/// The buttons under the placeholder of a question channel.
+fn placeholder_buttons<'a>(c: &'a mut CreateComponents, locale: &str) -> &'a mut CreateComponents {
+    c.create_action_row(|ar| {
+        ar.create_button(|button| {
+            button
+                .style(ButtonStyle::Primary)
+                .label(tr(locale, "questions-ask-button"))
+                .custom_id("gitpod_create_issue")
+                .emoji(ReactionType::Unicode("💡".to_string()))
+        });
+        ar.create_button(|button| {
+            button
+                .style(ButtonStyle::Secondary)
+                .label(tr(locale, "questions-ask-private-button"))
+                .custom_id("gitpod_create_private_issue")
+                .emoji(ReactionType::Unicode("🔐".to_string()))
+        });
+        ar.create_button(|button| {
+            button
+                // .custom_id("gitpod_docs_link")
+                .style(ButtonStyle::Link)
+                .label(tr(locale, "questions-docs-button"))
+                .emoji(ReactionType::Unicode("📚".to_string()))
+                .url("https://www.gitpod.io/docs/")
+        });
+        ar.create_button(|button| {
+            button
+                .style(ButtonStyle::Link)
+                .label(tr(locale, "questions-youtube-button"))
+                .url("https://youtube.com/playlist?list=PL3TSF5whlprXVp-7Br2oKwQgU4bji1S7H")
+                .emoji(ReactionType::Unicode("📺".to_string()))
+        });
+        ar.create_button(|button| {
+            button
+                .style(ButtonStyle::Link)
+                .label(tr(locale, "questions-status-button"))
+                .emoji(ReactionType::Unicode("🧭".to_string()))
+                .url("https://www.gitpodstatus.com/")
+        })
+    })
+}
 
 pub async fn responder(_ctx: &Context) {
     // #questions, #selfhosted-questions, #openvscode-questions, #documentation
@@ -18,7 +66,22 @@ pub async fn responder(_ctx: &Context) {
 
         let last_msg_id = last_msg_id.first();
 
-        if last_msg_id.is_some() && last_msg_id.unwrap().is_own(&_ctx.cache) {
+        let locale = i18n::channel_locale(_ctx, channel_id).await;
+        if let Some(last_msg) = last_msg_id.filter(|x| x.is_own(&_ctx.cache)) {
+            // Placeholders posted before "Ask privately" existed get the button added
+            if has_button(last_msg, "gitpod_create_issue")
+                && !has_button(last_msg, "gitpod_create_private_issue")
+            {
+                let mut last_msg = last_msg.clone();
+                if let Err(e) = last_msg
+                    .edit(&_ctx.http, |m| {
+                        m.components(|c| placeholder_buttons(c, locale))
+                    })
+                    .await
+                {
+                    eprintln!("Failed to update the placeholder in {}: {}", channel_id, e);
+                }
+            }
             continue;
         }
 
@@ -56,53 +119,24 @@ pub async fn responder(_ctx: &Context) {
 			.send_message(&_ctx, |m| {
 				m.content(
 					MessageBuilder::new()
-						.push_underline_line("**Welcome to the Gitpod community!**")
-						.push_line("Community is at the heart of Gitpod, we’re happy to help you out 🧡")
-						.push_line("Before **asking a question**, remember to check out our documentation or watch our screencasts.")
-						.push_line("If you think Gitpod is not working, please check our status page. Thank you!").build());
-				m.components(|c| {
-					c.create_action_row(|ar| {
-						ar.create_button(|button| {
-							button
-								.style(ButtonStyle::Primary)
-								.label("Ask a question")
-								.custom_id("gitpod_create_issue")
-								.emoji(ReactionType::Unicode("💡".to_string()))
-						});
-						ar.create_button(|button| {
-							button
-								// .custom_id("gitpod_docs_link")
-								.style(ButtonStyle::Link)
-								.label("Docs")
-								.emoji(ReactionType::Unicode("📚".to_string()))
-								.url("https://www.gitpod.io/docs/")
-						});
-						ar.create_button(|button| {
-							button.style(ButtonStyle::Link).label("YouTube").url(
-								"https://youtube.com/playlist?list=PL3TSF5whlprXVp-7Br2oKwQgU4bji1S7H",
-							).emoji(ReactionType::Unicode("📺".to_string()))
-						});
-						ar.create_button(|button| {
-							button
-								.style(ButtonStyle::Link)
-								.label("Status")
-								.emoji(ReactionType::Unicode("🧭".to_string()))
-								.url("https://www.gitpodstatus.com/")
-						})
-					})
-				})
+						.push_underline_line(tr(locale, "questions-welcome-title"))
+						.push_line(tr(locale, "questions-welcome-community"))
+						.push_line(tr(locale, "questions-welcome-docs"))
+						.push_line(tr(locale, "questions-welcome-status")).build());
+				m.components(|c| placeholder_buttons(c, locale))
 			})
 			.await
 			.unwrap();
     }
 
-    let placeholder_text = "**Press the button below** 👇 to gain access to the server";
+    let locale = i18n::channel_locale(_ctx, GETTING_STARTED_CHANNEL).await;
+    let placeholder_text = tr(locale, "getting-started-placeholder");
 
     let mut t = GETTING_STARTED_CHANNEL.messages_iter(&_ctx.http).boxed();
     while let Some(message_result) = t.next().await {
         match message_result {
             Ok(message) => {
-                if message.content == *placeholder_text {
+                if message.content == placeholder_text {
                     return;
                 }
             }
@@ -119,7 +153,7 @@ pub async fn responder(_ctx: &Context) {
             m.components(|c| {
                 c.create_action_row(|a| {
                     a.create_button(|b| {
-                        b.label("Let's go")
+                        b.label(tr(locale, "getting-started-button"))
                             .custom_id("getting_started_letsgo")
                             .style(ButtonStyle::Primary)
                             .emoji(ReactionType::Unicode("🙌".to_string()))
@@ -0,0 +1,620 @@
+use super::*;
+use crate::db::Db;
+use serenity::builder::CreateComponents;
+use serenity::model::{
+    application::interaction::{
+        application_command::ApplicationCommandInteraction,
+        message_component::MessageComponentInteraction, MessageFlags,
+    },
+    channel::{ChannelType, PermissionOverwriteType},
+    guild::VerificationLevel,
+    id::{RoleId, UserId},
+    Permissions, Timestamp,
+};
+use serenity::utils::MessageBuilder;
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::{Mutex, OnceLock},
+};
+
+/// Thresholds and lockdown measures, set per guild with `gp config raid_*`.
+pub struct RaidSettings {
+    pub enabled: bool,
+    pub join_threshold: i64,
+    pub window_secs: i64,
+    pub new_account_days: i64,
+    /// Percentage of the joins in the window made by new accounts
+    pub new_account_ratio: i64,
+    /// 0 leaves the slowmode alone
+    pub slowmode_secs: i64,
+    pub raise_verification: bool,
+    pub pause_onboarding: bool,
+}
+
+impl RaidSettings {
+    pub async fn get(ctx: &Context, guild_id: GuildId) -> Result<Self> {
+        let db = ctx.get_db().await;
+        Ok(Self {
+            // Lockdowns change guild settings, so guilds opt in with `gp config raid_protection true`
+            enabled: db
+                .get_guild_config_as(guild_id, "raid_protection")
+                .await?
+                .unwrap_or(false),
+            join_threshold: db
+                .get_guild_config_as(guild_id, "raid_join_threshold")
+                .await?
+                .unwrap_or(10),
+            window_secs: db
+                .get_guild_config_as(guild_id, "raid_window_secs")
+                .await?
+                .unwrap_or(60),
+            new_account_days: db
+                .get_guild_config_as(guild_id, "raid_new_account_days")
+                .await?
+                .unwrap_or(7),
+            new_account_ratio: db
+                .get_guild_config_as(guild_id, "raid_new_account_ratio")
+                .await?
+                .unwrap_or(50),
+            slowmode_secs: db
+                .get_guild_config_as(guild_id, "raid_slowmode_secs")
+                .await?
+                .unwrap_or(30),
+            raise_verification: db
+                .get_guild_config_as(guild_id, "raid_raise_verification")
+                .await?
+                .unwrap_or(true),
+            pause_onboarding: db
+                .get_guild_config_as(guild_id, "raid_pause_onboarding")
+                .await?
+                .unwrap_or(true),
+        })
+    }
+}
+
+/// What a lockdown changed, to put it back once it's lifted.
+pub struct Lockdown {
+    pub started_at: i64,
+    /// The level before the lockdown, when it was raised
+    pub verification_level: Option<i64>,
+    /// JSON list of channel ids with their slowmode before the lockdown
+    pub slowmodes: String,
+    pub alert_channel_id: Option<i64>,
+    pub alert_message_id: Option<i64>,
+}
+
+impl Db {
+    pub async fn get_raid_lockdown(&self, guild_id: GuildId) -> Result<Option<Lockdown>> {
+        let guild_id = guild_id.0 as i64;
+        let q = sqlx::query_as!(
+            Lockdown,
+            "select started_at, verification_level, slowmodes, alert_channel_id, alert_message_id from raid_lockdowns where guild_id=?",
+            guild_id
+        )
+        .fetch_optional(&self.sqlitedb)
+        .await?;
+        Ok(q)
+    }
+
+    pub async fn set_raid_lockdown(&self, guild_id: GuildId, lockdown: &Lockdown) -> Result<()> {
+        let guild_id = guild_id.0 as i64;
+        sqlx::query!(
+            "insert into raid_lockdowns (guild_id, started_at, verification_level, slowmodes, alert_channel_id, alert_message_id) values (?1, ?2, ?3, ?4, ?5, ?6)
+            on conflict(guild_id) do update set started_at=?2, verification_level=?3, slowmodes=?4, alert_channel_id=?5, alert_message_id=?6",
+            guild_id,
+            lockdown.started_at,
+            lockdown.verification_level,
+            lockdown.slowmodes,
+            lockdown.alert_channel_id,
+            lockdown.alert_message_id
+        )
+        .execute(&self.sqlitedb)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn remove_raid_lockdown(&self, guild_id: GuildId) -> Result<()> {
+        let guild_id = guild_id.0 as i64;
+        sqlx::query!("delete from raid_lockdowns where guild_id=?", guild_id)
+            .execute(&self.sqlitedb)
+            .await?;
+        sqlx::query!("delete from raid_flagged where guild_id=?", guild_id)
+            .execute(&self.sqlitedb)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn add_raid_flagged(
+        &self,
+        guild_id: GuildId,
+        user_id: UserId,
+        joined_at: i64,
+    ) -> Result<()> {
+        let guild_id = guild_id.0 as i64;
+        let user_id = user_id.0 as i64;
+        sqlx::query!(
+            "insert or ignore into raid_flagged (guild_id, user_id, joined_at) values (?, ?, ?)",
+            guild_id,
+            user_id,
+            joined_at
+        )
+        .execute(&self.sqlitedb)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn get_raid_flagged(&self, guild_id: GuildId) -> Result<Vec<UserId>> {
+        let guild_id = guild_id.0 as i64;
+        let q = sqlx::query!(
+            "select user_id from raid_flagged where guild_id=? order by joined_at",
+            guild_id
+        )
+        .fetch_all(&self.sqlitedb)
+        .await?
+        .into_iter()
+        .map(|x| UserId(x.user_id as u64))
+        .collect();
+        Ok(q)
+    }
+
+    pub async fn remove_raid_flagged(&self, guild_id: GuildId, user_id: UserId) -> Result<()> {
+        let guild_id = guild_id.0 as i64;
+        let user_id = user_id.0 as i64;
+        sqlx::query!(
+            "delete from raid_flagged where guild_id=? and user_id=?",
+            guild_id,
+            user_id
+        )
+        .execute(&self.sqlitedb)
+        .await?;
+        Ok(())
+    }
+}
+
+struct Join {
+    at: i64,
+    user_id: UserId,
+    new_account: bool,
+}
+
+// Recent joins per guild, only the last `window_secs` are kept
+static JOINS: OnceLock<Mutex<HashMap<GuildId, VecDeque<Join>>>> = OnceLock::new();
+
+fn verification_level(num: i64) -> VerificationLevel {
+    match num {
+        0 => VerificationLevel::None,
+        1 => VerificationLevel::Low,
+        2 => VerificationLevel::Medium,
+        3 => VerificationLevel::High,
+        _ => VerificationLevel::Higher,
+    }
+}
+
+pub async fn is_locked_down(ctx: &Context, guild_id: GuildId) -> bool {
+    let db = ctx.get_db().await;
+    matches!(db.get_raid_lockdown(guild_id).await, Ok(Some(_)))
+}
+
+/// Whether "Let's go" should turn new members away for now.
+pub async fn is_onboarding_paused(ctx: &Context, guild_id: GuildId) -> bool {
+    is_locked_down(ctx, guild_id).await
+        && RaidSettings::get(ctx, guild_id)
+            .await
+            .map_or(true, |x| x.pause_onboarding)
+}
+
+fn alert_buttons(components: &mut CreateComponents, flagged: usize) -> &mut CreateComponents {
+    components.create_action_row(|a| {
+        a.create_button(|b| {
+            b.custom_id("raid_lift")
+                .label("Lift lockdown")
+                .style(ButtonStyle::Success)
+        });
+        if flagged > 0 {
+            a.create_button(|b| {
+                b.custom_id("raid_kick")
+                    .label(format!("Kick {} flagged accounts", flagged))
+                    .style(ButtonStyle::Danger)
+            });
+        }
+        a
+    })
+}
+
+/// Text channels everyone can talk in.
+fn is_public(channel: &GuildChannel, everyone: RoleId) -> bool {
+    channel.kind == ChannelType::Text
+        && !channel.permission_overwrites.iter().any(|x| {
+            x.kind == PermissionOverwriteType::Role(everyone)
+                && (x.deny.view_channel() || x.deny.send_messages())
+        })
+}
+
+async fn start_lockdown(
+    ctx: &Context,
+    mut guild_id: GuildId,
+    settings: &RaidSettings,
+    reason: String,
+    flagged: &[(UserId, i64)],
+) -> Result<()> {
+    let db = ctx.get_db().await;
+    let now = Timestamp::now().unix_timestamp();
+    let guild = match guild_id.to_guild_cached(&ctx.cache) {
+        Some(guild) => guild,
+        None => anyhow::bail!("guild {} is not cached", guild_id),
+    };
+    // Saved right away so that joins coming in meanwhile don't start a second lockdown
+    let mut lockdown = Lockdown {
+        started_at: now,
+        verification_level: None,
+        slowmodes: "[]".to_string(),
+        alert_channel_id: None,
+        alert_message_id: None,
+    };
+    db.set_raid_lockdown(guild_id, &lockdown).await?;
+    for (user_id, joined_at) in flagged {
+        db.add_raid_flagged(guild_id, *user_id, *joined_at).await?;
+    }
+    println!("Raid lockdown in {}: {}", guild_id, reason);
+
+    let mut measures = MessageBuilder::new();
+    if settings.raise_verification && guild.verification_level < VerificationLevel::High {
+        match guild_id
+            .edit(&ctx.http, |g| g.verification_level(VerificationLevel::High))
+            .await
+        {
+            Ok(_) => {
+                lockdown.verification_level = Some(guild.verification_level.num() as i64);
+                measures.push_line("• Verification level raised to **High**");
+            }
+            Err(e) => {
+                eprintln!(
+                    "Failed to raise the verification level of {}: {}",
+                    guild_id, e
+                );
+                measures.push_line(format!("• Couldn't raise the verification level: {}", e));
+            }
+        }
+    }
+    if settings.pause_onboarding {
+        measures.push_line(format!(
+            "• Onboarding paused in {}",
+            GETTING_STARTED_CHANNEL.mention()
+        ));
+    }
+    if settings.slowmode_secs > 0 {
+        let everyone = RoleId(guild_id.0);
+        let mut slowmodes: Vec<(ChannelId, u64)> = Vec::new();
+        for channel in guild.channels.values().filter_map(|x| x.clone().guild()) {
+            let previous = channel.rate_limit_per_user.unwrap_or(0);
+            if !is_public(&channel, everyone) || previous >= settings.slowmode_secs as u64 {
+                continue;
+            }
+            if channel
+                .id
+                .edit(&ctx.http, |c| {
+                    c.rate_limit_per_user(settings.slowmode_secs as u64)
+                })
+                .await
+                .is_ok()
+            {
+                slowmodes.push((channel.id, previous));
+            }
+        }
+        lockdown.slowmodes = serde_json::to_string(&slowmodes)?;
+        measures.push_line(format!(
+            "• Slowmode of {}s in {} public channels",
+            settings.slowmode_secs,
+            slowmodes.len()
+        ));
+    }
+    db.set_raid_lockdown(guild_id, &lockdown).await?;
+
+    let alert_channel = match db
+        .get_guild_config_as::<ChannelId>(guild_id, "log_channel")
+        .await?
+        .or(guild.system_channel_id)
+    {
+        Some(channel) => channel,
+        None => return Ok(()),
+    };
+    let staff_role = db
+        .get_guild_config_as::<RoleId>(guild_id, "staff_role")
+        .await?;
+    let mut content = MessageBuilder::new();
+    if let Some(role_id) = staff_role {
+        content.mention(&role_id).push(" ");
+    }
+    content
+        .push_bold_line("🚨 Raid protection: lockdown")
+        .push_line(reason)
+        .push_line("")
+        .push(measures.build())
+        .push_line(format!(
+            "{} recently joined new accounts are flagged",
+            flagged.len()
+        ));
+    let alert = alert_channel
+        .send_message(&ctx.http, |m| {
+            m.content(content.build())
+                .components(|c| alert_buttons(c, flagged.len()))
+        })
+        .await?;
+    lockdown.alert_channel_id = Some(alert_channel.0 as i64);
+    lockdown.alert_message_id = Some(alert.id.0 as i64);
+    db.set_raid_lockdown(guild_id, &lockdown).await?;
+    Ok(())
+}
+
+/// Puts back what the lockdown changed.
+async fn lift_lockdown(ctx: &Context, mut guild_id: GuildId) -> Result<String> {
+    let db = ctx.get_db().await;
+    let lockdown = match db.get_raid_lockdown(guild_id).await? {
+        Some(lockdown) => lockdown,
+        None => return Ok("There is no lockdown to lift".to_string()),
+    };
+    db.remove_raid_lockdown(guild_id).await?;
+
+    if let Some(level) = lockdown.verification_level {
+        guild_id
+            .edit(&ctx.http, |g| {
+                g.verification_level(verification_level(level))
+            })
+            .await?;
+    }
+    let slowmodes: Vec<(ChannelId, u64)> = serde_json::from_str(&lockdown.slowmodes)?;
+    for (channel_id, previous) in &slowmodes {
+        channel_id
+            .edit(&ctx.http, |c| c.rate_limit_per_user(*previous))
+            .await
+            .ok();
+    }
+    Ok(format!(
+        "Lockdown lifted after {}, slowmode restored in {} channels",
+        crate::utils::misc::format_duration(
+            Timestamp::now().unix_timestamp() - lockdown.started_at
+        ),
+        slowmodes.len()
+    ))
+}
+
+async fn kick_flagged(ctx: &Context, guild_id: GuildId) -> Result<String> {
+    let db = ctx.get_db().await;
+    let flagged = db.get_raid_flagged(guild_id).await?;
+    let mut kicked = 0;
+    for user_id in &flagged {
+        if guild_id
+            .kick_with_reason(&ctx.http, *user_id, "Raid protection")
+            .await
+            .is_ok()
+        {
+            kicked += 1;
+        }
+        db.remove_raid_flagged(guild_id, *user_id).await?;
+    }
+    Ok(format!(
+        "Kicked {} of {} flagged accounts",
+        kicked,
+        flagged.len()
+    ))
+}
+
+/// Called on every join, tracks the join rate and locks the guild down when it looks like a raid.
+pub async fn member_joined(ctx: &Context, member: &Member) -> Result<()> {
+    let guild_id = member.guild_id;
+    let settings = RaidSettings::get(ctx, guild_id).await?;
+    if !settings.enabled {
+        return Ok(());
+    }
+    let now = Timestamp::now().unix_timestamp();
+    let new_account =
+        now - member.user.created_at().unix_timestamp() < settings.new_account_days * 86400;
+
+    let db = ctx.get_db().await;
+    if db.get_raid_lockdown(guild_id).await?.is_some() {
+        if new_account {
+            db.add_raid_flagged(guild_id, member.user.id, now).await?;
+        }
+        return Ok(());
+    }
+
+    let raid = {
+        let mut joins = JOINS.get_or_init(Default::default).lock().unwrap();
+        let window = joins.entry(guild_id).or_default();
+        window.push_back(Join {
+            at: now,
+            user_id: member.user.id,
+            new_account,
+        });
+        while window
+            .front()
+            .is_some_and(|x| x.at <= now - settings.window_secs)
+        {
+            window.pop_front();
+        }
+        let new_joins: Vec<(UserId, i64)> = window
+            .iter()
+            .filter(|x| x.new_account)
+            .map(|x| (x.user_id, x.at))
+            .collect();
+        let total = window.len() as i64;
+        if total >= settings.join_threshold
+            && new_joins.len() as i64 * 100 >= settings.new_account_ratio * total
+        {
+            window.clear();
+            Some((total, new_joins))
+        } else {
+            None
+        }
+    };
+
+    if let Some((total, new_joins)) = raid {
+        let reason = format!(
+            "{} members joined in the last {}s, {} of them with accounts younger than {} days",
+            total,
+            settings.window_secs,
+            new_joins.len(),
+            settings.new_account_days
+        );
+        start_lockdown(ctx, guild_id, &settings, reason, &new_joins).await?;
+    }
+    Ok(())
+}
+
+/// The Lift and Kick buttons of the alert.
+pub async fn handle_button(mci: &MessageComponentInteraction, ctx: &Context) {
+    let guild_id = mci.guild_id.unwrap();
+    let permissions = mci
+        .member
+        .as_ref()
+        .and_then(|x| x.permissions)
+        .unwrap_or_else(Permissions::empty);
+    let allowed = match mci.data.custom_id.as_str() {
+        "raid_lift" => permissions.manage_guild(),
+        "raid_kick" => permissions.kick_members(),
+        _ => return,
+    };
+    if !allowed {
+        mci.create_interaction_response(&ctx.http, |r| {
+            r.kind(InteractionResponseType::ChannelMessageWithSource)
+                .interaction_response_data(|d| {
+                    d.content("You don't have the permission to do that")
+                        .flags(MessageFlags::EPHEMERAL)
+                })
+        })
+        .await
+        .unwrap();
+        return;
+    }
+
+    // Kicking can take a while
+    mci.create_interaction_response(&ctx.http, |r| {
+        r.kind(InteractionResponseType::DeferredChannelMessageWithSource)
+            .interaction_response_data(|d| d.flags(MessageFlags::EPHEMERAL))
+    })
+    .await
+    .unwrap();
+    let lifted = mci.data.custom_id == "raid_lift";
+    let result = if lifted {
+        lift_lockdown(ctx, guild_id).await
+    } else {
+        kick_flagged(ctx, guild_id).await
+    };
+    let text = match result {
+        Ok(text) => text,
+        Err(e) => format!("Failed: {}", e),
+    };
+
+    let mut alert = mci.message.clone();
+    let content = format!("{}\n\n{} — {}", alert.content, mci.user.mention(), text);
+    alert
+        .edit(&ctx.http, |m| {
+            m.content(content);
+            if lifted {
+                m.components(|c| c)
+            } else {
+                m.components(|c| alert_buttons(c, 0))
+            }
+        })
+        .await
+        .ok();
+    mci.edit_original_interaction_response(&ctx.http, |d| d.content(text))
+        .await
+        .unwrap();
+}
+
+pub async fn responder(mci: &ApplicationCommandInteraction, ctx: &Context) {
+    let guild_id = mci.guild_id.unwrap();
+    let subcommand = match mci.data.options.first() {
+        Some(x) => x,
+        None => return,
+    };
+    let db = ctx.get_db().await;
+    let settings = RaidSettings::get(ctx, guild_id).await.unwrap();
+
+    let response = match subcommand.name.as_str() {
+        "status" => {
+            let lockdown = db.get_raid_lockdown(guild_id).await.unwrap();
+            let flagged = db.get_raid_flagged(guild_id).await.unwrap();
+            let now = Timestamp::now().unix_timestamp();
+            let (recent, recent_new) = JOINS
+                .get_or_init(Default::default)
+                .lock()
+                .unwrap()
+                .get(&guild_id)
+                .map_or((0, 0), |x| {
+                    let recent = x.iter().filter(|j| j.at > now - settings.window_secs);
+                    (
+                        recent.clone().count(),
+                        recent.filter(|j| j.new_account).count(),
+                    )
+                });
+            let mut text = MessageBuilder::new();
+            text.push_line(match &lockdown {
+                Some(lockdown) => format!(
+                    "🚨 **Locked down** since <t:{}:R>, {} flagged accounts",
+                    lockdown.started_at,
+                    flagged.len()
+                ),
+                None => "No lockdown".to_string(),
+            })
+            .push_line(format!(
+                "Joins in the last {}s: {}, {} of them new accounts",
+                settings.window_secs, recent, recent_new
+            ))
+            .push_line("")
+            .push_line(if settings.enabled {
+                format!(
+                    "Locks down at {} joins within {}s when at least {}% of the accounts are younger than {} days",
+                    settings.join_threshold,
+                    settings.window_secs,
+                    settings.new_account_ratio,
+                    settings.new_account_days
+                )
+            } else {
+                "Raid protection is disabled".to_string()
+            })
+            .push_line(format!(
+                "Lockdown: raise verification {}, pause onboarding {}, slowmode {}s",
+                settings.raise_verification, settings.pause_onboarding, settings.slowmode_secs
+            ));
+            text.build()
+        }
+        "lockdown" => {
+            if db.get_raid_lockdown(guild_id).await.unwrap().is_some() {
+                "The server is already locked down".to_string()
+            } else {
+                let now = Timestamp::now().unix_timestamp();
+                let new_joins: Vec<(UserId, i64)> = JOINS
+                    .get_or_init(Default::default)
+                    .lock()
+                    .unwrap()
+                    .get(&guild_id)
+                    .map(|x| {
+                        x.iter()
+                            .filter(|j| j.new_account && j.at > now - settings.window_secs)
+                            .map(|j| (j.user_id, j.at))
+                            .collect()
+                    })
+                    .unwrap_or_default();
+                let reason = format!("Started by {}", mci.user.mention());
+                match start_lockdown(ctx, guild_id, &settings, reason, &new_joins).await {
+                    Ok(()) => "Locked down, see the alert for the details".to_string(),
+                    Err(e) => format!("Failed to lock down: {}", e),
+                }
+            }
+        }
+        "lift" => match lift_lockdown(ctx, guild_id).await {
+            Ok(text) => text,
+            Err(e) => format!("Failed to lift the lockdown: {}", e),
+        },
+        _ => return,
+    };
+
+    mci.create_interaction_response(&ctx.http, |r| {
+        r.kind(InteractionResponseType::ChannelMessageWithSource)
+            .interaction_response_data(|d| d.content(response).flags(MessageFlags::EPHEMERAL))
+    })
+    .await
+    .unwrap();
+}
@@ -19,7 +19,861 @@ pub async fn responder(_ctx: &Context, ready: Ready) {
 
     let commands = GuildId::set_application_commands(&guild_id, &_ctx.http, |commands| {
         commands.create_application_command(|command| {
-            command.name("close").description("Close a question")
+            command
+                .name("close")
+                .description("Close a question or modmail thread")
+        });
+        commands.create_application_command(|c| {
+            c.name("support-stats")
+                .description("Question response and resolution times")
+                .kind(CommandType::ChatInput)
+                .default_member_permissions(Permissions::ADMINISTRATOR)
+                .create_option(|opt| {
+                    opt.kind(CommandOptionType::Channel)
+                        .name("channel")
+                        .description("Only include questions from this channel")
+                        .required(false)
+                })
+                .create_option(|opt| {
+                    opt.kind(CommandOptionType::Integer)
+                        .name("weeks")
+                        .description("How many weeks to look back (default: 8)")
+                        .min_int_value(1)
+                        .max_int_value(52)
+                        .required(false)
+                })
+                .create_option(|opt| {
+                    opt.kind(CommandOptionType::Boolean)
+                        .name("csv")
+                        .description("Attach the raw data as a CSV file")
+                        .required(false)
+                })
+        });
+        commands.create_application_command(|c| {
+            c.name("digest")
+                .description("Digest of open and unanswered questions")
+                .kind(CommandType::ChatInput)
+                .default_member_permissions(Permissions::ADMINISTRATOR)
+                .create_option(|opt| {
+                    opt.kind(CommandOptionType::SubCommand)
+                        .name("now")
+                        .description("Post the digest right here")
+                })
+                .create_option(|opt| {
+                    opt.kind(CommandOptionType::SubCommand)
+                        .name("configure")
+                        .description("Set where and when the digest is posted")
+                        .create_sub_option(|sub| {
+                            sub.kind(CommandOptionType::Channel)
+                                .name("channel")
+                                .description("Staff channel to post the digest in")
+                                .required(true)
+                        })
+                        .create_sub_option(|sub| {
+                            sub.kind(CommandOptionType::String)
+                                .name("schedule")
+                                .description("Cron expression in UTC, e.g. `0 9 * * Mon`")
+                                .required(true)
+                        })
+                })
+        });
+        commands.create_application_command(|c| {
+            c.name("oncall")
+                .description("Weekly on-call rotation for unanswered questions")
+                .kind(CommandType::ChatInput)
+                .default_member_permissions(Permissions::ADMINISTRATOR)
+                .create_option(|opt| {
+                    opt.kind(CommandOptionType::SubCommand)
+                        .name("set")
+                        .description("Set the rotation, starting with this week");
+                    for i in 1..=oncall::ROTATION_MAX_USERS {
+                        opt.create_sub_option(|sub| {
+                            sub.kind(CommandOptionType::User)
+                                .name(format!("user{}", i))
+                                .description(format!("On call in week {} of the rotation", i))
+                                .required(i == 1)
+                        });
+                    }
+                    opt
+                })
+                .create_option(|opt| {
+                    opt.kind(CommandOptionType::SubCommand)
+                        .name("show")
+                        .description("Show who is on call in the coming weeks")
+                })
+                .create_option(|opt| {
+                    opt.kind(CommandOptionType::SubCommand)
+                        .name("swap")
+                        .description("Swap who is on call in two weeks")
+                        .create_sub_option(|sub| {
+                            sub.kind(CommandOptionType::Integer)
+                                .name("first")
+                                .description("Weeks from now, 0 is this week")
+                                .min_int_value(0)
+                                .required(true)
+                        })
+                        .create_sub_option(|sub| {
+                            sub.kind(CommandOptionType::Integer)
+                                .name("second")
+                                .description("Weeks from now, 0 is this week")
+                                .min_int_value(0)
+                                .required(true)
+                        })
+                })
+        });
+        commands.create_application_command(|c| {
+            c.name("onboarding")
+                .description("Steps new members go through after pressing \"Let's go\"")
+                .kind(CommandType::ChatInput)
+                .default_member_permissions(Permissions::ADMINISTRATOR)
+                .create_option(|opt| {
+                    opt.kind(CommandOptionType::SubCommand)
+                        .name("show")
+                        .description("List the steps and what each option does")
+                })
+                .create_option(|opt| {
+                    opt.kind(CommandOptionType::SubCommand)
+                        .name("preview")
+                        .description("Show a step the way members see it")
+                        .create_sub_option(|sub| {
+                            sub.kind(CommandOptionType::Integer)
+                                .name("step")
+                                .description("Step number, starting at 1")
+                                .min_int_value(1)
+                                .required(false)
+                        })
+                })
+                .create_option(|opt| {
+                    opt.kind(CommandOptionType::SubCommand)
+                        .name("export")
+                        .description("Download the steps as YAML")
+                })
+                .create_option(|opt| {
+                    opt.kind(CommandOptionType::SubCommand)
+                        .name("import")
+                        .description("Replace the steps with a YAML definition")
+                        .create_sub_option(|sub| {
+                            sub.kind(CommandOptionType::Attachment)
+                                .name("file")
+                                .description("YAML file, start from `/onboarding export`")
+                                .required(true)
+                        })
+                })
+                .create_option(|opt| {
+                    opt.kind(CommandOptionType::SubCommand)
+                        .name("reset")
+                        .description("Go back to the default steps")
+                })
+        });
+        commands.create_application_command(|c| {
+            c.name("roles")
+                .description("Pick the topic and language roles you want")
+                .kind(CommandType::ChatInput)
+        });
+        commands.create_application_command(|c| {
+            c.name("role-menu")
+                .description("Post a menu members can pick their roles from")
+                .kind(CommandType::ChatInput)
+                .default_member_permissions(Permissions::ADMINISTRATOR)
+                .create_option(|opt| {
+                    opt.kind(CommandOptionType::Channel)
+                        .name("channel")
+                        .description("Where to post it (default: this channel)")
+                        .required(false)
+                })
+        });
+        commands.create_application_command(|c| {
+            c.name("welcome")
+                .description("Announcements of members joining and leaving")
+                .kind(CommandType::ChatInput)
+                .default_member_permissions(Permissions::ADMINISTRATOR)
+                .create_option(|opt| {
+                    opt.kind(CommandOptionType::SubCommand)
+                        .name("set")
+                        .description("Change the announcement of joins or leaves")
+                        .create_sub_option(|sub| {
+                            sub.kind(CommandOptionType::String)
+                                .name("event")
+                                .description("Which announcement to change")
+                                .add_string_choice("join", "join")
+                                .add_string_choice("leave", "leave")
+                                .required(true)
+                        })
+                        .create_sub_option(|sub| {
+                            sub.kind(CommandOptionType::Boolean)
+                                .name("enabled")
+                                .description("Whether to post it")
+                                .required(false)
+                        })
+                        .create_sub_option(|sub| {
+                            sub.kind(CommandOptionType::Channel)
+                                .name("channel")
+                                .description("Where to post it (default: the system channel)")
+                                .required(false)
+                        })
+                        .create_sub_option(|sub| {
+                            sub.kind(CommandOptionType::String)
+                                .name("template")
+                                .description("Text with {tag}, {mention}, {id}, {account_age}, {member_count}, {invite}, or `default`")
+                                .required(false)
+                        })
+                })
+                .create_option(|opt| {
+                    opt.kind(CommandOptionType::SubCommand)
+                        .name("show")
+                        .description("Show the current settings")
+                })
+                .create_option(|opt| {
+                    opt.kind(CommandOptionType::SubCommand)
+                        .name("test")
+                        .description("Preview both announcements with yourself as the member")
+                })
+        });
+        commands.create_application_command(|c| {
+            c.name("raid")
+                .description("Join raid protection")
+                .kind(CommandType::ChatInput)
+                .default_member_permissions(Permissions::MANAGE_GUILD)
+                .create_option(|opt| {
+                    opt.kind(CommandOptionType::SubCommand)
+                        .name("status")
+                        .description("Recent joins, thresholds and whether the server is locked down")
+                })
+                .create_option(|opt| {
+                    opt.kind(CommandOptionType::SubCommand)
+                        .name("lockdown")
+                        .description("Lock the server down now")
+                })
+                .create_option(|opt| {
+                    opt.kind(CommandOptionType::SubCommand)
+                        .name("lift")
+                        .description("End the lockdown and undo its changes")
+                })
+        });
+        commands.create_application_command(|c| {
+            c.name("screen")
+                .description("Username screening rules, run on join and on name changes")
+                .kind(CommandType::ChatInput)
+                .default_member_permissions(Permissions::MANAGE_GUILD)
+                .create_option(|opt| {
+                    opt.kind(CommandOptionType::SubCommand)
+                        .name("add")
+                        .description("Add a rule")
+                        .create_sub_option(|sub| {
+                            sub.kind(CommandOptionType::String)
+                                .name("pattern")
+                                .description("Regex, or the text to look for when matching confusables")
+                                .required(true)
+                        })
+                        .create_sub_option(|sub| {
+                            sub.kind(CommandOptionType::String)
+                                .name("action")
+                                .description("What to do with members whose name matches")
+                                .add_string_choice("flag", "flag")
+                                .add_string_choice("quarantine", "quarantine")
+                                .add_string_choice("kick", "kick")
+                                .add_string_choice("ban", "ban")
+                                .required(true)
+                        })
+                        .create_sub_option(|sub| {
+                            sub.kind(CommandOptionType::String)
+                                .name("reason")
+                                .description("Logged, and sent to the member when kicked or banned")
+                                .required(true)
+                        })
+                        .create_sub_option(|sub| {
+                            sub.kind(CommandOptionType::String)
+                                .name("match")
+                                .description("How to compare the pattern (default: regex)")
+                                .add_string_choice("regex", "regex")
+                                .add_string_choice("confusable", "confusable")
+                                .required(false)
+                        })
+                })
+                .create_option(|opt| {
+                    opt.kind(CommandOptionType::SubCommand)
+                        .name("list")
+                        .description("List the rules with their hits")
+                })
+                .create_option(|opt| {
+                    opt.kind(CommandOptionType::SubCommand)
+                        .name("test")
+                        .description("Check which rules a name would match")
+                        .create_sub_option(|sub| {
+                            sub.kind(CommandOptionType::String)
+                                .name("name")
+                                .description("The name to check")
+                                .required(true)
+                        })
+                })
+                .create_option(|opt| {
+                    opt.kind(CommandOptionType::SubCommand)
+                        .name("remove")
+                        .description("Remove a rule, its hits stay in the log")
+                        .create_sub_option(|sub| {
+                            sub.kind(CommandOptionType::Integer)
+                                .name("id")
+                                .description("Id of the rule, see `/screen list`")
+                                .required(true)
+                        })
+                })
+        });
+        commands.create_application_command(|c| {
+            c.name("names")
+                .description("Past usernames and nicknames of a member")
+                .kind(CommandType::ChatInput)
+                .default_member_permissions(Permissions::MODERATE_MEMBERS)
+                .create_option(|opt| {
+                    opt.kind(CommandOptionType::User)
+                        .name("user")
+                        .description("Whose names to show")
+                        .required(true)
+                })
+        });
+        commands.create_application_command(|c| {
+            c.name("warn")
+                .description("Warn a member and open a case")
+                .kind(CommandType::ChatInput)
+                .default_member_permissions(Permissions::MODERATE_MEMBERS)
+                .create_option(|opt| {
+                    opt.kind(CommandOptionType::User)
+                        .name("user")
+                        .description("The member")
+                        .required(true)
+                })
+                .create_option(|opt| {
+                    opt.kind(CommandOptionType::String)
+                        .name("reason")
+                        .description("Why, also sent to them")
+                        .required(true)
+                })
+                .create_option(|opt| {
+                    opt.kind(CommandOptionType::String)
+                        .name("evidence")
+                        .description("Message links, separated by spaces")
+                        .required(false)
+                })
+        });
+        commands.create_application_command(|c| {
+            c.name("timeout")
+                .description("Time a member out and open a case")
+                .kind(CommandType::ChatInput)
+                .default_member_permissions(Permissions::MODERATE_MEMBERS)
+                .create_option(|opt| {
+                    opt.kind(CommandOptionType::User)
+                        .name("user")
+                        .description("The member")
+                        .required(true)
+                })
+                .create_option(|opt| {
+                    opt.kind(CommandOptionType::String)
+                        .name("duration")
+                        .description("How long, e.g. 30m, 12h or 7d (up to 28d)")
+                        .required(true)
+                })
+                .create_option(|opt| {
+                    opt.kind(CommandOptionType::String)
+                        .name("reason")
+                        .description("Why, also sent to them")
+                        .required(false)
+                })
+                .create_option(|opt| {
+                    opt.kind(CommandOptionType::String)
+                        .name("evidence")
+                        .description("Message links, separated by spaces")
+                        .required(false)
+                })
+        });
+        commands.create_application_command(|c| {
+            c.name("kick")
+                .description("Kick a member and open a case")
+                .kind(CommandType::ChatInput)
+                .default_member_permissions(Permissions::KICK_MEMBERS)
+                .create_option(|opt| {
+                    opt.kind(CommandOptionType::User)
+                        .name("user")
+                        .description("The member")
+                        .required(true)
+                })
+                .create_option(|opt| {
+                    opt.kind(CommandOptionType::String)
+                        .name("reason")
+                        .description("Why, also sent to them")
+                        .required(false)
+                })
+                .create_option(|opt| {
+                    opt.kind(CommandOptionType::String)
+                        .name("evidence")
+                        .description("Message links, separated by spaces")
+                        .required(false)
+                })
+        });
+        commands.create_application_command(|c| {
+            c.name("ban")
+                .description("Ban a user and open a case")
+                .kind(CommandType::ChatInput)
+                .default_member_permissions(Permissions::BAN_MEMBERS)
+                .create_option(|opt| {
+                    opt.kind(CommandOptionType::User)
+                        .name("user")
+                        .description("The user")
+                        .required(true)
+                })
+                .create_option(|opt| {
+                    opt.kind(CommandOptionType::String)
+                        .name("reason")
+                        .description("Why, also sent to them")
+                        .required(false)
+                })
+                .create_option(|opt| {
+                    opt.kind(CommandOptionType::String)
+                        .name("duration")
+                        .description("Lift the ban after e.g. 7d (default: never)")
+                        .required(false)
+                })
+                .create_option(|opt| {
+                    opt.kind(CommandOptionType::Integer)
+                        .name("delete_messages")
+                        .description("Days of their messages to delete, up to 7").min_int_value(0).max_int_value(7)
+                        .required(false)
+                })
+                .create_option(|opt| {
+                    opt.kind(CommandOptionType::String)
+                        .name("evidence")
+                        .description("Message links, separated by spaces")
+                        .required(false)
+                })
+        });
+        commands.create_application_command(|c| {
+            c.name("unban")
+                .description("Unban a user and open a case")
+                .kind(CommandType::ChatInput)
+                .default_member_permissions(Permissions::BAN_MEMBERS)
+                .create_option(|opt| {
+                    opt.kind(CommandOptionType::User)
+                        .name("user")
+                        .description("The user, by id")
+                        .required(true)
+                })
+                .create_option(|opt| {
+                    opt.kind(CommandOptionType::String)
+                        .name("reason")
+                        .description("Why")
+                        .required(false)
+                })
+        });
+        commands.create_application_command(|c| {
+            c.name("history")
+                .description("Moderation cases of a user")
+                .kind(CommandType::ChatInput)
+                .default_member_permissions(Permissions::MODERATE_MEMBERS)
+                .create_option(|opt| {
+                    opt.kind(CommandOptionType::User)
+                        .name("user")
+                        .description("The user")
+                        .required(true)
+                })
+        });
+        commands.create_application_command(|c| {
+            c.name("case")
+                .description("Look up or change a moderation case")
+                .kind(CommandType::ChatInput)
+                .default_member_permissions(Permissions::MODERATE_MEMBERS)
+                .create_option(|opt| {
+                    opt.kind(CommandOptionType::SubCommand)
+                        .name("view")
+                        .description("Show a case")
+                        .create_sub_option(|sub| {
+                            sub.kind(CommandOptionType::Integer)
+                                .name("number")
+                                .description("The case number")
+                                .required(true)
+                        })
+                })
+                .create_option(|opt| {
+                    opt.kind(CommandOptionType::SubCommand)
+                        .name("edit")
+                        .description("Change the reason or evidence of a case")
+                        .create_sub_option(|sub| {
+                            sub.kind(CommandOptionType::Integer)
+                                .name("number")
+                                .description("The case number")
+                                .required(true)
+                        })
+                        .create_sub_option(|sub| {
+                            sub.kind(CommandOptionType::String)
+                                .name("reason")
+                                .description("The new reason")
+                                .required(false)
+                        })
+                        .create_sub_option(|sub| {
+                            sub.kind(CommandOptionType::String)
+                                .name("evidence")
+                                .description("Message links replacing the current ones")
+                                .required(false)
+                        })
+                })
+        });
+        commands.create_application_command(|c| {
+            c.name("filters")
+                .description("Content filters on messages and edits")
+                .kind(CommandType::ChatInput)
+                .default_member_permissions(Permissions::MANAGE_GUILD)
+                .create_option(|opt| {
+                    opt.kind(CommandOptionType::SubCommand)
+                        .name("show")
+                        .description("List the filters")
+                })
+                .create_option(|opt| {
+                    opt.kind(CommandOptionType::SubCommand)
+                        .name("export")
+                        .description("Download the filters as YAML, or an example when there are none")
+                })
+                .create_option(|opt| {
+                    opt.kind(CommandOptionType::SubCommand)
+                        .name("import")
+                        .description("Replace the filters")
+                        .create_sub_option(|sub| {
+                            sub.kind(CommandOptionType::Attachment)
+                                .name("definition")
+                                .description("YAML file, start from `/filters export`")
+                                .required(true)
+                        })
+                })
+                .create_option(|opt| {
+                    opt.kind(CommandOptionType::SubCommand)
+                        .name("reset")
+                        .description("Remove all filters")
+                })
+                .create_option(|opt| {
+                    opt.kind(CommandOptionType::SubCommand)
+                        .name("dry-run")
+                        .description("Only log what the filters would do, to tune them")
+                        .create_sub_option(|sub| {
+                            sub.kind(CommandOptionType::Boolean)
+                                .name("enabled")
+                                .description("Whether to only log")
+                                .required(true)
+                        })
+                })
+        });
+        commands.create_application_command(|c| {
+            c.name("reply")
+                .description("Answer in this modmail thread")
+                .kind(CommandType::ChatInput)
+                .default_member_permissions(Permissions::MODERATE_MEMBERS)
+                .create_option(|opt| {
+                    opt.kind(CommandOptionType::String)
+                        .name("message")
+                        .description("What to send")
+                        .required(false)
+                })
+                .create_option(|opt| {
+                    opt.kind(CommandOptionType::String)
+                        .name("snippet")
+                        .description("A saved reply to send before the message")
+                        .required(false)
+                })
+                .create_option(|opt| {
+                    opt.kind(CommandOptionType::Attachment)
+                        .name("attachment")
+                        .description("A file to send along")
+                        .required(false)
+                })
+        });
+        commands.create_application_command(|c| {
+            c.name("areply")
+                .description("Answer in this modmail thread without your name")
+                .kind(CommandType::ChatInput)
+                .default_member_permissions(Permissions::MODERATE_MEMBERS)
+                .create_option(|opt| {
+                    opt.kind(CommandOptionType::String)
+                        .name("message")
+                        .description("What to send")
+                        .required(false)
+                })
+                .create_option(|opt| {
+                    opt.kind(CommandOptionType::String)
+                        .name("snippet")
+                        .description("A saved reply to send before the message")
+                        .required(false)
+                })
+                .create_option(|opt| {
+                    opt.kind(CommandOptionType::Attachment)
+                        .name("attachment")
+                        .description("A file to send along")
+                        .required(false)
+                })
+        });
+        commands.create_application_command(|c| {
+            c.name("modmail")
+                .description("Manage modmail blocks and snippets")
+                .kind(CommandType::ChatInput)
+                .default_member_permissions(Permissions::MODERATE_MEMBERS)
+                .create_option(|opt| {
+                    opt.kind(CommandOptionType::SubCommand)
+                        .name("block")
+                        .description("Stop a user from opening modmail")
+                        .create_sub_option(|sub| {
+                            sub.kind(CommandOptionType::User)
+                                .name("user")
+                                .description("Who to block")
+                                .required(true)
+                        })
+                        .create_sub_option(|sub| {
+                            sub.kind(CommandOptionType::String)
+                                .name("reason")
+                                .description("Why")
+                                .required(false)
+                        })
+                })
+                .create_option(|opt| {
+                    opt.kind(CommandOptionType::SubCommand)
+                        .name("unblock")
+                        .description("Let a user open modmail again")
+                        .create_sub_option(|sub| {
+                            sub.kind(CommandOptionType::User)
+                                .name("user")
+                                .description("Who to unblock")
+                                .required(true)
+                        })
+                })
+                .create_option(|opt| {
+                    opt.kind(CommandOptionType::SubCommand)
+                        .name("blocked")
+                        .description("List blocked users")
+                })
+                .create_option(|opt| {
+                    opt.kind(CommandOptionType::SubCommand)
+                        .name("snippet-set")
+                        .description("Save a canned reply")
+                        .create_sub_option(|sub| {
+                            sub.kind(CommandOptionType::String)
+                                .name("name")
+                                .description("Name to send it by")
+                                .required(true)
+                        })
+                        .create_sub_option(|sub| {
+                            sub.kind(CommandOptionType::String)
+                                .name("content")
+                                .description("The reply")
+                                .required(true)
+                        })
+                })
+                .create_option(|opt| {
+                    opt.kind(CommandOptionType::SubCommand)
+                        .name("snippet-remove")
+                        .description("Remove a canned reply")
+                        .create_sub_option(|sub| {
+                            sub.kind(CommandOptionType::String)
+                                .name("name")
+                                .description("The snippet's name")
+                                .required(true)
+                        })
+                })
+                .create_option(|opt| {
+                    opt.kind(CommandOptionType::SubCommand)
+                        .name("snippets")
+                        .description("List canned replies")
+                })
+        });
+        commands.create_application_command(|c| {
+            c.name("Run code")
+                .kind(CommandType::Message)
+                .dm_permission(false)
+        });
+        commands.create_application_command(|c| {
+            c.name("runtimes")
+                .description("Languages and versions `gp exec` can run")
+                .kind(CommandType::ChatInput)
+                .create_option(|opt| {
+                    opt.kind(CommandOptionType::String)
+                        .name("language")
+                        .description("Only show runtimes matching this name or alias")
+                        .required(false)
+                })
+        });
+        commands.create_application_command(|c| {
+            c.name("Report message").kind(CommandType::Message)
+        });
+        commands.create_application_command(|c| {
+            c.name("purge")
+                .description("Delete recent messages in this channel")
+                .kind(CommandType::ChatInput)
+                .default_member_permissions(Permissions::MANAGE_MESSAGES)
+                .create_option(|opt| {
+                    opt.kind(CommandOptionType::Integer)
+                        .name("count")
+                        .description("How many matching messages to delete")
+                        .min_int_value(1)
+                        .max_int_value(1000)
+                        .required(true)
+                })
+                .create_option(|opt| {
+                    opt.kind(CommandOptionType::User)
+                        .name("user")
+                        .description("Only messages from this user")
+                        .required(false)
+                })
+                .create_option(|opt| {
+                    opt.kind(CommandOptionType::Boolean)
+                        .name("bots")
+                        .description("Only messages from bots")
+                        .required(false)
+                })
+                .create_option(|opt| {
+                    opt.kind(CommandOptionType::String)
+                        .name("contains")
+                        .description("Only messages containing this text")
+                        .required(false)
+                })
+                .create_option(|opt| {
+                    opt.kind(CommandOptionType::String)
+                        .name("regex")
+                        .description("Only messages matching this regex")
+                        .required(false)
+                })
+                .create_option(|opt| {
+                    opt.kind(CommandOptionType::Boolean)
+                        .name("attachments")
+                        .description("Only messages with attachments")
+                        .required(false)
+                })
+                .create_option(|opt| {
+                    opt.kind(CommandOptionType::Boolean)
+                        .name("links")
+                        .description("Only messages with links")
+                        .required(false)
+                })
+                .create_option(|opt| {
+                    opt.kind(CommandOptionType::String)
+                        .name("before")
+                        .description("Only messages before this message id or link")
+                        .required(false)
+                })
+                .create_option(|opt| {
+                    opt.kind(CommandOptionType::String)
+                        .name("after")
+                        .description("Only messages after this message id or link")
+                        .required(false)
+                })
+        });
+        commands.create_application_command(|c| {
+            c.name("automod")
+                .description("Discord's AutoMod rules, kept in a YAML policy")
+                .kind(CommandType::ChatInput)
+                .default_member_permissions(Permissions::MANAGE_GUILD)
+                .create_option(|opt| {
+                    opt.kind(CommandOptionType::SubCommand)
+                        .name("list")
+                        .description("List the rules and download the policy")
+                })
+                .create_option(|opt| {
+                    opt.kind(CommandOptionType::SubCommand)
+                        .name("diff")
+                        .description("Show what applying the policy would change")
+                        .create_sub_option(|sub| {
+                            sub.kind(CommandOptionType::Attachment)
+                                .name("policy")
+                                .description("YAML file to compare instead of the saved policy")
+                                .required(false)
+                        })
+                })
+                .create_option(|opt| {
+                    opt.kind(CommandOptionType::SubCommand)
+                        .name("apply")
+                        .description("Create, update and delete rules to match the policy")
+                        .create_sub_option(|sub| {
+                            sub.kind(CommandOptionType::Attachment)
+                                .name("policy")
+                                .description("YAML file to save and apply, start from `/automod list`")
+                                .required(false)
+                        })
+                })
+        });
+        commands.create_application_command(|c| {
+            c.name("scam-domains")
+                .description("Domains whose links get a member quarantined")
+                .kind(CommandType::ChatInput)
+                .default_member_permissions(Permissions::MANAGE_GUILD)
+                .create_option(|opt| {
+                    opt.kind(CommandOptionType::SubCommand)
+                        .name("list")
+                        .description("List the blocked domains")
+                })
+                .create_option(|opt| {
+                    opt.kind(CommandOptionType::SubCommand)
+                        .name("add")
+                        .description("Block a domain and its subdomains")
+                        .create_sub_option(|sub| {
+                            sub.kind(CommandOptionType::String)
+                                .name("domain")
+                                .description("Domain or link, e.g. `dlscord-nitro.com`")
+                                .required(true)
+                        })
+                })
+                .create_option(|opt| {
+                    opt.kind(CommandOptionType::SubCommand)
+                        .name("remove")
+                        .description("Unblock a domain")
+                        .create_sub_option(|sub| {
+                            sub.kind(CommandOptionType::String)
+                                .name("domain")
+                                .description("The blocked domain")
+                                .required(true)
+                        })
+                })
+                .create_option(|opt| {
+                    opt.kind(CommandOptionType::SubCommand)
+                        .name("check")
+                        .description("Whether a link would be caught, and why")
+                        .create_sub_option(|sub| {
+                            sub.kind(CommandOptionType::String)
+                                .name("domain")
+                                .description("Domain or link to check")
+                                .required(true)
+                        })
+                })
+        });
+        commands.create_application_command(|c| {
+            c.name("survey")
+                .description("Answers to the onboarding survey questions")
+                .kind(CommandType::ChatInput)
+                .default_member_permissions(Permissions::ADMINISTRATOR)
+                .create_option(|opt| {
+                    opt.kind(CommandOptionType::SubCommand)
+                        .name("results")
+                        .description("Count the answers given in a date range")
+                        .create_sub_option(|sub| {
+                            sub.kind(CommandOptionType::String)
+                                .name("question")
+                                .description("Id of the onboarding step, e.g. `found`")
+                                .required(false)
+                        })
+                        .create_sub_option(|sub| {
+                            sub.kind(CommandOptionType::String)
+                                .name("from")
+                                .description("First day, as YYYY-MM-DD")
+                                .required(false)
+                        })
+                        .create_sub_option(|sub| {
+                            sub.kind(CommandOptionType::String)
+                                .name("to")
+                                .description("Last day, as YYYY-MM-DD")
+                                .required(false)
+                        })
+                        .create_sub_option(|sub| {
+                            sub.kind(CommandOptionType::Boolean)
+                                .name("csv")
+                                .description("Attach the raw answers as a CSV file")
+                                .required(false)
+                        })
+                })
+                .create_option(|opt| {
+                    opt.kind(CommandOptionType::SubCommand)
+                        .name("migrate-roles")
+                        .description("Move the answers kept as \"Found: …\" roles into the survey, then delete the roles")
+                })
         });
         commands.create_application_command(|c| {
             c.name("nothing_to_see_here")
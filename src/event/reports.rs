@@ -0,0 +1,706 @@
+use super::*;
+use crate::db::Db;
+use crate::utils::misc::parse_duration;
+use moderation::{CaseAction, MAX_TIMEOUT_SECS};
+use serenity::builder::{CreateComponents, CreateEmbed};
+use serenity::model::{
+    application::interaction::{
+        application_command::ApplicationCommandInteraction,
+        message_component::MessageComponentInteraction, modal::ModalSubmitInteraction,
+        MessageFlags,
+    },
+    id::UserId,
+    Permissions, Timestamp,
+};
+use substr::StringUtils;
+
+// Attachments are copied into the queue up to this size, the originals go with the message
+const MAX_SNAPSHOT_BYTES: u64 = 8 * 1024 * 1024;
+
+pub struct Report {
+    pub id: i64,
+    pub guild_id: i64,
+    pub reporter_id: i64,
+    pub reporter_locale: String,
+    pub author_id: i64,
+    pub channel_id: i64,
+    pub message_id: i64,
+    pub content: String,
+    pub attachments: String,
+    pub reason: String,
+    pub status: String,
+    pub claimed_by: Option<i64>,
+    pub handled_by: Option<i64>,
+    pub handled_at: Option<i64>,
+    pub case_number: Option<i64>,
+    pub queue_channel_id: Option<i64>,
+    pub queue_message_id: Option<i64>,
+    pub created_at: i64,
+}
+
+impl Report {
+    fn link(&self) -> String {
+        format!(
+            "https://discord.com/channels/{}/{}/{}",
+            self.guild_id, self.channel_id, self.message_id
+        )
+    }
+
+    /// Dismissed or acted on, nothing left to do.
+    fn is_closed(&self) -> bool {
+        matches!(self.status.as_str(), "dismissed" | "actioned")
+    }
+}
+
+impl Db {
+    pub async fn add_report(
+        &self,
+        guild_id: GuildId,
+        reporter_id: UserId,
+        reporter_locale: &str,
+        message: &Message,
+        attachments: &str,
+        reason: &str,
+    ) -> Result<i64> {
+        let guild_id = guild_id.0 as i64;
+        let reporter_id = reporter_id.0 as i64;
+        let author_id = message.author.id.0 as i64;
+        let channel_id = message.channel_id.0 as i64;
+        let message_id = message.id.0 as i64;
+        let created_at = Timestamp::now().unix_timestamp();
+        let q = sqlx::query!(
+            "insert into message_reports (guild_id, reporter_id, reporter_locale, author_id, channel_id, message_id, content, attachments, reason, created_at) values (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+            guild_id,
+            reporter_id,
+            reporter_locale,
+            author_id,
+            channel_id,
+            message_id,
+            message.content,
+            attachments,
+            reason,
+            created_at
+        )
+        .execute(&self.sqlitedb)
+        .await?;
+        Ok(q.last_insert_rowid())
+    }
+
+    pub async fn get_report(&self, id: i64) -> Result<Option<Report>> {
+        let q = sqlx::query_as!(
+            Report,
+            r#"select id as "id!", guild_id, reporter_id, reporter_locale, author_id, channel_id, message_id, content, attachments, reason, status, claimed_by, handled_by, handled_at, case_number, queue_channel_id, queue_message_id, created_at from message_reports where id=?"#,
+            id
+        )
+        .fetch_optional(&self.sqlitedb)
+        .await?;
+        Ok(q)
+    }
+
+    pub async fn has_report(
+        &self,
+        guild_id: GuildId,
+        reporter_id: UserId,
+        message_id: MessageId,
+    ) -> Result<bool> {
+        let guild_id = guild_id.0 as i64;
+        let reporter_id = reporter_id.0 as i64;
+        let message_id = message_id.0 as i64;
+        let q = sqlx::query!(
+            "select id from message_reports where guild_id=? and reporter_id=? and message_id=?",
+            guild_id,
+            reporter_id,
+            message_id
+        )
+        .fetch_optional(&self.sqlitedb)
+        .await?;
+        Ok(q.is_some())
+    }
+
+    pub async fn count_open_reports(
+        &self,
+        guild_id: GuildId,
+        message_id: MessageId,
+    ) -> Result<i64> {
+        let guild_id = guild_id.0 as i64;
+        let message_id = message_id.0 as i64;
+        let q = sqlx::query!(
+            r#"select count(*) as "count!: i64" from message_reports where guild_id=? and message_id=? and status in ('open', 'claimed')"#,
+            guild_id,
+            message_id
+        )
+        .fetch_one(&self.sqlitedb)
+        .await?;
+        Ok(q.count)
+    }
+
+    pub async fn set_report_queue_message(
+        &self,
+        id: i64,
+        channel_id: ChannelId,
+        message_id: MessageId,
+    ) -> Result<()> {
+        let channel_id = channel_id.0 as i64;
+        let message_id = message_id.0 as i64;
+        sqlx::query!(
+            "update message_reports set queue_channel_id=?, queue_message_id=? where id=?",
+            channel_id,
+            message_id,
+            id
+        )
+        .execute(&self.sqlitedb)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn update_report(&self, report: &Report) -> Result<()> {
+        sqlx::query!(
+            "update message_reports set status=?, claimed_by=?, handled_by=?, handled_at=?, case_number=? where id=?",
+            report.status,
+            report.claimed_by,
+            report.handled_by,
+            report.handled_at,
+            report.case_number,
+            report.id
+        )
+        .execute(&self.sqlitedb)
+        .await?;
+        Ok(())
+    }
+}
+
+fn render_embed<'a>(e: &'a mut CreateEmbed, report: &Report) -> &'a mut CreateEmbed {
+    let (colour, status) = match report.status.as_str() {
+        "claimed" => (
+            0xE67E22,
+            format!("Claimed by <@{}>", report.claimed_by.unwrap_or_default()),
+        ),
+        "deleted" => (
+            0x2ECC71,
+            format!(
+                "Message deleted by <@{}>",
+                report.handled_by.unwrap_or_default()
+            ),
+        ),
+        "actioned" => (
+            0x2ECC71,
+            format!(
+                "Case #{} opened by <@{}>",
+                report.case_number.unwrap_or_default(),
+                report.handled_by.unwrap_or_default()
+            ),
+        ),
+        "dismissed" => (
+            0x95A5A6,
+            format!("Dismissed by <@{}>", report.handled_by.unwrap_or_default()),
+        ),
+        _ => (0xE74C3C, "Open".to_string()),
+    };
+    e.title(format!("Report #{}", report.id))
+        .description(if report.content.is_empty() {
+            "*No text*".to_string()
+        } else {
+            report.content.as_str().substring(0, 4000).to_string()
+        })
+        .field(
+            "Author",
+            format!("<@{}> ({})", report.author_id, report.author_id),
+            true,
+        )
+        .field(
+            "Message",
+            format!("<#{}> [jump]({})", report.channel_id, report.link()),
+            true,
+        )
+        .field("Reported by", format!("<@{}>", report.reporter_id), true)
+        .field("Reason", report.reason.as_str().substring(0, 1024), false);
+    if !report.attachments.is_empty() {
+        e.field(
+            "Attachments",
+            report.attachments.as_str().substring(0, 1024),
+            false,
+        );
+    }
+    e.field("Status", status, false).colour(colour);
+    if let Ok(created_at) = Timestamp::from_unix_timestamp(report.created_at) {
+        e.timestamp(created_at);
+    }
+    e
+}
+
+fn render_buttons<'a>(c: &'a mut CreateComponents, report: &Report) -> &'a mut CreateComponents {
+    if report.is_closed() {
+        return c;
+    }
+    c.create_action_row(|a| {
+        if report.status != "deleted" {
+            a.create_button(|b| {
+                b.custom_id(format!("report:claim:{}", report.id))
+                    .label("Claim")
+                    .style(ButtonStyle::Primary)
+                    .disabled(report.status == "claimed")
+            })
+            .create_button(|b| {
+                b.custom_id(format!("report:dismiss:{}", report.id))
+                    .label("Dismiss")
+                    .style(ButtonStyle::Secondary)
+            })
+            .create_button(|b| {
+                b.custom_id(format!("report:delete:{}", report.id))
+                    .label("Delete message")
+                    .style(ButtonStyle::Danger)
+            });
+        }
+        a.create_button(|b| {
+            b.custom_id(format!("report:case:{}", report.id))
+                .label("Open case")
+                .style(ButtonStyle::Danger)
+        })
+    })
+}
+
+/// Lets the reporter know what came of their report.
+async fn notify_reporter(ctx: &Context, report: &Report, key: &str) {
+    let guild_id = GuildId(report.guild_id as u64);
+    let locale = i18n::user_locale(ctx, Some(guild_id), &report.reporter_locale).await;
+    let guild_name = guild_id
+        .name(&ctx.cache)
+        .unwrap_or_else(|| guild_id.to_string());
+    let content = tr_args(locale, key, &[("guild", guild_name)]);
+    if let Ok(channel) = UserId(report.reporter_id as u64)
+        .create_dm_channel(&ctx.http)
+        .await
+    {
+        channel.say(&ctx.http, content).await.ok();
+    }
+}
+
+/// Re-renders the queue post after a change made outside of its buttons.
+async fn refresh_queue_message(ctx: &Context, report: &Report) {
+    if let (Some(channel_id), Some(message_id)) = (report.queue_channel_id, report.queue_message_id)
+    {
+        ChannelId(channel_id as u64)
+            .edit_message(&ctx.http, message_id as u64, |m| {
+                m.embed(|e| render_embed(e, report))
+                    .components(|c| render_buttons(c, report))
+            })
+            .await
+            .ok();
+    }
+}
+
+async fn reply_ephemeral(mci: &ModalSubmitInteraction, ctx: &Context, content: String) {
+    mci.edit_original_interaction_response(&ctx.http, |d| d.content(content))
+        .await
+        .unwrap();
+}
+
+/// The "Report message" context menu.
+pub async fn show_form(mci: &ApplicationCommandInteraction, ctx: &Context) {
+    let locale = i18n::user_locale(ctx, mci.guild_id, &mci.locale).await;
+    let message = match mci.data.resolved.messages.values().next() {
+        Some(message) => message,
+        None => return,
+    };
+    if message.author.id == mci.user.id {
+        mci.create_interaction_response(&ctx.http, |r| {
+            r.kind(InteractionResponseType::ChannelMessageWithSource)
+                .interaction_response_data(|d| {
+                    d.content(tr(locale, "report-own-message"))
+                        .flags(MessageFlags::EPHEMERAL)
+                })
+        })
+        .await
+        .unwrap();
+        return;
+    }
+    mci.create_interaction_response(&ctx.http, |r| {
+        r.kind(InteractionResponseType::Modal)
+            .interaction_response_data(|d| {
+                d.custom_id(format!("report_form:{}:{}", message.channel_id, message.id))
+                    .title(tr(locale, "report-form-title"))
+                    .components(|c| {
+                        c.create_action_row(|ar| {
+                            ar.create_input_text(|it| {
+                                it.style(InputTextStyle::Paragraph)
+                                    .custom_id("reason")
+                                    .label(tr(locale, "report-form-reason-label"))
+                                    .required(true)
+                                    .max_length(1000)
+                            })
+                        })
+                    })
+            })
+    })
+    .await
+    .unwrap();
+}
+
+fn input_values(mci: &ModalSubmitInteraction) -> Vec<String> {
+    mci.data
+        .components
+        .iter()
+        .flat_map(|x| &x.components)
+        .filter_map(|x| match x {
+            ActionRowComponent::InputText(it) => Some(it.value.trim().to_string()),
+            _ => None,
+        })
+        .collect()
+}
+
+/// The reason modal, `report_form:{channel_id}:{message_id}`.
+pub async fn handle_form(mci: &ModalSubmitInteraction, ctx: &Context) {
+    let guild_id = mci.guild_id.unwrap();
+    let locale = i18n::user_locale(ctx, mci.guild_id, &mci.locale).await;
+    let ids: Vec<u64> = mci
+        .data
+        .custom_id
+        .split(':')
+        .skip(1)
+        .filter_map(|x| x.parse().ok())
+        .collect();
+    let (channel_id, message_id) = match ids[..] {
+        [channel_id, message_id] => (ChannelId(channel_id), MessageId(message_id)),
+        _ => return,
+    };
+    let reason = input_values(mci).into_iter().next().unwrap_or_default();
+    // Copying the attachments can take a while
+    mci.create_interaction_response(&ctx.http, |r| {
+        r.kind(InteractionResponseType::DeferredChannelMessageWithSource)
+            .interaction_response_data(|d| d.flags(MessageFlags::EPHEMERAL))
+    })
+    .await
+    .unwrap();
+
+    let db = ctx.get_db().await;
+    if db
+        .has_report(guild_id, mci.user.id, message_id)
+        .await
+        .unwrap()
+    {
+        reply_ephemeral(mci, ctx, tr(locale, "report-already")).await;
+        return;
+    }
+    let queue = match db
+        .get_guild_config_as::<ChannelId>(guild_id, "report_channel")
+        .await
+        .unwrap()
+    {
+        Some(channel_id) => Some(channel_id),
+        None => moderation::mod_log_channel(ctx, guild_id).await.unwrap(),
+    };
+    let queue = match queue {
+        Some(queue) => queue,
+        None => {
+            reply_ephemeral(mci, ctx, tr(locale, "report-unavailable")).await;
+            return;
+        }
+    };
+    let message = match channel_id.message(&ctx.http, message_id).await {
+        Ok(message) => message,
+        Err(_) => {
+            reply_ephemeral(mci, ctx, tr(locale, "report-message-gone")).await;
+            return;
+        }
+    };
+
+    let mut files = Vec::new();
+    let mut size = 0;
+    for attachment in &message.attachments {
+        size += attachment.size;
+        if size > MAX_SNAPSHOT_BYTES {
+            break;
+        }
+        if let Ok(data) = attachment.download().await {
+            files.push((data, attachment.filename.clone()));
+        }
+    }
+    let attachments = message
+        .attachments
+        .iter()
+        .map(|x| x.filename.clone())
+        .collect::<Vec<_>>()
+        .join("\n");
+    let id = db
+        .add_report(
+            guild_id,
+            mci.user.id,
+            &mci.locale,
+            &message,
+            &attachments,
+            &reason,
+        )
+        .await
+        .unwrap();
+    let report = db.get_report(id).await.unwrap().unwrap();
+    let others = db.count_open_reports(guild_id, message_id).await.unwrap() - 1;
+
+    let mut content = String::new();
+    if let Some(role_id) = support_stats::get_staff_role(ctx, guild_id).await {
+        content.push_str(&role_id.mention().to_string());
+    }
+    if others > 0 {
+        content.push_str(&format!(" {} other open reports on this message", others));
+    }
+    let posted = queue
+        .send_message(&ctx.http, |m| {
+            m.embed(|e| render_embed(e, &report))
+                .components(|c| render_buttons(c, &report));
+            if !content.is_empty() {
+                m.content(content.trim());
+            }
+            for (data, filename) in &files {
+                m.add_file((data.as_slice(), filename.as_str()));
+            }
+            m
+        })
+        .await;
+    match posted {
+        Ok(posted) => {
+            db.set_report_queue_message(id, queue, posted.id)
+                .await
+                .unwrap();
+            reply_ephemeral(mci, ctx, tr(locale, "report-sent")).await;
+        }
+        Err(e) => {
+            eprintln!("Failed to post report #{} to {}: {}", id, queue, e);
+            reply_ephemeral(mci, ctx, tr(locale, "report-unavailable")).await;
+        }
+    }
+}
+
+async fn respond_ephemeral(mci: &MessageComponentInteraction, ctx: &Context, content: &str) {
+    mci.create_interaction_response(&ctx.http, |r| {
+        r.kind(InteractionResponseType::ChannelMessageWithSource)
+            .interaction_response_data(|d| d.content(content).flags(MessageFlags::EPHEMERAL))
+    })
+    .await
+    .unwrap();
+}
+
+/// The queue buttons, `report:{claim|dismiss|delete|case}:{id}`.
+pub async fn handle_button(mci: &MessageComponentInteraction, ctx: &Context) {
+    let mut parts = mci.data.custom_id.split(':').skip(1);
+    let (action, id) = match (
+        parts.next(),
+        parts.next().and_then(|x| x.parse::<i64>().ok()),
+    ) {
+        (Some(action), Some(id)) => (action, id),
+        _ => return,
+    };
+    let permissions = mci
+        .member
+        .as_ref()
+        .and_then(|x| x.permissions)
+        .unwrap_or_else(Permissions::empty);
+    let allowed = match action {
+        "delete" => permissions.manage_messages(),
+        _ => permissions.moderate_members() || permissions.kick_members(),
+    };
+    if !allowed {
+        respond_ephemeral(mci, ctx, "You don't have the permission to do that").await;
+        return;
+    }
+    let db = ctx.get_db().await;
+    let mut report = match db.get_report(id).await.unwrap() {
+        Some(report) => report,
+        None => return,
+    };
+    if report.is_closed() {
+        respond_ephemeral(mci, ctx, "This report is closed already").await;
+        return;
+    }
+
+    let now = Timestamp::now().unix_timestamp();
+    let moderator = mci.user.id.0 as i64;
+    match action {
+        "claim" => {
+            report.status = "claimed".to_string();
+            report.claimed_by = Some(moderator);
+        }
+        "dismiss" => {
+            report.status = "dismissed".to_string();
+            report.handled_by = Some(moderator);
+            report.handled_at = Some(now);
+            notify_reporter(ctx, &report, "report-dismissed").await;
+        }
+        "delete" => {
+            // Gone already is as good as deleted
+            ChannelId(report.channel_id as u64)
+                .delete_message(&ctx.http, report.message_id as u64)
+                .await
+                .ok();
+            report.status = "deleted".to_string();
+            report.handled_by = Some(moderator);
+            report.handled_at = Some(now);
+            notify_reporter(ctx, &report, "report-resolved").await;
+        }
+        "case" => {
+            mci.create_interaction_response(&ctx.http, |r| {
+                r.kind(InteractionResponseType::Modal)
+                    .interaction_response_data(|d| {
+                        d.custom_id(format!("report_case:{}", report.id))
+                            .title(format!("Case for report #{}", report.id))
+                            .components(|c| {
+                                c.create_action_row(|ar| {
+                                    ar.create_input_text(|it| {
+                                        it.style(InputTextStyle::Short)
+                                            .custom_id("action")
+                                            .label("Action: warn, timeout, kick or ban")
+                                            .value("warn")
+                                            .required(true)
+                                            .max_length(10)
+                                    })
+                                })
+                                .create_action_row(|ar| {
+                                    ar.create_input_text(|it| {
+                                        it.style(InputTextStyle::Short)
+                                            .custom_id("duration")
+                                            .label("Duration, for timeouts and temporary bans")
+                                            .placeholder("e.g. 30m, 12h or 7d")
+                                            .required(false)
+                                            .max_length(10)
+                                    })
+                                })
+                                .create_action_row(|ar| {
+                                    ar.create_input_text(|it| {
+                                        it.style(InputTextStyle::Paragraph)
+                                            .custom_id("reason")
+                                            .label("Reason")
+                                            .value(report.reason.as_str().substring(0, 500))
+                                            .required(true)
+                                            .max_length(500)
+                                    })
+                                })
+                            })
+                    })
+            })
+            .await
+            .unwrap();
+            return;
+        }
+        _ => return,
+    }
+    db.update_report(&report).await.unwrap();
+    mci.create_interaction_response(&ctx.http, |r| {
+        r.kind(InteractionResponseType::UpdateMessage)
+            .interaction_response_data(|d| {
+                d.embed(|e| render_embed(e, &report))
+                    .components(|c| render_buttons(c, &report))
+            })
+    })
+    .await
+    .unwrap();
+}
+
+/// The Open case modal, `report_case:{id}`.
+pub async fn handle_case_form(mci: &ModalSubmitInteraction, ctx: &Context) {
+    let guild_id = mci.guild_id.unwrap();
+    let id = match mci
+        .data
+        .custom_id
+        .trim_start_matches("report_case:")
+        .parse()
+    {
+        Ok(id) => id,
+        Err(_) => return,
+    };
+    mci.create_interaction_response(&ctx.http, |r| {
+        r.kind(InteractionResponseType::DeferredChannelMessageWithSource)
+            .interaction_response_data(|d| d.flags(MessageFlags::EPHEMERAL))
+    })
+    .await
+    .unwrap();
+    let db = ctx.get_db().await;
+    let mut report = match db.get_report(id).await.unwrap() {
+        Some(report) if !report.is_closed() => report,
+        _ => {
+            reply_ephemeral(mci, ctx, "This report is closed already".to_string()).await;
+            return;
+        }
+    };
+
+    let values = input_values(mci);
+    let (action, duration, reason) = match &values[..] {
+        [action, duration, reason] => (action.to_lowercase(), duration, reason),
+        _ => return,
+    };
+    let action = match CaseAction::parse(&action) {
+        Some(action) if action != CaseAction::Unban => action,
+        _ => {
+            reply_ephemeral(mci, ctx, format!("Unknown action `{}`", action)).await;
+            return;
+        }
+    };
+    let permissions = mci
+        .member
+        .as_ref()
+        .and_then(|x| x.permissions)
+        .unwrap_or_else(Permissions::empty);
+    let allowed = match action {
+        CaseAction::Warn | CaseAction::Timeout => permissions.moderate_members(),
+        CaseAction::Kick => permissions.kick_members(),
+        CaseAction::Ban | CaseAction::Unban => permissions.ban_members(),
+    };
+    if !allowed {
+        reply_ephemeral(
+            mci,
+            ctx,
+            format!("You don't have the permission to {}", action.name()),
+        )
+        .await;
+        return;
+    }
+    let duration = match (action, duration.is_empty()) {
+        (CaseAction::Timeout | CaseAction::Ban, false) => match parse_duration(duration) {
+            Some(secs) if action != CaseAction::Timeout || secs <= MAX_TIMEOUT_SECS => Some(secs),
+            _ => {
+                reply_ephemeral(mci, ctx, format!("Invalid duration `{}`", duration)).await;
+                return;
+            }
+        },
+        (CaseAction::Timeout, true) => {
+            reply_ephemeral(mci, ctx, "A timeout needs a duration".to_string()).await;
+            return;
+        }
+        _ => None,
+    };
+
+    let mut evidence = report.link();
+    if let (Some(channel_id), Some(message_id)) = (report.queue_channel_id, report.queue_message_id)
+    {
+        evidence.push_str(&format!(
+            "\nhttps://discord.com/channels/{}/{}/{}",
+            report.guild_id, channel_id, message_id
+        ));
+    }
+    let text = match moderation::open_case(
+        ctx,
+        guild_id,
+        UserId(report.author_id as u64),
+        mci.user.id,
+        action,
+        reason,
+        duration,
+        &evidence,
+        0,
+    )
+    .await
+    {
+        Ok((case, _)) => {
+            // Reporters heard back already when the message was deleted
+            if report.status != "deleted" {
+                notify_reporter(ctx, &report, "report-resolved").await;
+            }
+            report.status = "actioned".to_string();
+            report.handled_by = Some(mci.user.id.0 as i64);
+            report.handled_at = Some(Timestamp::now().unix_timestamp());
+            report.case_number = Some(case.case_number);
+            db.update_report(&report).await.unwrap();
+            refresh_queue_message(ctx, &report).await;
+            format!("Opened case #{}", case.case_number)
+        }
+        Err(e) => format!("Failed to {}: {}", action.name(), e),
+    };
+    reply_ephemeral(mci, ctx, text).await;
+}
@@ -0,0 +1,276 @@
+use super::*;
+use crate::db::ClientContextExt;
+use onboarding::{Step, Wizard};
+use serenity::builder::CreateComponents;
+use serenity::model::{
+    application::interaction::{
+        application_command::{ApplicationCommandInteraction, CommandDataOptionValue},
+        message_component::MessageComponentInteraction,
+        MessageFlags,
+    },
+    id::RoleId,
+};
+use serenity::utils::MessageBuilder;
+use substr::StringUtils;
+
+// One select menu per action row
+const MAX_MENUS: usize = 5;
+
+/// Steps of the wizard that hand out roles, with only the options that do.
+fn role_steps(wizard: &Wizard) -> Vec<(&Step, Vec<&onboarding::StepOption>)> {
+    wizard
+        .steps
+        .iter()
+        .map(|step| {
+            (
+                step,
+                step.options
+                    .iter()
+                    .filter(|x| !x.roles.is_empty())
+                    .collect::<Vec<_>>(),
+            )
+        })
+        .filter(|(_, options)| !options.is_empty())
+        .take(MAX_MENUS)
+        .collect()
+}
+
+fn menu_components(wizard: &Wizard, locale: &str) -> CreateComponents {
+    let mut components = CreateComponents::default();
+    for (step, options) in role_steps(wizard) {
+        components.create_action_row(|a| {
+            a.create_select_menu(|s| {
+                s.custom_id(format!("role_menu:{}", step.id))
+                    .placeholder(tr(locale, &step.prompt).substring(0, 150))
+                    .min_values(0)
+                    .max_values(options.len() as u64)
+                    .options(|o| {
+                        for option in &options {
+                            o.create_option(|opt| {
+                                opt.label(tr(locale, &option.label).substring(0, 100))
+                                    .value(&option.value);
+                                if let Some(description) = &option.description {
+                                    opt.description(tr(locale, description).substring(0, 100));
+                                }
+                                if let Some(emoji) = option.reaction() {
+                                    opt.emoji(emoji);
+                                }
+                                opt
+                            });
+                        }
+                        o
+                    })
+            })
+        });
+    }
+    components
+}
+
+/// The opt-in roles the member has right now, as listed for them.
+fn current_roles(ctx: &Context, guild_id: GuildId, wizard: &Wizard, roles: &[RoleId]) -> String {
+    let opt_in = wizard.all_roles();
+    let mentions: Vec<String> = guild_id
+        .to_guild_cached(&ctx.cache)
+        .map(|guild| {
+            roles
+                .iter()
+                .filter_map(|x| guild.roles.get(x))
+                .filter(|x| opt_in.contains(x.name.as_str()))
+                .map(|x| x.mention().to_string())
+                .collect()
+        })
+        .unwrap_or_default();
+    if mentions.is_empty() {
+        "-".to_string()
+    } else {
+        mentions.join(", ")
+    }
+}
+
+/// `/roles`, shows the menu to the member only.
+pub async fn responder(mci: &ApplicationCommandInteraction, ctx: &Context) {
+    let guild_id = mci.guild_id.unwrap();
+    let locale = i18n::user_locale(ctx, mci.guild_id, &mci.locale).await;
+    let wizard = onboarding::get_wizard(ctx, guild_id).await;
+    let roles = mci
+        .member
+        .as_ref()
+        .map(|x| x.roles.clone())
+        .unwrap_or_default();
+    let content = MessageBuilder::new()
+        .push_line(tr(locale, "roles-menu-heading"))
+        .push_line(tr_args(
+            locale,
+            "roles-current",
+            &[("roles", current_roles(ctx, guild_id, &wizard, &roles))],
+        ))
+        .build();
+
+    mci.create_interaction_response(&ctx.http, |r| {
+        r.kind(InteractionResponseType::ChannelMessageWithSource)
+            .interaction_response_data(|d| {
+                d.content(content)
+                    .set_components(menu_components(&wizard, locale))
+                    .flags(MessageFlags::EPHEMERAL)
+            })
+    })
+    .await
+    .unwrap();
+}
+
+/// `/role-menu`, posts a menu anyone can use. It keeps working across restarts and
+/// follows changes to the onboarding wizard, as long as the step ids stay the same.
+pub async fn post_menu(mci: &ApplicationCommandInteraction, ctx: &Context) {
+    let guild_id = mci.guild_id.unwrap();
+    let mut channel_id = mci.channel_id;
+    for option in &mci.data.options {
+        if let ("channel", Some(CommandDataOptionValue::Channel(c))) =
+            (option.name.as_str(), &option.resolved)
+        {
+            channel_id = c.id;
+        }
+    }
+    // The menu is public, use the guild's locale
+    let locale = i18n::guild_locale(ctx, mci.guild_id).await;
+    let wizard = onboarding::get_wizard(ctx, guild_id).await;
+
+    let response = if role_steps(&wizard).is_empty() {
+        "None of the onboarding options give roles, there is nothing to put in a menu".to_string()
+    } else {
+        match channel_id
+            .send_message(&ctx.http, |m| {
+                m.content(tr(locale, "roles-menu-heading"))
+                    .set_components(menu_components(&wizard, locale))
+            })
+            .await
+        {
+            Ok(_) => format!("Posted the role menu in {}", channel_id.mention()),
+            Err(e) => format!("Failed to post in {}: {}", channel_id.mention(), e),
+        }
+    };
+
+    mci.create_interaction_response(&ctx.http, |r| {
+        r.kind(InteractionResponseType::ChannelMessageWithSource)
+            .interaction_response_data(|d| d.content(response).flags(MessageFlags::EPHEMERAL))
+    })
+    .await
+    .unwrap();
+}
+
+/// A select of either menu, picking an option toggles its roles.
+pub async fn toggle(mci: &MessageComponentInteraction, ctx: &Context) {
+    let guild_id = mci.guild_id.unwrap();
+    let locale = i18n::user_locale(ctx, mci.guild_id, &mci.locale).await;
+    let wizard = onboarding::get_wizard(ctx, guild_id).await;
+    let step_id = mci.data.custom_id.trim_start_matches("role_menu:");
+    let step = match wizard.steps.iter().find(|x| x.id == step_id) {
+        Some(step) => step,
+        None => return,
+    };
+    let guild = match guild_id.to_guild_cached(&ctx.cache) {
+        Some(guild) => guild,
+        None => return,
+    };
+    let mut member = mci.member.clone().unwrap();
+
+    let mut toggled: Vec<RoleId> = Vec::new();
+    for option in step
+        .options
+        .iter()
+        .filter(|x| mci.data.values.contains(&x.value))
+    {
+        for name in &option.roles {
+            if let Some(role) = guild.role_by_name(name) {
+                if !toggled.contains(&role.id) {
+                    toggled.push(role.id);
+                }
+            }
+        }
+    }
+    let (removed, added): (Vec<RoleId>, Vec<RoleId>) =
+        toggled.into_iter().partition(|x| member.roles.contains(x));
+    if !added.is_empty() {
+        member.add_roles(&ctx.http, &added).await.unwrap();
+    }
+    if !removed.is_empty() {
+        member.remove_roles(&ctx.http, &removed).await.unwrap();
+    }
+
+    // Keep the saved roles in sync, they are restored when the member rejoins
+    let opt_in = wizard.all_roles();
+    let saved: Vec<RoleId> = member
+        .roles
+        .iter()
+        .filter(|x| {
+            guild
+                .roles
+                .get(x)
+                .is_some_and(|r| opt_in.contains(r.name.as_str()))
+        })
+        .copied()
+        .collect();
+    let db = ctx.get_db().await;
+    db.set_user_roles(member.user.id, saved).await.unwrap();
+
+    let mentions = |roles: &[RoleId]| {
+        roles
+            .iter()
+            .map(|x| x.mention().to_string())
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+    let mut content = MessageBuilder::new();
+    content.push_line(tr(locale, "roles-menu-heading"));
+    if !added.is_empty() {
+        content.push_line(tr_args(
+            locale,
+            "roles-added",
+            &[("roles", mentions(&added))],
+        ));
+    }
+    if !removed.is_empty() {
+        content.push_line(tr_args(
+            locale,
+            "roles-removed",
+            &[("roles", mentions(&removed))],
+        ));
+    }
+    content.push_line(tr_args(
+        locale,
+        "roles-current",
+        &[(
+            "roles",
+            current_roles(ctx, guild_id, &wizard, &member.roles),
+        )],
+    ));
+    let content = content.build();
+
+    if mci.message.interaction.is_some() {
+        // Our own `/roles` menu, refresh it in place
+        mci.create_interaction_response(&ctx.http, |r| {
+            r.kind(InteractionResponseType::UpdateMessage)
+                .interaction_response_data(|d| {
+                    d.content(content)
+                        .set_components(menu_components(&wizard, locale))
+                })
+        })
+        .await
+        .unwrap();
+    } else {
+        // The shared menu, rebuilding it clears the selection so the same option can be picked again
+        let guild_locale = i18n::guild_locale(ctx, mci.guild_id).await;
+        mci.create_interaction_response(&ctx.http, |r| {
+            r.kind(InteractionResponseType::UpdateMessage)
+                .interaction_response_data(|d| {
+                    d.set_components(menu_components(&wizard, guild_locale))
+                })
+        })
+        .await
+        .unwrap();
+        mci.create_followup_message(&ctx.http, |d| {
+            d.content(content).flags(MessageFlags::EPHEMERAL)
+        })
+        .await
+        .unwrap();
+    }
+}
@@ -0,0 +1,565 @@
+use super::*;
+use crate::db::Db;
+use serenity::model::{
+    application::interaction::{
+        application_command::{ApplicationCommandInteraction, CommandDataOptionValue},
+        MessageFlags,
+    },
+    id::{RoleId, UserId},
+    Timestamp,
+};
+use serenity::utils::MessageBuilder;
+use std::{
+    collections::HashMap,
+    sync::{Mutex, OnceLock},
+};
+
+// Regex rules compiled so far, by pattern, so member events don't compile them again
+static COMPILED: OnceLock<Mutex<HashMap<String, Regex>>> = OnceLock::new();
+
+/// The compiled pattern of a regex rule, `None` when it's invalid.
+fn compile(pattern: &str) -> Option<Regex> {
+    let mut compiled = COMPILED.get_or_init(Default::default).lock().unwrap();
+    if let Some(re) = compiled.get(pattern) {
+        return Some(re.clone());
+    }
+    let re = Regex::new(&format!("(?i){}", pattern)).ok()?;
+    compiled.insert(pattern.to_string(), re.clone());
+    Some(re)
+}
+
+/// How a rule's pattern is compared to names.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum MatchKind {
+    /// Case-insensitive regex on the names as they are
+    Regex,
+    /// Substring of the names after folding lookalike characters, see `skeleton`
+    Confusable,
+}
+
+impl MatchKind {
+    fn parse(s: &str) -> Option<Self> {
+        match s {
+            "regex" => Some(MatchKind::Regex),
+            "confusable" => Some(MatchKind::Confusable),
+            _ => None,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            MatchKind::Regex => "regex",
+            MatchKind::Confusable => "confusable",
+        }
+    }
+}
+
+/// What happens to a member whose name matches, ordered from the mildest.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ScreenAction {
+    /// Only logged
+    Flag,
+    /// Given the `gp config quarantine_role` role
+    Quarantine,
+    Kick,
+    Ban,
+}
+
+impl ScreenAction {
+    fn parse(s: &str) -> Option<Self> {
+        match s {
+            "flag" => Some(ScreenAction::Flag),
+            "quarantine" => Some(ScreenAction::Quarantine),
+            "kick" => Some(ScreenAction::Kick),
+            "ban" => Some(ScreenAction::Ban),
+            _ => None,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            ScreenAction::Flag => "flag",
+            ScreenAction::Quarantine => "quarantine",
+            ScreenAction::Kick => "kick",
+            ScreenAction::Ban => "ban",
+        }
+    }
+}
+
+pub struct ScreeningRule {
+    pub id: i64,
+    pub kind: String,
+    pub pattern: String,
+    pub action: String,
+    pub reason: String,
+    /// The pattern of a regex rule, compiled when the rules are loaded.
+    regex: Option<Regex>,
+}
+
+impl ScreeningRule {
+    fn kind(&self) -> MatchKind {
+        MatchKind::parse(&self.kind).unwrap_or(MatchKind::Regex)
+    }
+
+    fn action(&self) -> ScreenAction {
+        ScreenAction::parse(&self.action).unwrap_or(ScreenAction::Flag)
+    }
+
+    /// The first of the names that matches.
+    fn matches<'a>(&self, names: &[&'a str]) -> Option<&'a str> {
+        match self.kind() {
+            MatchKind::Regex => {
+                let re = self.regex.as_ref()?;
+                names.iter().find(|x| re.is_match(x)).copied()
+            }
+            MatchKind::Confusable => {
+                let pattern = skeleton(&self.pattern);
+                if pattern.is_empty() {
+                    return None;
+                }
+                names
+                    .iter()
+                    .find(|x| skeleton(x).contains(&pattern))
+                    .copied()
+            }
+        }
+    }
+}
+
+impl Db {
+    pub async fn add_screening_rule(
+        &self,
+        guild_id: GuildId,
+        kind: MatchKind,
+        pattern: &str,
+        action: ScreenAction,
+        reason: &str,
+        created_by: UserId,
+    ) -> Result<i64> {
+        let guild_id = guild_id.0 as i64;
+        let kind = kind.name();
+        let action = action.name();
+        let created_by = created_by.0 as i64;
+        let created_at = Timestamp::now().unix_timestamp();
+        let id = sqlx::query!(
+            "insert into screening_rules (guild_id, kind, pattern, action, reason, created_by, created_at) values (?, ?, ?, ?, ?, ?, ?)",
+            guild_id,
+            kind,
+            pattern,
+            action,
+            reason,
+            created_by,
+            created_at
+        )
+        .execute(&self.sqlitedb)
+        .await?
+        .last_insert_rowid();
+        Ok(id)
+    }
+
+    pub async fn get_screening_rules(&self, guild_id: GuildId) -> Result<Vec<ScreeningRule>> {
+        let guild_id = guild_id.0 as i64;
+        let q = sqlx::query!(
+            "select id, kind, pattern, action, reason from screening_rules where guild_id=? order by id",
+            guild_id
+        )
+        .fetch_all(&self.sqlitedb)
+        .await?
+        .into_iter()
+        .map(|x| ScreeningRule {
+            regex: (MatchKind::parse(&x.kind) != Some(MatchKind::Confusable))
+                .then(|| compile(&x.pattern))
+                .flatten(),
+            id: x.id,
+            kind: x.kind,
+            pattern: x.pattern,
+            action: x.action,
+            reason: x.reason,
+        })
+        .collect();
+        Ok(q)
+    }
+
+    pub async fn remove_screening_rule(&self, guild_id: GuildId, id: i64) -> Result<bool> {
+        let guild_id = guild_id.0 as i64;
+        let removed = sqlx::query!(
+            "delete from screening_rules where guild_id=? and id=?",
+            guild_id,
+            id
+        )
+        .execute(&self.sqlitedb)
+        .await?
+        .rows_affected();
+        Ok(removed > 0)
+    }
+
+    /// Every hit is kept, even after its rule is removed.
+    pub async fn add_screening_hit(
+        &self,
+        guild_id: GuildId,
+        rule: &ScreeningRule,
+        user_id: UserId,
+        name: &str,
+        trigger: &str,
+    ) -> Result<()> {
+        let guild_id = guild_id.0 as i64;
+        let user_id = user_id.0 as i64;
+        let hit_at = Timestamp::now().unix_timestamp();
+        sqlx::query!(
+            "insert into screening_hits (guild_id, rule_id, user_id, name, pattern, action, trigger, hit_at) values (?, ?, ?, ?, ?, ?, ?, ?)",
+            guild_id,
+            rule.id,
+            user_id,
+            name,
+            rule.pattern,
+            rule.action,
+            trigger,
+            hit_at
+        )
+        .execute(&self.sqlitedb)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn has_screening_hit(
+        &self,
+        guild_id: GuildId,
+        rule_id: i64,
+        user_id: UserId,
+        name: &str,
+    ) -> Result<bool> {
+        let guild_id = guild_id.0 as i64;
+        let user_id = user_id.0 as i64;
+        let q = sqlx::query!(
+            "select id from screening_hits where guild_id=? and rule_id=? and user_id=? and name=?",
+            guild_id,
+            rule_id,
+            user_id,
+            name
+        )
+        .fetch_optional(&self.sqlitedb)
+        .await?;
+        Ok(q.is_some())
+    }
+
+    /// Hit count and the last hit of every rule.
+    pub async fn get_screening_hit_counts(
+        &self,
+        guild_id: GuildId,
+    ) -> Result<HashMap<i64, (i64, i64)>> {
+        let guild_id = guild_id.0 as i64;
+        let q = sqlx::query!(
+            r#"select rule_id, count(*) as "hits!: i64", max(hit_at) as "last_hit!: i64" from screening_hits where guild_id=? group by rule_id"#,
+            guild_id
+        )
+        .fetch_all(&self.sqlitedb)
+        .await?
+        .into_iter()
+        .map(|x| (x.rule_id, (x.hits, x.last_hit)))
+        .collect();
+        Ok(q)
+    }
+}
+
+/// Folds a name into what it looks like: lowercase ASCII letters and digits only, with
+/// accents, lookalike letters from other scripts, fullwidth and math letters and
+/// leetspeak mapped to the letter they imitate. Separators and invisible characters are dropped.
+pub fn skeleton(name: &str) -> String {
+    const LOOKALIKES: &[(&str, char)] = &[
+        ("àáâãäåāăąǎạảấầẩẫậắằẳẵặаα@4", 'a'),
+        ("ßвβ8", 'b'),
+        ("çćĉċčсϲ¢", 'c'),
+        ("ďđԁ", 'd'),
+        ("èéêëēĕėęěẹẻẽếềểễệеεё3", 'e'),
+        ("ĝğġģɡ9", 'g'),
+        ("ĥħһн", 'h'),
+        ("ìíîïĩīĭįıǐịỉіιї1!|lӏĺļľŀł", 'i'),
+        ("ĵј", 'j'),
+        ("ķкκ", 'k'),
+        ("м", 'm'),
+        ("ñńņňŉпη", 'n'),
+        ("òóôõöøōŏőǒọỏốồổỗộớờởỡợоοσ0", 'o'),
+        ("рρ", 'p'),
+        ("ŕŗřг", 'r'),
+        ("śŝşšѕ$5", 's'),
+        ("ţťŧтτ7+", 't'),
+        ("ùúûüũūŭůűųǔụủứừửữựυц", 'u'),
+        ("νѵ", 'v'),
+        ("ŵω", 'w'),
+        ("хχ", 'x'),
+        ("ýÿŷуγ", 'y'),
+        ("źżž", 'z'),
+    ];
+
+    let mut folded = String::with_capacity(name.len());
+    for c in name.chars().flat_map(char::to_lowercase) {
+        let c = match c as u32 {
+            // Fullwidth forms
+            0xFF01..=0xFF5E => char::from_u32(c as u32 - 0xFEE0).unwrap_or(c),
+            // Mathematical bold, italic, script... letters, 52 per style
+            0x1D400..=0x1D6A3 => (b'a' + ((c as u32 - 0x1D400) % 52 % 26) as u8) as char,
+            // Mathematical digits, 10 per style
+            0x1D7CE..=0x1D7FF => (b'0' + ((c as u32 - 0x1D7CE) % 10) as u8) as char,
+            _ => c,
+        };
+        let c = c.to_ascii_lowercase();
+        let c = LOOKALIKES
+            .iter()
+            .find(|(chars, _)| chars.contains(c))
+            .map_or(c, |(_, to)| *to);
+        if c.is_ascii_alphanumeric() {
+            folded.push(c);
+        }
+    }
+    folded.replace("rn", "m").replace("vv", "w")
+}
+
+/// The names a member is shown with.
+fn member_names(member: &Member) -> Vec<&str> {
+    let mut names = vec![member.user.name.as_str()];
+    if let Some(nick) = &member.nick {
+        names.push(nick.as_str());
+    }
+    names
+}
+
+async fn apply_action(
+    ctx: &Context,
+    member: &Member,
+    action: ScreenAction,
+    reason: &str,
+) -> Result<()> {
+    let guild_id = member.guild_id;
+    match action {
+        ScreenAction::Flag => {}
+        ScreenAction::Quarantine => {
+            let db = ctx.get_db().await;
+            match db
+                .get_guild_config_as::<RoleId>(guild_id, "quarantine_role")
+                .await?
+            {
+                Some(role_id) => {
+                    member.clone().add_role(&ctx.http, role_id).await?;
+                }
+                None => {
+                    anyhow::bail!("no quarantine role, set one with `gp config quarantine_role`")
+                }
+            }
+        }
+        ScreenAction::Kick | ScreenAction::Ban => {
+            let locale = i18n::guild_locale(ctx, Some(guild_id)).await;
+            let guild_name = guild_id
+                .name(&ctx.cache)
+                .unwrap_or_else(|| guild_id.to_string());
+            // Can't DM them once they're gone, and they may not accept DMs at all
+            member
+                .user
+                .direct_message(&ctx.http, |m| {
+                    m.content(tr_args(
+                        locale,
+                        "screen-removed",
+                        &[("guild", guild_name), ("reason", reason.to_string())],
+                    ))
+                })
+                .await
+                .ok();
+            if action == ScreenAction::Kick {
+                member.kick_with_reason(&ctx.http, reason).await?;
+            } else {
+                member.ban_with_reason(&ctx.http, 0, reason).await?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Runs the guild's rules on the member's names, `trigger` tells why for the log.
+/// Names that already hit a flag rule aren't logged again, harsher rules act on every match.
+/// Returns the action taken, if any.
+pub async fn screen_member(
+    ctx: &Context,
+    member: &Member,
+    trigger: &str,
+) -> Result<Option<ScreenAction>> {
+    if member.user.bot {
+        return Ok(None);
+    }
+    let guild_id = member.guild_id;
+    let db = ctx.get_db().await;
+    let names = member_names(member);
+
+    let mut hits: Vec<(ScreeningRule, &str)> = Vec::new();
+    for rule in db.get_screening_rules(guild_id).await? {
+        if let Some(name) = rule.matches(&names) {
+            // A member removed for their name mustn't get back in by rejoining under it
+            if rule.action() != ScreenAction::Flag
+                || !db
+                    .has_screening_hit(guild_id, rule.id, member.user.id, name)
+                    .await?
+            {
+                hits.push((rule, name));
+            }
+        }
+    }
+    // The harshest action wins, its reason is the one given to the member
+    let worst = match hits.iter().max_by_key(|(rule, _)| rule.action()) {
+        Some((rule, _)) => (rule.action(), rule.reason.clone()),
+        None => return Ok(None),
+    };
+
+    let mut log = MessageBuilder::new();
+    log.push(format!(
+        "> :shield: {} (**{}**) failed name screening on {}",
+        member.user.mention(),
+        member.user.tag(),
+        trigger
+    ));
+    for (rule, name) in &hits {
+        db.add_screening_hit(guild_id, rule, member.user.id, name, trigger)
+            .await?;
+        log.push_line("").push(format!(
+            "> • `{}` matched rule #{} ({} `{}`, {}): {}",
+            name, rule.id, rule.kind, rule.pattern, rule.action, rule.reason
+        ));
+    }
+    let outcome = match apply_action(ctx, member, worst.0, &worst.1).await {
+        Ok(()) => format!("Action taken: **{}**", worst.0.name()),
+        Err(e) => format!("Failed to {}: {}", worst.0.name(), e),
+    };
+    println!(
+        "Name screening of {} ({}) in {}: {}",
+        member.user.tag(),
+        member.user.id,
+        guild_id,
+        outcome
+    );
+    log.push_line("").push(format!("> {}", outcome));
+    post_log(ctx, guild_id, log.build()).await?;
+    Ok(Some(worst.0))
+}
+
+/// Nickname and username changes, the member is screened again when a name changed.
+pub async fn member_updated(ctx: &Context, old: Option<&Member>, new: &Member) {
+    let changed = old.is_none_or(|old| old.user.name != new.user.name || old.nick != new.nick);
+    if changed {
+        if let Err(e) = screen_member(ctx, new, "name change").await {
+            eprintln!(
+                "Failed to screen the name of {} in {}: {}",
+                new.user.id, new.guild_id, e
+            );
+        }
+    }
+}
+
+fn describe(rule: &ScreeningRule) -> String {
+    format!(
+        "#{} {} `{}` → **{}**: {}",
+        rule.id, rule.kind, rule.pattern, rule.action, rule.reason
+    )
+}
+
+pub async fn responder(mci: &ApplicationCommandInteraction, ctx: &Context) {
+    let guild_id = mci.guild_id.unwrap();
+    let subcommand = match mci.data.options.first() {
+        Some(x) => x,
+        None => return,
+    };
+    let db = ctx.get_db().await;
+
+    let mut pattern = String::new();
+    let mut kind = MatchKind::Regex;
+    let mut action = ScreenAction::Flag;
+    let mut reason = String::new();
+    let mut name = String::new();
+    let mut id = 0;
+    for option in &subcommand.options {
+        match (option.name.as_str(), &option.resolved) {
+            ("pattern", Some(CommandDataOptionValue::String(s))) => pattern = s.clone(),
+            ("match", Some(CommandDataOptionValue::String(s))) => {
+                kind = MatchKind::parse(s).unwrap_or(kind)
+            }
+            ("action", Some(CommandDataOptionValue::String(s))) => {
+                action = ScreenAction::parse(s).unwrap_or(action)
+            }
+            ("reason", Some(CommandDataOptionValue::String(s))) => reason = s.clone(),
+            ("name", Some(CommandDataOptionValue::String(s))) => name = s.clone(),
+            ("id", Some(CommandDataOptionValue::Integer(i))) => id = *i,
+            _ => {}
+        }
+    }
+
+    let response = match subcommand.name.as_str() {
+        "add" => {
+            if kind == MatchKind::Regex && compile(&pattern).is_none() {
+                format!("Invalid regex: `{}`", pattern)
+            } else if kind == MatchKind::Confusable && skeleton(&pattern).is_empty() {
+                "The pattern has no letters or digits left to match once folded".to_string()
+            } else {
+                let id = db
+                    .add_screening_rule(guild_id, kind, &pattern, action, &reason, mci.user.id)
+                    .await
+                    .unwrap();
+                let mut text = format!("Added rule #{}", id);
+                if action == ScreenAction::Quarantine
+                    && db
+                        .get_guild_config(guild_id, "quarantine_role")
+                        .await
+                        .unwrap()
+                        .is_none()
+                {
+                    text.push_str(", set the role with `gp config quarantine_role` for it to work");
+                }
+                text
+            }
+        }
+        "list" => {
+            let rules = db.get_screening_rules(guild_id).await.unwrap();
+            let hits = db.get_screening_hit_counts(guild_id).await.unwrap();
+            let mut text = MessageBuilder::new();
+            if rules.is_empty() {
+                text.push_line("No screening rules");
+            }
+            for rule in &rules {
+                text.push(describe(rule));
+                match hits.get(&rule.id) {
+                    Some((count, last_hit)) => {
+                        text.push_line(format!(" ({} hits, last <t:{}:R>)", count, last_hit))
+                    }
+                    None => text.push_line(" (no hits)"),
+                };
+            }
+            text.build()
+        }
+        "test" => {
+            let rules = db.get_screening_rules(guild_id).await.unwrap();
+            let mut text = MessageBuilder::new();
+            text.push_line(format!("Folded: `{}`", skeleton(&name)));
+            let matched: Vec<&ScreeningRule> = rules
+                .iter()
+                .filter(|x| x.matches(&[name.as_str()]).is_some())
+                .collect();
+            if matched.is_empty() {
+                text.push_line("No rule matches");
+            }
+            for rule in matched {
+                text.push_line(describe(rule));
+            }
+            text.build()
+        }
+        "remove" => {
+            if db.remove_screening_rule(guild_id, id).await.unwrap() {
+                format!("Removed rule #{}", id)
+            } else {
+                format!("There is no rule #{}", id)
+            }
+        }
+        _ => return,
+    };
+
+    mci.create_interaction_response(&ctx.http, |r| {
+        r.kind(InteractionResponseType::ChannelMessageWithSource)
+            .interaction_response_data(|d| d.content(response).flags(MessageFlags::EPHEMERAL))
+    })
+    .await
+    .unwrap();
+}
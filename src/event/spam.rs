@@ -0,0 +1,656 @@
+use super::*;
+use crate::db::Db;
+use moderation::CaseAction;
+use screening::skeleton;
+use serenity::builder::CreateComponents;
+use serenity::model::{
+    application::interaction::{
+        application_command::{ApplicationCommandInteraction, CommandDataOptionValue},
+        message_component::MessageComponentInteraction,
+        MessageFlags,
+    },
+    id::{RoleId, UserId},
+    Permissions, Timestamp,
+};
+use serenity::utils::MessageBuilder;
+use std::{
+    collections::{hash_map::DefaultHasher, HashMap, HashSet, VecDeque},
+    hash::{Hash, Hasher},
+    sync::{Mutex, OnceLock},
+};
+use substr::StringUtils;
+
+/// The real domains of the brands scams imitate.
+const LEGIT_DOMAINS: &[&str] = &[
+    "discord.com",
+    "discord.gg",
+    "discord.gift",
+    "discord.media",
+    "discord.new",
+    "discord.dev",
+    "discordapp.com",
+    "discordapp.net",
+    "discordstatus.com",
+    "steamcommunity.com",
+    "steampowered.com",
+    "gitpod.io",
+];
+const BRANDS: &[&str] = &[
+    "discord",
+    "discordapp",
+    "steamcommunity",
+    "steampowered",
+    "gitpod",
+];
+/// Misspellings scam domains use that no homoglyph folding catches, and the brand they imitate.
+const LOOKALIKES: &[(&str, &str)] = &[
+    ("dicsord", "discord"),
+    ("discrod", "discord"),
+    ("disocrd", "discord"),
+    ("discorcl", "discord"),
+    ("dsicord", "discord"),
+    ("discordd", "discord"),
+    ("discordaap", "discordapp"),
+    ("discorapp", "discordapp"),
+    ("steamcomunity", "steamcommunity"),
+    ("steamcommnuity", "steamcommunity"),
+    ("stearncommunity", "steamcommunity"),
+    ("steampowerd", "steampowered"),
+    ("stearnpowered", "steampowered"),
+];
+/// Words that turn a domain using a brand's name into bait, e.g. `discord-nitro.ru`.
+const BAIT_WORDS: &[&str] = &["nitro", "gift", "free", "airdrop", "promo", "claim", "drop"];
+
+pub struct SpamSettings {
+    pub enabled: bool,
+    /// Distinct channels the same message has to show up in
+    pub channels: usize,
+    pub window_secs: i64,
+}
+
+impl SpamSettings {
+    pub async fn get(ctx: &Context, guild_id: GuildId) -> Result<Self> {
+        let db = ctx.get_db().await;
+        Ok(Self {
+            enabled: db
+                .get_guild_config_as(guild_id, "spam_protection")
+                .await?
+                .unwrap_or(false),
+            channels: db
+                .get_guild_config_as(guild_id, "spam_channels")
+                .await?
+                .unwrap_or(3),
+            window_secs: db
+                .get_guild_config_as(guild_id, "spam_window_secs")
+                .await?
+                .unwrap_or(60),
+        })
+    }
+}
+
+impl Db {
+    pub async fn add_scam_domain(&self, domain: &str, added_by: UserId) -> Result<bool> {
+        let added_by = added_by.0 as i64;
+        let added_at = Timestamp::now().unix_timestamp();
+        let q = sqlx::query!(
+            "insert or ignore into scam_domains (domain, added_by, added_at) values (?, ?, ?)",
+            domain,
+            added_by,
+            added_at
+        )
+        .execute(&self.sqlitedb)
+        .await?;
+        Ok(q.rows_affected() > 0)
+    }
+
+    pub async fn remove_scam_domain(&self, domain: &str) -> Result<bool> {
+        let q = sqlx::query!("delete from scam_domains where domain=?", domain)
+            .execute(&self.sqlitedb)
+            .await?;
+        Ok(q.rows_affected() > 0)
+    }
+
+    pub async fn get_scam_domains(&self) -> Result<Vec<String>> {
+        let q = sqlx::query!("select domain from scam_domains order by domain")
+            .fetch_all(&self.sqlitedb)
+            .await?
+            .into_iter()
+            .map(|x| x.domain)
+            .collect();
+        Ok(q)
+    }
+}
+
+/// Decodes the part of an IDNA label after `xn--` (RFC 3492).
+fn punycode_decode(input: &str) -> Option<String> {
+    const BASE: u32 = 36;
+    const TMIN: u32 = 1;
+    const TMAX: u32 = 26;
+    fn adapt(delta: u32, points: u32, first: bool) -> u32 {
+        let mut delta = if first { delta / 700 } else { delta / 2 };
+        delta += delta / points;
+        let mut k = 0;
+        while delta > ((BASE - TMIN) * TMAX) / 2 {
+            delta /= BASE - TMIN;
+            k += BASE;
+        }
+        k + (BASE - TMIN + 1) * delta / (delta + 38)
+    }
+
+    let (basic, encoded) = match input.rfind('-') {
+        Some(i) => (&input[..i], &input[i + 1..]),
+        None => ("", input),
+    };
+    let mut output: Vec<char> = basic.chars().collect();
+    let (mut n, mut i, mut bias) = (128u32, 0u32, 72u32);
+    let mut digits = encoded.chars().peekable();
+    while digits.peek().is_some() {
+        let old_i = i;
+        let mut w = 1u32;
+        let mut k = BASE;
+        loop {
+            let digit = match digits.next()? {
+                c @ 'a'..='z' => c as u32 - 'a' as u32,
+                c @ 'A'..='Z' => c as u32 - 'A' as u32,
+                c @ '0'..='9' => c as u32 - '0' as u32 + 26,
+                _ => return None,
+            };
+            i = i.checked_add(digit.checked_mul(w)?)?;
+            let t = if k <= bias {
+                TMIN
+            } else if k >= bias + TMAX {
+                TMAX
+            } else {
+                k - bias
+            };
+            if digit < t {
+                break;
+            }
+            w = w.checked_mul(BASE - t)?;
+            k += BASE;
+        }
+        let points = output.len() as u32 + 1;
+        bias = adapt(i - old_i, points, old_i == 0);
+        n = n.checked_add(i / points)?;
+        i %= points;
+        output.insert(i as usize, char::from_u32(n)?);
+        i += 1;
+    }
+    Some(output.into_iter().collect())
+}
+
+/// The domain as it is displayed, punycode labels decoded.
+fn decode_domain(domain: &str) -> String {
+    domain
+        .split('.')
+        .map(|label| match label.strip_prefix("xn--") {
+            Some(encoded) => punycode_decode(encoded).unwrap_or_else(|| label.to_string()),
+            None => label.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+fn is_subdomain_of(domain: &str, parent: &str) -> bool {
+    domain == parent || domain.ends_with(&format!(".{}", parent))
+}
+
+/// Why the domain looks like a scam, if it does.
+pub fn check_domain(domain: &str, blocklist: &[String]) -> Option<String> {
+    let domain = domain.trim_end_matches('.').to_lowercase();
+    if LEGIT_DOMAINS.iter().any(|x| is_subdomain_of(&domain, x)) {
+        return None;
+    }
+    if let Some(blocked) = blocklist.iter().find(|x| is_subdomain_of(&domain, x)) {
+        return Some(format!("`{}` is on the blocklist", blocked));
+    }
+
+    let decoded = decode_domain(&domain);
+    let punycode = decoded != domain;
+    // Lookalikes of blocked domains, e.g. with Cyrillic letters
+    let folded = skeleton(&decoded);
+    if let Some(blocked) = blocklist.iter().find(|x| skeleton(x) == folded) {
+        return Some(format!("`{}` looks like blocked `{}`", decoded, blocked));
+    }
+
+    let labels: Vec<&str> = decoded
+        .split(['.', '-'])
+        .filter(|x| !x.is_empty())
+        .collect();
+    let has_bait = labels
+        .iter()
+        .any(|x| BAIT_WORDS.iter().any(|bait| skeleton(x).contains(bait)));
+    for label in &labels {
+        let folded = skeleton(label);
+        // A known misspelling, or homoglyphs folding into the brand's name
+        if let Some((_, brand)) = LOOKALIKES.iter().find(|(x, _)| skeleton(x) == folded) {
+            return Some(format!("`{}` imitates {}", decoded, brand));
+        }
+        for brand in BRANDS {
+            let imitation = *label != *brand && folded == *brand;
+            let named = *label == *brand && has_bait;
+            let disguised = punycode && folded.contains(brand);
+            if imitation || named || disguised {
+                return Some(format!("`{}` imitates {}", decoded, brand));
+            }
+        }
+    }
+    None
+}
+
+static LINK_RE: OnceLock<Regex> = OnceLock::new();
+
+fn link_domains(content: &str) -> Vec<String> {
+    LINK_RE
+        .get_or_init(|| Regex::new(r"(?i)https?://([^/\s<>:?#]+)").unwrap())
+        .captures_iter(content)
+        .map(|x| x[1].to_string())
+        .collect()
+}
+
+struct Posted {
+    at: i64,
+    fingerprint: u64,
+    channel_id: ChannelId,
+    message_id: MessageId,
+}
+
+type History = HashMap<(GuildId, UserId), VecDeque<Posted>>;
+
+// Recent messages per author, across channels
+static RECENT: OnceLock<Mutex<History>> = OnceLock::new();
+
+fn fingerprint(msg: &Message) -> Option<u64> {
+    let text = msg
+        .content
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase();
+    // Short replies like "thanks" repeat legitimately
+    if text.chars().count() < 10 && msg.attachments.is_empty() {
+        return None;
+    }
+    let mut hasher = DefaultHasher::new();
+    text.hash(&mut hasher);
+    for attachment in &msg.attachments {
+        (&attachment.filename, attachment.size).hash(&mut hasher);
+    }
+    Some(hasher.finish())
+}
+
+/// Copies of the message posted within the window, when they're in enough channels.
+fn burst_copies(
+    guild_id: GuildId,
+    msg: &Message,
+    settings: &SpamSettings,
+) -> Option<Vec<(ChannelId, MessageId)>> {
+    let print = fingerprint(msg)?;
+    let now = msg.timestamp.unix_timestamp();
+    let mut recent = RECENT.get_or_init(Default::default).lock().unwrap();
+    let history = recent.entry((guild_id, msg.author.id)).or_default();
+    while history
+        .front()
+        .is_some_and(|x| x.at <= now - settings.window_secs)
+    {
+        history.pop_front();
+    }
+    history.push_back(Posted {
+        at: now,
+        fingerprint: print,
+        channel_id: msg.channel_id,
+        message_id: msg.id,
+    });
+    let copies: Vec<(ChannelId, MessageId)> = history
+        .iter()
+        .filter(|x| x.fingerprint == print)
+        .map(|x| (x.channel_id, x.message_id))
+        .collect();
+    let channels: HashSet<ChannelId> = copies.iter().map(|(c, _)| *c).collect();
+    if channels.len() >= settings.channels {
+        history.retain(|x| x.fingerprint != print);
+        Some(copies)
+    } else {
+        None
+    }
+}
+
+fn alert_buttons(components: &mut CreateComponents, user_id: UserId) -> &mut CreateComponents {
+    components.create_action_row(|a| {
+        a.create_button(|b| {
+            b.custom_id(format!("spam_ban:{}", user_id))
+                .label("Ban")
+                .style(ButtonStyle::Danger)
+        })
+        .create_button(|b| {
+            b.custom_id(format!("spam_release:{}", user_id))
+                .label("Release")
+                .style(ButtonStyle::Secondary)
+        })
+    })
+}
+
+/// Takes the member out of circulation until a moderator looks at it, returns how.
+async fn quarantine(ctx: &Context, guild_id: GuildId, user_id: UserId) -> Result<String> {
+    let db = ctx.get_db().await;
+    match db
+        .get_guild_config_as::<RoleId>(guild_id, "quarantine_role")
+        .await?
+    {
+        Some(role_id) => {
+            ctx.http
+                .add_member_role(guild_id.0, user_id.0, role_id.0, Some("Spam detected"))
+                .await?;
+            Ok(format!("Quarantined with {}", role_id.mention()))
+        }
+        // Only the alert then, moderators decide what happens
+        None => Ok("Not quarantined, set `gp config quarantine_role` to do that".to_string()),
+    }
+}
+
+async fn act(
+    ctx: &Context,
+    guild_id: GuildId,
+    msg: &Message,
+    reason: String,
+    copies: &[(ChannelId, MessageId)],
+) {
+    let contained = match quarantine(ctx, guild_id, msg.author.id).await {
+        Ok(text) => text,
+        Err(e) => format!("Failed to quarantine: {}", e),
+    };
+    let mut deleted = 0;
+    for (channel_id, message_id) in copies {
+        if channel_id
+            .delete_message(&ctx.http, message_id)
+            .await
+            .is_ok()
+        {
+            deleted += 1;
+        }
+    }
+    println!(
+        "Spam from {} ({}) in {}: {}",
+        msg.author.tag(),
+        msg.author.id,
+        guild_id,
+        reason
+    );
+
+    let alert_channel = match moderation::mod_log_channel(ctx, guild_id).await {
+        Ok(Some(channel)) => channel,
+        Ok(None) => return,
+        Err(e) => {
+            eprintln!(
+                "Failed to get the spam alert channel of {}: {}",
+                guild_id, e
+            );
+            return;
+        }
+    };
+    let mut content = MessageBuilder::new();
+    if let Some(role_id) = support_stats::get_staff_role(ctx, guild_id).await {
+        content.mention(&role_id).push(" ");
+    }
+    content
+        .push_bold_line("🚨 Spam detected")
+        .push_line(format!(
+            "{} (**{}**, {}): {}",
+            msg.author.mention(),
+            msg.author.tag(),
+            msg.author.id,
+            reason
+        ))
+        .push_line(format!(
+            "{}, deleted {} of {} messages",
+            contained,
+            deleted,
+            copies.len()
+        ))
+        .push_quote_line_safe(msg.content.replace('\n', " ").as_str().substring(0, 500));
+    if let Err(e) = alert_channel
+        .send_message(&ctx.http, |m| {
+            m.content(content.build())
+                .components(|c| alert_buttons(c, msg.author.id))
+        })
+        .await
+    {
+        eprintln!("Failed to post a spam alert in {}: {}", guild_id, e);
+    }
+}
+
+/// Checks the message for burst duplicates and scam links, returns whether it was removed.
+pub async fn check_message(ctx: &Context, msg: &Message) -> bool {
+    let guild_id = match msg.guild_id {
+        Some(guild_id) => guild_id,
+        None => return false,
+    };
+    if msg.author.bot || msg.webhook_id.is_some() {
+        return false;
+    }
+    let settings = match SpamSettings::get(ctx, guild_id).await {
+        Ok(settings) => settings,
+        Err(e) => {
+            eprintln!("Failed to get the spam settings of {}: {}", guild_id, e);
+            return false;
+        }
+    };
+    if !settings.enabled {
+        return false;
+    }
+    // Moderators post announcements in several channels
+    let trusted = ctx
+        .cache
+        .member(guild_id, msg.author.id)
+        .and_then(|member| member.permissions(&ctx.cache).ok())
+        .is_some_and(|x| x.manage_messages());
+    if trusted {
+        return false;
+    }
+
+    if let Some(copies) = burst_copies(guild_id, msg, &settings) {
+        let channels: HashSet<ChannelId> = copies.iter().map(|(c, _)| *c).collect();
+        let reason = format!(
+            "the same message in {} channels within {}s",
+            channels.len(),
+            settings.window_secs
+        );
+        act(ctx, guild_id, msg, reason, &copies).await;
+        return true;
+    }
+
+    let domains = link_domains(&msg.content);
+    if !domains.is_empty() {
+        let db = ctx.get_db().await;
+        let blocklist = match db.get_scam_domains().await {
+            Ok(blocklist) => blocklist,
+            Err(e) => {
+                eprintln!("Failed to get the scam domains: {}", e);
+                Vec::new()
+            }
+        };
+        if let Some(reason) = domains.iter().find_map(|x| check_domain(x, &blocklist)) {
+            act(
+                ctx,
+                guild_id,
+                msg,
+                format!("scam link, {}", reason),
+                &[(msg.channel_id, msg.id)],
+            )
+            .await;
+            return true;
+        }
+    }
+    false
+}
+
+/// The Ban and Release buttons of the alert, `spam_{ban|release}:{user_id}`.
+pub async fn handle_button(mci: &MessageComponentInteraction, ctx: &Context) {
+    let guild_id = mci.guild_id.unwrap();
+    let (action, user_id) = match mci.data.custom_id.split_once(':') {
+        Some((action, id)) => match id.parse::<u64>() {
+            Ok(id) => (action, UserId(id)),
+            Err(_) => return,
+        },
+        None => return,
+    };
+    let permissions = mci
+        .member
+        .as_ref()
+        .and_then(|x| x.permissions)
+        .unwrap_or_else(Permissions::empty);
+    let allowed = match action {
+        "spam_ban" => permissions.ban_members(),
+        "spam_release" => permissions.moderate_members(),
+        _ => return,
+    };
+    if !allowed {
+        mci.create_interaction_response(&ctx.http, |r| {
+            r.kind(InteractionResponseType::ChannelMessageWithSource)
+                .interaction_response_data(|d| {
+                    d.content("You don't have the permission to do that")
+                        .flags(MessageFlags::EPHEMERAL)
+                })
+        })
+        .await
+        .unwrap();
+        return;
+    }
+
+    mci.create_interaction_response(&ctx.http, |r| {
+        r.kind(InteractionResponseType::DeferredChannelMessageWithSource)
+            .interaction_response_data(|d| d.flags(MessageFlags::EPHEMERAL))
+    })
+    .await
+    .unwrap();
+    let text = if action == "spam_ban" {
+        match moderation::open_case(
+            ctx,
+            guild_id,
+            user_id,
+            mci.user.id,
+            CaseAction::Ban,
+            "Spam",
+            None,
+            &mci.message.link(),
+            1,
+        )
+        .await
+        {
+            Ok((case, _)) => format!("Banned, case #{}", case.case_number),
+            Err(e) => format!("Failed to ban: {}", e),
+        }
+    } else {
+        // Only undoes the quarantine, a timeout a moderator set stays
+        let db = ctx.get_db().await;
+        match db
+            .get_guild_config_as::<RoleId>(guild_id, "quarantine_role")
+            .await
+        {
+            Ok(Some(role_id)) => match ctx
+                .http
+                .remove_member_role(guild_id.0, user_id.0, role_id.0, Some("Released"))
+                .await
+            {
+                Ok(_) => "Released".to_string(),
+                Err(e) => format!("Failed to release: {}", e),
+            },
+            Ok(None) => "Nothing to release, there's no quarantine role".to_string(),
+            Err(e) => format!("Failed to release: {}", e),
+        }
+    };
+
+    let mut alert = mci.message.clone();
+    let content = format!("{}\n\n{} — {}", alert.content, mci.user.mention(), text);
+    alert
+        .edit(&ctx.http, |m| m.content(content).components(|c| c))
+        .await
+        .ok();
+    mci.edit_original_interaction_response(&ctx.http, |d| d.content(text))
+        .await
+        .unwrap();
+}
+
+/// Normalizes what moderators type, whether a domain or a link.
+fn parse_domain(input: &str) -> Option<String> {
+    let input = input.trim().to_lowercase();
+    let input = input
+        .strip_prefix("https://")
+        .or_else(|| input.strip_prefix("http://"))
+        .unwrap_or(&input);
+    let domain = input
+        .split(['/', '?', '#', ':'])
+        .next()?
+        .trim_end_matches('.');
+    (domain.contains('.') && !domain.contains(char::is_whitespace)).then(|| domain.to_string())
+}
+
+/// `/scam-domains`
+pub async fn responder(mci: &ApplicationCommandInteraction, ctx: &Context) {
+    let subcommand = match mci.data.options.first() {
+        Some(x) => x,
+        None => return,
+    };
+    let db = ctx.get_db().await;
+    let input = subcommand.options.iter().find_map(|x| match &x.resolved {
+        Some(CommandDataOptionValue::String(s)) => Some(s.as_str()),
+        _ => None,
+    });
+
+    let response = match (subcommand.name.as_str(), input.and_then(parse_domain)) {
+        ("list", _) => {
+            let domains = db.get_scam_domains().await.unwrap();
+            if domains.is_empty() {
+                "The blocklist is empty".to_string()
+            } else {
+                format!(
+                    "**{} blocked domains**\n{}",
+                    domains.len(),
+                    domains
+                        .iter()
+                        .map(|x| format!("`{}`", x))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                )
+            }
+        }
+        ("add", Some(domain)) => {
+            if db.add_scam_domain(&domain, mci.user.id).await.unwrap() {
+                format!("Blocked `{}` and its subdomains", domain)
+            } else {
+                format!("`{}` is already blocked", domain)
+            }
+        }
+        ("remove", Some(domain)) => {
+            if db.remove_scam_domain(&domain).await.unwrap() {
+                format!("Unblocked `{}`", domain)
+            } else {
+                format!("`{}` isn't blocked", domain)
+            }
+        }
+        ("check", Some(domain)) => {
+            let blocklist = db.get_scam_domains().await.unwrap();
+            let decoded = decode_domain(&domain);
+            let shown = if decoded == domain {
+                format!("`{}`", domain)
+            } else {
+                format!("`{}` (`{}`)", domain, decoded)
+            };
+            match check_domain(&domain, &blocklist) {
+                Some(reason) => format!("{} would be caught: {}", shown, reason),
+                None => format!("{} looks fine", shown),
+            }
+        }
+        _ => "That isn't a domain or a link".to_string(),
+    };
+
+    mci.create_interaction_response(&ctx.http, |r| {
+        r.kind(InteractionResponseType::ChannelMessageWithSource)
+            .interaction_response_data(|d| {
+                d.content(response.as_str().substring(0, 2000))
+                    .flags(MessageFlags::EPHEMERAL)
+            })
+    })
+    .await
+    .unwrap();
+}
@@ -0,0 +1,335 @@
+use super::*;
+use crate::db::{ClientContextExt, Db};
+use crate::utils::misc::{format_duration, week_start, WEEK_SECS};
+use serenity::model::{
+    application::interaction::{
+        application_command::{ApplicationCommandInteraction, CommandDataOptionValue},
+        MessageFlags,
+    },
+    id::{RoleId, UserId},
+    Timestamp,
+};
+use serenity::utils::MessageBuilder;
+use std::{collections::BTreeMap, fmt::Write};
+use substr::StringUtils;
+
+pub struct QuestionThread {
+    pub thread_id: i64,
+    pub channel_id: i64,
+    pub user_id: i64,
+    pub created_at: i64,
+    pub first_response_at: Option<i64>,
+    pub first_staff_response_at: Option<i64>,
+    pub closed_at: Option<i64>,
+}
+
+impl Db {
+    pub async fn add_question_thread(
+        &self,
+        thread_id: ChannelId,
+        guild_id: GuildId,
+        channel_id: ChannelId,
+        user_id: UserId,
+        created_at: i64,
+    ) -> Result<()> {
+        let thread_id = thread_id.0 as i64;
+        let guild_id = guild_id.0 as i64;
+        let channel_id = channel_id.0 as i64;
+        let user_id = user_id.0 as i64;
+        sqlx::query!(
+            "insert into question_threads (thread_id, guild_id, channel_id, user_id, created_at) values (?, ?, ?, ?, ?)",
+            thread_id,
+            guild_id,
+            channel_id,
+            user_id,
+            created_at
+        )
+        .execute(&self.sqlitedb)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn get_question_thread(
+        &self,
+        thread_id: ChannelId,
+    ) -> Result<Option<QuestionThread>> {
+        let thread_id = thread_id.0 as i64;
+        let q = sqlx::query_as!(
+            QuestionThread,
+            "select thread_id, channel_id, user_id, created_at, first_response_at, first_staff_response_at, closed_at from question_threads where thread_id=?",
+            thread_id
+        )
+        .fetch_optional(&self.sqlitedb)
+        .await?;
+        Ok(q)
+    }
+
+    pub async fn get_question_threads_since(
+        &self,
+        guild_id: GuildId,
+        since: i64,
+        channel_id: Option<ChannelId>,
+    ) -> Result<Vec<QuestionThread>> {
+        let guild_id = guild_id.0 as i64;
+        let channel_id = channel_id.map(|x| x.0 as i64);
+        let q = sqlx::query_as!(
+            QuestionThread,
+            "select thread_id, channel_id, user_id, created_at, first_response_at, first_staff_response_at, closed_at from question_threads where guild_id=?1 and created_at>=?2 and (?3 is null or channel_id=?3) order by created_at",
+            guild_id,
+            since,
+            channel_id
+        )
+        .fetch_all(&self.sqlitedb)
+        .await?;
+        Ok(q)
+    }
+
+    pub async fn set_question_first_response(&self, thread_id: ChannelId, at: i64) -> Result<()> {
+        let thread_id = thread_id.0 as i64;
+        sqlx::query!(
+            "update question_threads set first_response_at=? where thread_id=? and first_response_at is null",
+            at,
+            thread_id
+        )
+        .execute(&self.sqlitedb)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn set_question_first_staff_response(
+        &self,
+        thread_id: ChannelId,
+        at: i64,
+    ) -> Result<()> {
+        let thread_id = thread_id.0 as i64;
+        sqlx::query!(
+            "update question_threads set first_staff_response_at=? where thread_id=? and first_staff_response_at is null",
+            at,
+            thread_id
+        )
+        .execute(&self.sqlitedb)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn close_question_thread(&self, thread_id: ChannelId, at: i64) -> Result<()> {
+        let thread_id = thread_id.0 as i64;
+        sqlx::query!(
+            "update question_threads set closed_at=? where thread_id=? and closed_at is null",
+            at,
+            thread_id
+        )
+        .execute(&self.sqlitedb)
+        .await?;
+        Ok(())
+    }
+}
+
+/// The role whose members count as staff, falls back to the `Gitpodders` role when not configured.
+pub async fn get_staff_role(ctx: &Context, guild_id: GuildId) -> Option<RoleId> {
+    let db = ctx.get_db().await;
+    if let Ok(Some(role_id)) = db
+        .get_guild_config_as::<RoleId>(guild_id, "staff_role")
+        .await
+    {
+        return Some(role_id);
+    }
+    guild_id
+        .to_guild_cached(&ctx.cache)
+        .and_then(|g| g.role_by_name("Gitpodders").map(|r| r.id))
+}
+
+/// Called for every message, records the first replies on tracked question threads.
+pub async fn record_reply(ctx: &Context, msg: &Message) -> Result<()> {
+    let guild_id = match msg.guild_id {
+        Some(id) => id,
+        None => return Ok(()),
+    };
+    let db = ctx.get_db().await;
+    let thread = match db.get_question_thread(msg.channel_id).await? {
+        Some(thread) => thread,
+        None => return Ok(()),
+    };
+    if thread.user_id == msg.author.id.0 as i64 {
+        return Ok(());
+    }
+
+    let at = msg.timestamp.unix_timestamp();
+    if thread.first_response_at.is_none() {
+        db.set_question_first_response(msg.channel_id, at).await?;
+    }
+    if thread.first_staff_response_at.is_none() {
+        if let (Some(staff_role), Some(member)) =
+            (get_staff_role(ctx, guild_id).await, msg.member.as_ref())
+        {
+            if member.roles.contains(&staff_role) {
+                db.set_question_first_staff_response(msg.channel_id, at)
+                    .await?;
+            }
+        }
+    }
+    Ok(())
+}
+
+#[derive(Default)]
+struct Summary {
+    total: usize,
+    closed: usize,
+    first_response: Vec<i64>,
+    staff_response: Vec<i64>,
+    resolution: Vec<i64>,
+}
+
+impl Summary {
+    fn add(&mut self, thread: &QuestionThread) {
+        self.total += 1;
+        if let Some(at) = thread.first_response_at {
+            self.first_response.push(at - thread.created_at);
+        }
+        if let Some(at) = thread.first_staff_response_at {
+            self.staff_response.push(at - thread.created_at);
+        }
+        if let Some(at) = thread.closed_at {
+            self.closed += 1;
+            self.resolution.push(at - thread.created_at);
+        }
+    }
+
+    fn push_to(&mut self, heading: String, builder: &mut MessageBuilder) {
+        builder.push_line(format!(
+            "**{}** — {} questions, {} closed",
+            heading, self.total, self.closed
+        ));
+        for (label, values) in [
+            ("First response", &mut self.first_response),
+            ("Staff response", &mut self.staff_response),
+            ("Resolution", &mut self.resolution),
+        ] {
+            builder.push_quote_line(format!("{}: {}", label, percentiles(values)));
+        }
+    }
+}
+
+/// Nearest-rank percentile of an already sorted slice.
+fn percentile(sorted: &[i64], p: usize) -> i64 {
+    let rank = (p * sorted.len()).div_ceil(100).max(1);
+    sorted[rank - 1]
+}
+
+fn percentiles(values: &mut [i64]) -> String {
+    if values.is_empty() {
+        return "n/a".to_string();
+    }
+    values.sort_unstable();
+    format!(
+        "median {} · p75 {} · p90 {}",
+        format_duration(percentile(values, 50)),
+        format_duration(percentile(values, 75)),
+        format_duration(percentile(values, 90))
+    )
+}
+
+fn to_csv(threads: &[QuestionThread]) -> String {
+    let fmt_ts = |ts: Option<i64>| {
+        ts.and_then(|x| Timestamp::from_unix_timestamp(x).ok())
+            .map(|x| x.to_string())
+            .unwrap_or_default()
+    };
+    let delta = |ts: Option<i64>, from: i64| ts.map(|x| (x - from).to_string()).unwrap_or_default();
+
+    let mut csv = String::from(
+        "thread_id,channel_id,user_id,created_at,first_response_at,first_staff_response_at,closed_at,first_response_secs,first_staff_response_secs,resolution_secs\n",
+    );
+    for t in threads {
+        writeln!(
+            csv,
+            "{},{},{},{},{},{},{},{},{},{}",
+            t.thread_id,
+            t.channel_id,
+            t.user_id,
+            fmt_ts(Some(t.created_at)),
+            fmt_ts(t.first_response_at),
+            fmt_ts(t.first_staff_response_at),
+            fmt_ts(t.closed_at),
+            delta(t.first_response_at, t.created_at),
+            delta(t.first_staff_response_at, t.created_at),
+            delta(t.closed_at, t.created_at),
+        )
+        .ok();
+    }
+    csv
+}
+
+pub async fn responder(mci: &ApplicationCommandInteraction, ctx: &Context) {
+    let mut channel_id: Option<ChannelId> = None;
+    let mut weeks: i64 = 8;
+    let mut export_csv = false;
+    for option in &mci.data.options {
+        match (option.name.as_str(), &option.resolved) {
+            ("channel", Some(CommandDataOptionValue::Channel(c))) => channel_id = Some(c.id),
+            ("weeks", Some(CommandDataOptionValue::Integer(n))) => weeks = *n,
+            ("csv", Some(CommandDataOptionValue::Boolean(b))) => export_csv = *b,
+            _ => {}
+        }
+    }
+
+    let db = ctx.get_db().await;
+    let since = week_start(Timestamp::now().unix_timestamp()) - (weeks - 1) * WEEK_SECS;
+    let threads = db
+        .get_question_threads_since(mci.guild_id.unwrap(), since, channel_id)
+        .await
+        .unwrap();
+
+    let mut per_channel: BTreeMap<i64, Summary> = BTreeMap::new();
+    let mut per_week: BTreeMap<i64, Summary> = BTreeMap::new();
+    for thread in &threads {
+        per_channel
+            .entry(thread.channel_id)
+            .or_default()
+            .add(thread);
+        per_week
+            .entry(week_start(thread.created_at))
+            .or_default()
+            .add(thread);
+    }
+
+    let mut channel_text = MessageBuilder::new();
+    for (channel, summary) in per_channel.iter_mut() {
+        summary.push_to(
+            ChannelId(*channel as u64).mention().to_string(),
+            &mut channel_text,
+        );
+    }
+    let mut week_text = MessageBuilder::new();
+    for (week, summary) in per_week.iter_mut() {
+        summary.push_to(format!("Week of <t:{}:d>", week), &mut week_text);
+    }
+    let csv = to_csv(&threads);
+
+    mci.create_interaction_response(&ctx.http, |r| {
+        r.kind(InteractionResponseType::ChannelMessageWithSource)
+            .interaction_response_data(|d| {
+                if threads.is_empty() {
+                    d.content(format!(
+                        "No questions were tracked in the last {} weeks",
+                        weeks
+                    ));
+                } else {
+                    d.embed(|e| {
+                        e.title(format!("Support stats per channel, last {} weeks", weeks))
+                            .description(channel_text.build().as_str().substring(0, 4096))
+                    });
+                    d.embed(|e| {
+                        e.title("Support stats per week")
+                            .description(week_text.build().as_str().substring(0, 4096))
+                    });
+                    if export_csv {
+                        d.add_file((csv.as_bytes(), "support-stats.csv"));
+                    }
+                }
+                d.flags(MessageFlags::EPHEMERAL)
+            })
+    })
+    .await
+    .unwrap();
+}
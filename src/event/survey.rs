@@ -0,0 +1,334 @@
+use super::*;
+use crate::db::{ClientContextExt, Db};
+use chrono::{NaiveDate, TimeZone, Utc};
+use serenity::futures::StreamExt;
+use serenity::model::{
+    application::interaction::{
+        application_command::{ApplicationCommandInteraction, CommandDataOptionValue},
+        MessageFlags,
+    },
+    id::UserId,
+    Timestamp,
+};
+use serenity::utils::MessageBuilder;
+use std::{
+    collections::{BTreeMap, HashSet},
+    fmt::Write,
+};
+use substr::StringUtils;
+
+/// Poll answers used to be handed out as roles named like this
+const LEGACY_ROLE_PREFIX: &str = "Found: ";
+const LEGACY_QUESTION: &str = "found";
+
+pub struct SurveyResponse {
+    pub user_id: i64,
+    pub question: String,
+    pub answer: String,
+    pub answered_at: i64,
+}
+
+impl Db {
+    /// Replaces the member's previous answers to `question`.
+    pub async fn set_survey_answers(
+        &self,
+        guild_id: GuildId,
+        user_id: UserId,
+        question: &str,
+        answers: &[String],
+        answered_at: i64,
+    ) -> Result<()> {
+        let guild_id = guild_id.0 as i64;
+        let user_id = user_id.0 as i64;
+        let mut tx = self.sqlitedb.begin().await?;
+        sqlx::query!(
+            "delete from survey_responses where guild_id=? and user_id=? and question=?",
+            guild_id,
+            user_id,
+            question
+        )
+        .execute(&mut tx)
+        .await?;
+        for answer in answers {
+            sqlx::query!(
+                "insert or ignore into survey_responses (guild_id, user_id, question, answer, answered_at) values (?, ?, ?, ?, ?)",
+                guild_id,
+                user_id,
+                question,
+                answer,
+                answered_at
+            )
+            .execute(&mut tx)
+            .await?;
+        }
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Adds an answer unless the member already gave it. Returns whether it was new.
+    pub async fn add_survey_answer(
+        &self,
+        guild_id: GuildId,
+        user_id: UserId,
+        question: &str,
+        answer: &str,
+        answered_at: i64,
+    ) -> Result<bool> {
+        let guild_id = guild_id.0 as i64;
+        let user_id = user_id.0 as i64;
+        let q = sqlx::query!(
+            "insert or ignore into survey_responses (guild_id, user_id, question, answer, answered_at) values (?, ?, ?, ?, ?)",
+            guild_id,
+            user_id,
+            question,
+            answer,
+            answered_at
+        )
+        .execute(&self.sqlitedb)
+        .await?;
+        Ok(q.rows_affected() > 0)
+    }
+
+    pub async fn get_survey_responses(
+        &self,
+        guild_id: GuildId,
+        question: Option<&str>,
+        from: i64,
+        to: i64,
+    ) -> Result<Vec<SurveyResponse>> {
+        let guild_id = guild_id.0 as i64;
+        let q = sqlx::query_as!(
+            SurveyResponse,
+            "select user_id, question, answer, answered_at from survey_responses
+            where guild_id=?1 and (?2 is null or question=?2) and answered_at>=?3 and answered_at<?4
+            order by answered_at",
+            guild_id,
+            question,
+            from,
+            to
+        )
+        .fetch_all(&self.sqlitedb)
+        .await?;
+        Ok(q)
+    }
+}
+
+fn to_csv(responses: &[SurveyResponse]) -> String {
+    let mut csv = String::from("user_id,question,answer,answered_at\n");
+    for r in responses {
+        let answered_at = Timestamp::from_unix_timestamp(r.answered_at)
+            .map(|x| x.to_string())
+            .unwrap_or_default();
+        writeln!(
+            csv,
+            "{},\"{}\",\"{}\",{}",
+            r.user_id,
+            r.question.replace('"', "\"\""),
+            r.answer.replace('"', "\"\""),
+            answered_at
+        )
+        .ok();
+    }
+    csv
+}
+
+/// `YYYY-MM-DD` to the unix timestamp of that day's start, in UTC.
+fn parse_date(date: &str) -> Option<i64> {
+    let date = NaiveDate::parse_from_str(date.trim(), "%Y-%m-%d").ok()?;
+    Some(
+        Utc.from_utc_datetime(&date.and_hms_opt(0, 0, 0)?)
+            .timestamp(),
+    )
+}
+
+/// Answer counts per question, with the labels the wizard shows for them.
+async fn results_text(ctx: &Context, guild_id: GuildId, responses: &[SurveyResponse]) -> String {
+    let wizard = onboarding::get_wizard(ctx, guild_id).await;
+    let mut per_question: BTreeMap<&str, (HashSet<i64>, BTreeMap<&str, i64>)> = BTreeMap::new();
+    for r in responses {
+        let (users, answers) = per_question.entry(&r.question).or_default();
+        users.insert(r.user_id);
+        *answers.entry(&r.answer).or_default() += 1;
+    }
+
+    let mut text = MessageBuilder::new();
+    for (question, (users, answers)) in per_question {
+        let step = wizard.steps.iter().find(|x| x.id == question);
+        text.push_bold_line(match step {
+            Some(step) => format!(
+                "{} (`{}`)",
+                tr(i18n::DEFAULT_LOCALE, &step.prompt),
+                question
+            ),
+            None => format!("`{}`", question),
+        });
+        let mut answers: Vec<_> = answers.into_iter().collect();
+        answers.sort_by_key(|x| std::cmp::Reverse(x.1));
+        for (answer, count) in answers {
+            let label = step
+                .and_then(|x| x.options.iter().find(|o| o.value == answer))
+                .map(|x| format!("{} (`{}`)", tr(i18n::DEFAULT_LOCALE, &x.label), answer))
+                .unwrap_or_else(|| format!("`{}`", answer));
+            text.push_quote_line(format!(
+                "{}: {} ({}%)",
+                label,
+                count,
+                count * 100 / users.len() as i64
+            ));
+        }
+        text.push_line(format!("{} respondents", users.len()))
+            .push_line("");
+    }
+    text.build()
+}
+
+/// Moves the answers kept as "Found: …" roles into the survey table, then deletes the roles.
+async fn migrate_roles(ctx: &Context, guild_id: GuildId) -> Result<String> {
+    let db = ctx.get_db().await;
+    let roles: Vec<_> = guild_id
+        .roles(&ctx.http)
+        .await?
+        .into_values()
+        .filter(|x| x.name.starts_with(LEGACY_ROLE_PREFIX))
+        .collect();
+    if roles.is_empty() {
+        return Ok("There are no \"Found: …\" roles left to migrate".to_string());
+    }
+
+    let now = Timestamp::now().unix_timestamp();
+    let mut imported = 0;
+    let mut members = guild_id.members_iter(&ctx).boxed();
+    while let Some(member) = members.next().await {
+        let member = member?;
+        // When they joined is the closest we have to when they answered
+        let answered_at = member.joined_at.map_or(now, |x| x.unix_timestamp());
+        for role in roles.iter().filter(|x| member.roles.contains(&x.id)) {
+            let answer = role.name.trim_start_matches(LEGACY_ROLE_PREFIX);
+            if db
+                .add_survey_answer(
+                    guild_id,
+                    member.user.id,
+                    LEGACY_QUESTION,
+                    answer,
+                    answered_at,
+                )
+                .await?
+            {
+                imported += 1;
+            }
+        }
+    }
+    for role in &roles {
+        guild_id.delete_role(&ctx.http, role.id).await?;
+    }
+
+    Ok(format!(
+        "Imported {} answers and deleted {} roles: {}",
+        imported,
+        roles.len(),
+        roles
+            .iter()
+            .map(|x| format!("`{}`", x.name))
+            .collect::<Vec<_>>()
+            .join(", ")
+    ))
+}
+
+pub async fn responder(mci: &ApplicationCommandInteraction, ctx: &Context) {
+    let guild_id = mci.guild_id.unwrap();
+    let subcommand = match mci.data.options.first() {
+        Some(x) => x,
+        None => return,
+    };
+
+    match subcommand.name.as_str() {
+        "results" => {
+            let mut question: Option<String> = None;
+            let mut from: Option<String> = None;
+            let mut to: Option<String> = None;
+            let mut export_csv = false;
+            for option in &subcommand.options {
+                match (option.name.as_str(), &option.resolved) {
+                    ("question", Some(CommandDataOptionValue::String(s))) => {
+                        question = Some(s.clone())
+                    }
+                    ("from", Some(CommandDataOptionValue::String(s))) => from = Some(s.clone()),
+                    ("to", Some(CommandDataOptionValue::String(s))) => to = Some(s.clone()),
+                    ("csv", Some(CommandDataOptionValue::Boolean(b))) => export_csv = *b,
+                    _ => {}
+                }
+            }
+
+            let range = (
+                from.as_deref().map_or(Some(0), parse_date),
+                // Inclusive, up to the end of that day
+                to.as_deref()
+                    .map_or(Some(i64::MAX), |x| parse_date(x).map(|x| x + 86400)),
+            );
+            let (from_ts, to_ts) = match range {
+                (Some(from_ts), Some(to_ts)) => (from_ts, to_ts),
+                _ => {
+                    mci.create_interaction_response(&ctx.http, |r| {
+                        r.kind(InteractionResponseType::ChannelMessageWithSource)
+                            .interaction_response_data(|d| {
+                                d.content("Dates must look like `2022-05-01`")
+                                    .flags(MessageFlags::EPHEMERAL)
+                            })
+                    })
+                    .await
+                    .unwrap();
+                    return;
+                }
+            };
+
+            let db = ctx.get_db().await;
+            let responses = db
+                .get_survey_responses(guild_id, question.as_deref(), from_ts, to_ts)
+                .await
+                .unwrap();
+            let title = format!(
+                "Survey results from {} to {}",
+                from.as_deref().unwrap_or("the start"),
+                to.as_deref().unwrap_or("today")
+            );
+            let text = results_text(ctx, guild_id, &responses).await;
+            let csv = to_csv(&responses);
+
+            mci.create_interaction_response(&ctx.http, |r| {
+                r.kind(InteractionResponseType::ChannelMessageWithSource)
+                    .interaction_response_data(|d| {
+                        if responses.is_empty() {
+                            d.content("No survey answers in that range");
+                        } else {
+                            d.embed(|e| {
+                                e.title(title).description(text.as_str().substring(0, 4096))
+                            });
+                            if export_csv {
+                                d.add_file((csv.as_bytes(), "survey.csv"));
+                            }
+                        }
+                        d.flags(MessageFlags::EPHEMERAL)
+                    })
+            })
+            .await
+            .unwrap();
+        }
+        "migrate-roles" => {
+            // Going through all the members can take a while
+            mci.create_interaction_response(&ctx.http, |r| {
+                r.kind(InteractionResponseType::DeferredChannelMessageWithSource)
+                    .interaction_response_data(|d| d.flags(MessageFlags::EPHEMERAL))
+            })
+            .await
+            .unwrap();
+            let text = match migrate_roles(ctx, guild_id).await {
+                Ok(text) => text,
+                Err(e) => format!("Migration failed: {}", e),
+            };
+            mci.edit_original_interaction_response(&ctx.http, |d| d.content(text))
+                .await
+                .unwrap();
+        }
+        _ => {}
+    }
+}
@@ -1,4 +1,3 @@
-use regex::Regex;
 use serenity::model::channel::MessageType;
 
 use super::*;
@@ -38,9 +37,14 @@ pub async fn responder(_ctx: Context, _thread: GuildChannel) {
 
     if _thread.thread_metadata.unwrap().archived && last_msg.is_own(&_ctx.cache) {
         if last_msg.kind.eq(&MessageType::GroupNameUpdate)
-            || Regex::new("^This [a-z]+ was closed ?b?y?")
-                .unwrap()
-                .is_match(last_msg.content.as_str())
+            || [
+                "question-closed",
+                "thread-closed",
+                "question-closed-by",
+                "thread-closed-by",
+            ]
+            .iter()
+            .any(|key| i18n::matches(key, &last_msg.content))
         {
         } else {
             unarchival_action(_ctx, _thread).await;
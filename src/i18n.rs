@@ -0,0 +1,136 @@
+use crate::db::ClientContextExt;
+use fluent_bundle::{concurrent::FluentBundle, FluentArgs, FluentResource};
+use regex::Regex;
+use serenity::{
+    client::Context,
+    model::id::{ChannelId, GuildId},
+};
+use std::{
+    collections::HashMap,
+    sync::{Mutex, OnceLock},
+};
+use unic_langid::LanguageIdentifier;
+
+pub const DEFAULT_LOCALE: &str = "en";
+
+// The message catalogues are embedded in the binary, register new locales here.
+const CATALOGUES: &[(&str, &str)] = &[
+    ("en", include_str!("../locales/en.ftl")),
+    ("de", include_str!("../locales/de.ftl")),
+];
+
+static BUNDLES: OnceLock<HashMap<&'static str, FluentBundle<FluentResource>>> = OnceLock::new();
+// The patterns `matches` compiled for a key, one per locale
+static MATCHERS: OnceLock<Mutex<HashMap<String, Vec<Regex>>>> = OnceLock::new();
+
+fn bundles() -> &'static HashMap<&'static str, FluentBundle<FluentResource>> {
+    BUNDLES.get_or_init(|| {
+        CATALOGUES
+            .iter()
+            .map(|(locale, source)| {
+                let langid: LanguageIdentifier = locale.parse().expect("Invalid locale name");
+                let resource = FluentResource::try_new(source.to_string())
+                    .unwrap_or_else(|_| panic!("Failed to parse the {} catalogue", locale));
+                let mut bundle = FluentBundle::new_concurrent(vec![langid]);
+                // Unicode isolation marks show up as garbage in Discord
+                bundle.set_use_isolating(false);
+                bundle
+                    .add_resource(resource)
+                    .unwrap_or_else(|_| panic!("Duplicate keys in the {} catalogue", locale));
+                (*locale, bundle)
+            })
+            .collect()
+    })
+}
+
+/// Picks the closest locale we have a catalogue for, e.g. `pt-BR` falls back to `pt`.
+pub fn resolve(locale: &str) -> Option<&'static str> {
+    let language = locale.split('-').next().unwrap_or(locale);
+    let mut available = bundles().keys().copied();
+    available
+        .clone()
+        .find(|x| x.eq_ignore_ascii_case(locale))
+        .or_else(|| available.find(|x| x.eq_ignore_ascii_case(language)))
+}
+
+pub fn available_locales() -> Vec<&'static str> {
+    let mut locales: Vec<_> = bundles().keys().copied().collect();
+    locales.sort_unstable();
+    locales
+}
+
+fn format(locale: &str, key: &str, args: Option<&FluentArgs>) -> Option<String> {
+    let bundle = bundles().get(locale)?;
+    let pattern = bundle.get_message(key)?.value()?;
+    let mut errors = vec![];
+    Some(
+        bundle
+            .format_pattern(pattern, args, &mut errors)
+            .into_owned(),
+    )
+}
+
+/// Looks up `key` in the catalogue of `locale`. Missing keys fall back to English,
+/// and then to the key itself.
+pub fn tr(locale: &str, key: &str) -> String {
+    format(locale, key, None)
+        .or_else(|| format(DEFAULT_LOCALE, key, None))
+        .unwrap_or_else(|| key.to_string())
+}
+
+/// Same as `tr`, with `{ $name }` placeables filled in from `args`.
+pub fn tr_args(locale: &str, key: &str, args: &[(&str, String)]) -> String {
+    let mut fluent_args = FluentArgs::new();
+    for (name, value) in args {
+        fluent_args.set(*name, value.clone());
+    }
+    format(locale, key, Some(&fluent_args))
+        .or_else(|| format(DEFAULT_LOCALE, key, Some(&fluent_args)))
+        .unwrap_or_else(|| key.to_string())
+}
+
+/// Whether `text` is the message `key` in any locale, with any values for its placeables.
+pub fn matches(key: &str, text: &str) -> bool {
+    let mut matchers = MATCHERS.get_or_init(Default::default).lock().unwrap();
+    let patterns = matchers.entry(key.to_string()).or_insert_with(|| {
+        let placeable = Regex::new(r"\\\{\\\$[a-zA-Z0-9_-]+\\\}").unwrap();
+        bundles()
+            .keys()
+            .filter_map(|locale| {
+                // Without args Fluent renders the placeables as `{$name}`
+                let pattern = regex::escape(&format(locale, key, None)?);
+                let pattern = placeable.replace_all(&pattern, ".+");
+                Regex::new(&format!("^{}$", pattern)).ok()
+            })
+            .collect()
+    });
+    patterns.iter().any(|re| re.is_match(text))
+}
+
+/// The locale configured for the guild through `gp config locale`, English by default.
+pub async fn guild_locale(ctx: &Context, guild_id: Option<GuildId>) -> &'static str {
+    if let Some(guild_id) = guild_id {
+        let db = ctx.get_db().await;
+        if let Ok(Some(locale)) = db.get_guild_config(guild_id, "locale").await {
+            if let Some(locale) = resolve(&locale) {
+                return locale;
+            }
+        }
+    }
+    DEFAULT_LOCALE
+}
+
+/// Same as `guild_locale`, for the guild the channel belongs to.
+pub async fn channel_locale(ctx: &Context, channel_id: ChannelId) -> &'static str {
+    let guild_id = ctx.cache.guild_channel(channel_id).map(|x| x.guild_id);
+    guild_locale(ctx, guild_id).await
+}
+
+/// The locale of the user's Discord client if we have a catalogue for it,
+/// otherwise the guild's.
+pub async fn user_locale(ctx: &Context, guild_id: Option<GuildId>, locale: &str) -> &'static str {
+    match resolve(locale) {
+        Some(locale) => locale,
+        None => guild_locale(ctx, guild_id).await,
+    }
+}
@@ -1,5 +1,6 @@
 mod command;
 mod event;
+mod i18n;
 mod utils;
 use command::*;
 mod db;
@@ -20,3 +20,59 @@ pub fn vowel_gen(sentense: &str) -> &str {
         "A"
     }
 }
+
+/// Renders a number of seconds as a short human readable duration, e.g. `2d 4h` or `13m`.
+pub fn format_duration(secs: i64) -> String {
+    let secs = secs.max(0);
+    let (days, hours, mins) = (secs / 86400, secs % 86400 / 3600, secs % 3600 / 60);
+    if days > 0 {
+        format!("{days}d {hours}h")
+    } else if hours > 0 {
+        format!("{hours}h {mins}m")
+    } else if mins > 0 {
+        format!("{mins}m")
+    } else {
+        format!("{secs}s")
+    }
+}
+
+pub const WEEK_SECS: i64 = 7 * 86400;
+// 1970-01-05 was the first Monday after the unix epoch
+const FIRST_MONDAY_SECS: i64 = 4 * 86400;
+
+/// Unix timestamp of the Monday 00:00 UTC that starts the week of `ts`.
+pub fn week_start(ts: i64) -> i64 {
+    ts - (ts - FIRST_MONDAY_SECS).rem_euclid(WEEK_SECS)
+}
+
+/// Parses a duration like `30m`, `12h`, `1d12h` or `2w` into seconds, a bare number is minutes.
+/// `None` when it is invalid or too large to fit.
+pub fn parse_duration(s: &str) -> Option<i64> {
+    let s = s.trim().to_lowercase();
+    if let Ok(mins) = s.parse::<i64>() {
+        return mins.checked_mul(60).filter(|x| *x > 0);
+    }
+    let mut total = 0;
+    let mut number = String::new();
+    for c in s.chars().filter(|x| !x.is_whitespace()) {
+        if c.is_ascii_digit() {
+            number.push(c);
+            continue;
+        }
+        let unit = match c {
+            's' => 1,
+            'm' => 60,
+            'h' => 3600,
+            'd' => 86400,
+            'w' => WEEK_SECS,
+            _ => return None,
+        };
+        total = number
+            .parse::<i64>()
+            .ok()?
+            .checked_mul(unit)
+            .and_then(|x| x.checked_add(total))?;
+        number.clear();
+    }
+    (number.is_empty() && total > 0).then_some(total)
+}
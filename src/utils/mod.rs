@@ -6,4 +6,5 @@ use tokio::fs;
 pub mod db;
 pub mod misc;
 pub mod parser;
+pub mod piston;
 pub mod substr;
@@ -1,36 +1,55 @@
+use crate::db::ClientContextExt;
 use regex::Regex;
-use serenity::{client::Context, framework::standard::Args, model::channel::Message};
+use serenity::{
+    client::Context,
+    framework::standard::Args,
+    model::{channel::Message, id::UserId},
+};
 
 pub struct Parse;
 
 impl Parse {
-    pub async fn user(_ctx: &Context, _message: &Message, _arguments: &Args) -> u64 {
-        if _arguments.rest().is_empty() {
-            *_message.author.id.as_u64()
-        } else {
-            let re = Regex::new(r#"\W"#).unwrap();
-            let pstr = &_arguments.rest().to_string();
-            let to_return = re.replace_all(pstr.as_str(), "");
+    /// Resolves the arguments to a user, trying in order: nothing (the author), a mention
+    /// or an id, a member's tag, name or nickname, a prefix of those, and names
+    /// recorded in the name history.
+    pub async fn user(_ctx: &Context, _message: &Message, _arguments: &Args) -> Option<UserId> {
+        let query = _arguments.rest().trim();
+        if query.is_empty() {
+            return Some(_message.author.id);
+        }
+
+        if let Some(caps) = Regex::new(r"^(?:<@!?)?([0-9]{17,20})>?$")
+            .unwrap()
+            .captures(query)
+        {
+            return caps[1].parse::<u64>().ok().map(UserId);
+        }
 
-            if Regex::new("[0-9]{18}+")
-                .unwrap()
-                .is_match(to_return.to_string().as_str())
+        let name = query.trim_start_matches('@');
+        if let Some(guild) = _message
+            .guild_id
+            .and_then(|x| x.to_guild_cached(&_ctx.cache))
+        {
+            if let Some(member) = guild.member_named(name) {
+                return Some(member.user.id);
+            }
+            let lowercase = name.to_lowercase();
+            if let Some(member) = guild.members.values().find(|x| {
+                x.user.name.to_lowercase() == lowercase
+                    || x.nick
+                        .as_ref()
+                        .is_some_and(|n| n.to_lowercase() == lowercase)
+            }) {
+                return Some(member.user.id);
+            }
+            if let Some((member, _)) = guild.members_starting_with(name, false, true).await.first()
             {
-                to_return.parse::<u64>().unwrap()
-            } else {
-                let userid_byname = _ctx
-                    .cache
-                    .guild(*_message.guild_id.unwrap().as_u64())
-                    .unwrap()
-                    .member_named(&to_return)
-                    .unwrap()
-                    .user
-                    .id;
-                *userid_byname.as_u64()
+                return Some(member.user.id);
             }
-
-            // _arguments.rest().to_string().replace("<@!", "").replace(">", "")
         }
+
+        let db = _ctx.get_db().await;
+        db.find_name_owner(name).await.ok().flatten()
     }
 
     // pub fn avatar(_user_data: &Member) -> String {
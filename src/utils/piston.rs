@@ -0,0 +1,154 @@
+use piston_rs::{Client, Executor, File, Runtime};
+use regex::Regex;
+use std::{
+    env,
+    sync::{Mutex, OnceLock},
+    time::{Duration, Instant},
+};
+
+// Piston's runtimes only change when packages are installed
+const RUNTIMES_TTL: Duration = Duration::from_secs(3600);
+/// What still fits in an embed description with the code fence around it.
+pub const MAX_EMBED_OUTPUT: usize = 4000;
+
+type RuntimeCache = Option<(Instant, Vec<Runtime>)>;
+static RUNTIMES: OnceLock<Mutex<RuntimeCache>> = OnceLock::new();
+
+// Fence tags people use that Piston doesn't know as aliases
+const EXTRA_ALIASES: &[(&str, &str)] = &[
+    ("golang", "go"),
+    ("shell", "bash"),
+    ("console", "bash"),
+    ("zsh", "bash"),
+    ("cxx", "c++"),
+    ("hpp", "c++"),
+    ("h", "c"),
+    ("kt", "kotlin"),
+    ("cs", "csharp"),
+    ("rb", "ruby"),
+    ("pl", "perl"),
+    ("ps1", "powershell"),
+];
+
+/// The configured Piston instance, the public emkc one unless `PISTON_URL` is set.
+pub fn client() -> Client {
+    let url = env::var("PISTON_URL").ok().filter(|x| !x.is_empty());
+    let key = env::var("PISTON_API_KEY").ok().filter(|x| !x.is_empty());
+    match (url, key) {
+        (Some(url), Some(key)) => Client::with_url_and_key(url.trim_end_matches('/'), &key),
+        (Some(url), None) => Client::with_url(url.trim_end_matches('/')),
+        (None, Some(key)) => Client::with_key(&key),
+        (None, None) => Client::new(),
+    }
+}
+
+fn env_ms(name: &str, default: isize) -> isize {
+    env::var(name)
+        .ok()
+        .and_then(|x| x.parse().ok())
+        .unwrap_or(default)
+}
+
+pub async fn runtimes() -> Result<Vec<Runtime>, String> {
+    let cache = RUNTIMES.get_or_init(Default::default);
+    if let Some((at, runtimes)) = cache.lock().unwrap().as_ref() {
+        if at.elapsed() < RUNTIMES_TTL {
+            return Ok(runtimes.clone());
+        }
+    }
+    let runtimes = client().fetch_runtimes().await.map_err(|e| e.to_string())?;
+    *cache.lock().unwrap() = Some((Instant::now(), runtimes.clone()));
+    Ok(runtimes)
+}
+
+/// The runtime for a code fence tag or file extension, the newest version when there are several.
+pub fn resolve<'a>(runtimes: &'a [Runtime], tag: &str) -> Option<&'a Runtime> {
+    let tag = tag.trim().to_lowercase();
+    let tag = EXTRA_ALIASES
+        .iter()
+        .find(|(alias, _)| *alias == tag)
+        .map_or(tag.as_str(), |(_, language)| language);
+    runtimes
+        .iter()
+        .filter(|x| x.language == tag || x.aliases.iter().any(|a| a == tag))
+        .max_by_key(|x| {
+            x.version
+                .split('.')
+                .map(|n| n.parse::<u32>().unwrap_or(0))
+                .collect::<Vec<_>>()
+        })
+}
+
+pub struct CodeBlock {
+    pub lang: String,
+    pub code: String,
+}
+
+/// Every fenced code block in the text, in order.
+pub fn code_blocks(text: &str) -> Vec<CodeBlock> {
+    Regex::new(r"```(?P<lang>[\w+#.-]*)[ \t]*\n(?s:(?P<code>.*?))```")
+        .unwrap()
+        .captures_iter(text)
+        .map(|caps| CodeBlock {
+            lang: caps["lang"].to_string(),
+            code: caps["code"].to_string(),
+        })
+        .collect()
+}
+
+pub struct Run {
+    pub language: String,
+    pub version: String,
+    pub output: String,
+    pub success: bool,
+}
+
+/// Runs the files, the first one being the entry point, on the runtime for `lang`.
+pub async fn run(
+    lang: &str,
+    files: Vec<File>,
+    stdin: &str,
+    args: &[String],
+) -> Result<Run, String> {
+    let runtimes = runtimes().await?;
+    let runtime = resolve(&runtimes, lang).ok_or_else(|| {
+        format!(
+            "`{}` isn't a known language, see `/runtimes` for the list",
+            lang
+        )
+    })?;
+    let executor = Executor::new()
+        .set_language(&runtime.language)
+        .set_version(&runtime.version)
+        .add_files(files)
+        .set_stdin(stdin)
+        .add_args(args.iter().map(|x| x.as_str()).collect())
+        .set_compile_timeout(env_ms("PISTON_COMPILE_TIMEOUT", 10000))
+        .set_run_timeout(env_ms("PISTON_RUN_TIMEOUT", 3000));
+    let response = client()
+        .execute(&executor)
+        .await
+        .map_err(|e| format!("Something went wrong: {}", e))?;
+
+    // A failed compile is all there is to show
+    if let Some(compile) = response.compile {
+        if compile.code != Some(0) {
+            return Ok(Run {
+                language: response.language,
+                version: response.version,
+                output: compile.output,
+                success: false,
+            });
+        }
+    }
+    let mut output = response.run.output;
+    if let Some(signal) = response.run.signal {
+        output.push_str(&format!("\n[killed by {}]", signal));
+    }
+    Ok(Run {
+        language: response.language,
+        version: response.version,
+        output,
+        success: response.run.code == Some(0),
+    })
+}